@@ -0,0 +1,274 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, Utc};
+
+use crate::zone::Zone;
+
+use std::ffi::OsStr;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// the directory where backups of the given data file are stored
+pub fn backup_dir(data_file: &Path) -> PathBuf {
+    data_file
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("backups")
+}
+
+fn backup_prefix(data_file: &Path) -> String {
+    let stem = data_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "timetracking".to_string());
+    format!("{}-", stem)
+}
+
+/// lists all backups of the given data file, oldest first
+pub fn list_backups(data_file: &Path) -> Result<Vec<PathBuf>> {
+    let dir = backup_dir(data_file);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let prefix = backup_prefix(data_file);
+    let mut backups = std::fs::read_dir(&dir)
+        .with_context(|| format!("could not read backup directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            path.is_file() && path.extension() == data_file.extension() && name.starts_with(&prefix)
+        })
+        .collect::<Vec<_>>();
+    // the timestamp in the name sorts chronologically
+    backups.sort();
+    Ok(backups)
+}
+
+/// removes the oldest backups, so that at most `keep` backups remain
+pub fn prune_backups(data_file: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    let backups = list_backups(data_file)?;
+    let excess = backups.len().saturating_sub(keep);
+    let removed = backups.into_iter().take(excess).collect::<Vec<_>>();
+    for backup in &removed {
+        std::fs::remove_file(backup)
+            .with_context(|| format!("could not remove backup {}", backup.display()))?;
+    }
+    Ok(removed)
+}
+
+/// copies the data file into the backup directory and prunes old backups.
+/// returns the path of the new backup, or `None` if there is no data file yet
+/// or no backups are kept.
+pub fn create_backup(data_file: &Path, keep: usize) -> Result<Option<PathBuf>> {
+    if keep == 0 || !data_file.exists() {
+        return Ok(None);
+    }
+    let dir = backup_dir(data_file);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("could not create backup directory {}", dir.display()))?;
+
+    let mut name = format!(
+        "{}{}",
+        backup_prefix(data_file),
+        // microseconds, so backups within the same second don't overwrite each other
+        Local::now().format("%Y%m%d-%H%M%S-%6f")
+    );
+    if let Some(extension) = data_file.extension() {
+        name = format!("{}.{}", name, extension.to_string_lossy());
+    }
+    let backup = dir.join(name);
+    std::fs::copy(data_file, &backup)
+        .with_context(|| format!("could not write backup {}", backup.display()))?;
+    prune_backups(data_file, keep)?;
+    Ok(Some(backup))
+}
+
+/// one line per backup with its name, the time it was created and its size, oldest first
+pub fn describe_backups(data_file: &Path, time_format: &str, zone: Zone) -> Result<Vec<String>> {
+    list_backups(data_file)?
+        .iter()
+        .map(|backup| {
+            let metadata = std::fs::metadata(backup)
+                .with_context(|| format!("could not read backup {}", backup.display()))?;
+            let created = DateTime::<Utc>::from(metadata.modified()?).with_timezone(&zone);
+            Ok(format!(
+                "{}  {}  {} bytes",
+                backup.file_name().unwrap_or_default().to_string_lossy(),
+                created.format(time_format),
+                metadata.len()
+            ))
+        })
+        .collect()
+}
+
+/// finds the backup of the given data file with the file name `name`
+pub fn find_backup(data_file: &Path, name: &str) -> Result<PathBuf> {
+    match list_backups(data_file)?
+        .into_iter()
+        .find(|backup| backup.file_name() == Some(OsStr::new(name)))
+    {
+        Some(backup) => Ok(backup),
+        None => bail!("no backup named \"{}\", see \"backup --list\"", name),
+    }
+}
+
+/// replaces the data file with the backup `name`, after backing up the current data file.
+/// returns false if the user declined.
+pub fn restore_backup(data_file: &Path, name: &str, yes: bool, keep: usize) -> Result<bool> {
+    let backup = find_backup(data_file, name)?;
+    // read it first, creating the new backup could prune the one to restore
+    let contents = std::fs::read(&backup)
+        .with_context(|| format!("could not read backup {}", backup.display()))?;
+    let action = format!("replace the data file with the backup {}", name);
+    if !prepare_destructive_write(data_file, &action, yes, keep)? {
+        return Ok(false);
+    }
+    std::fs::write(data_file, contents)
+        .with_context(|| format!("could not write {}", data_file.display()))?;
+    Ok(true)
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N]: ", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let answer = input.trim().to_lowercase();
+    Ok(answer == "y" || answer == "yes")
+}
+
+/// must be called before any write which rewrites existing history.
+/// asks for confirmation on interactive terminals, unless `yes` is set,
+/// and creates a backup of the data file. returns false if the user declined.
+pub fn prepare_destructive_write(
+    data_file: &Path,
+    action: &str,
+    yes: bool,
+    keep: usize,
+) -> Result<bool> {
+    if !yes
+        && atty::is(atty::Stream::Stdin)
+        && !confirm(&format!("This will {}. Continue?", action))?
+    {
+        eprintln!("Aborted.");
+        return Ok(false);
+    }
+    if let Some(backup) = create_backup(data_file, keep)? {
+        eprintln!("Created backup at {}", backup.display());
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    /// a data file in a directory which is removed with the returned guard
+    fn temp_data_file(name: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new(name);
+        let path = dir.join("timetracking.bin");
+        std::fs::write(&path, "data").unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_prune_backups() {
+        let (_dir, data_file) = temp_data_file("prune");
+        let dir = backup_dir(&data_file);
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 1..=5 {
+            std::fs::write(
+                dir.join(format!("timetracking-2021010{}-000000.bin", i)),
+                "old",
+            )
+            .unwrap();
+        }
+        std::fs::write(dir.join("unrelated.txt"), "").unwrap();
+
+        let removed = prune_backups(&data_file, 2).unwrap();
+
+        assert_eq!(3, removed.len());
+        assert_eq!(
+            vec![
+                dir.join("timetracking-20210104-000000.bin"),
+                dir.join("timetracking-20210105-000000.bin"),
+            ],
+            list_backups(&data_file).unwrap()
+        );
+        assert!(dir.join("unrelated.txt").exists());
+    }
+
+    #[test]
+    fn test_create_backup() {
+        let (_dir, data_file) = temp_data_file("create");
+        let backup = create_backup(&data_file, 10).unwrap().unwrap();
+
+        assert_eq!("data", std::fs::read_to_string(&backup).unwrap());
+        assert_eq!(vec![backup], list_backups(&data_file).unwrap());
+    }
+
+    #[test]
+    fn test_create_backup_twice() {
+        let (_dir, data_file) = temp_data_file("twice");
+        let first = create_backup(&data_file, 10).unwrap().unwrap();
+        let second = create_backup(&data_file, 10).unwrap().unwrap();
+
+        assert_eq!(vec![first, second], list_backups(&data_file).unwrap());
+    }
+
+    #[test]
+    fn test_create_backup_disabled() {
+        let (_dir, data_file) = temp_data_file("disabled");
+
+        assert_eq!(None, create_backup(&data_file, 0).unwrap());
+        assert!(!backup_dir(&data_file).exists());
+    }
+
+    #[test]
+    fn test_create_backup_rotation() {
+        let (_dir, data_file) = temp_data_file("rotation");
+        let dir = backup_dir(&data_file);
+        std::fs::create_dir_all(&dir).unwrap();
+        for i in 1..=3 {
+            std::fs::write(
+                dir.join(format!("timetracking-2021010{}-000000.bin", i)),
+                "old",
+            )
+            .unwrap();
+        }
+
+        let backup = create_backup(&data_file, 2).unwrap().unwrap();
+
+        assert_eq!(
+            vec![dir.join("timetracking-20210103-000000.bin"), backup],
+            list_backups(&data_file).unwrap()
+        );
+        assert_eq!(
+            2,
+            describe_backups(&data_file, "%Y", Zone::System)
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_restore_backup() {
+        let (_dir, data_file) = temp_data_file("restore");
+        let dir = backup_dir(&data_file);
+        std::fs::create_dir_all(&dir).unwrap();
+        let name = "timetracking-20210101-000000.bin";
+        std::fs::write(dir.join(name), "old").unwrap();
+
+        assert!(restore_backup(&data_file, "timetracking-missing.bin", true, 10).is_err());
+        // the restored backup is the oldest one, so it is pruned by the new backup
+        assert!(restore_backup(&data_file, name, true, 1).unwrap());
+
+        assert_eq!("old", std::fs::read_to_string(&data_file).unwrap());
+        let backups = list_backups(&data_file).unwrap();
+        assert_eq!(1, backups.len());
+        assert_eq!("data", std::fs::read_to_string(&backups[0]).unwrap());
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{bail, Context, Result};
+
+use std::path::{Path, PathBuf};
+
+/// the file name of the systemd user unit written by "install-hooks"
+pub const UNIT_NAME: &str = "tt-stop.service";
+
+/// whether the system was booted with systemd, checked like sd_booted(3) does
+pub fn has_systemd() -> bool {
+    Path::new("/run/systemd/system").is_dir()
+}
+
+/// quotes an argument of a systemd command line if it needs it. "%" starts a specifier in unit
+/// files, so it is doubled
+fn quote(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg
+    }
+}
+
+/// a systemd user unit which runs "tt stop --all" when the session of the user ends, e.g. on
+/// logout or shutdown. the unit has no start command, it only stays active until it is stopped
+pub fn systemd_unit(binary: &Path, data_file: Option<&str>) -> String {
+    let mut command = vec![quote(&binary.to_string_lossy())];
+    if let Some(data_file) = data_file {
+        command.push("--data-file".to_string());
+        command.push(quote(data_file));
+    }
+    command.extend(["stop".to_string(), "--all".to_string()]);
+    format!(
+        "[Unit]\n\
+         Description=Stop the time tracking of tt when the session ends\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         RemainAfterExit=yes\n\
+         ExecStop={}\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        command.join(" ")
+    )
+}
+
+/// the directory of the systemd user units, in $XDG_CONFIG_HOME or ~/.config
+pub fn unit_dir() -> Result<PathBuf> {
+    let config = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(config) if !config.is_empty() => config,
+        _ => shellexpand::full("~/.config")
+            .context("could not find the home directory")?
+            .to_string(),
+    };
+    Ok(Path::new(&config).join("systemd").join("user"))
+}
+
+/// writes the unit into the directory of the systemd user units and returns its path. fails
+/// with instructions for other setups if there is no systemd
+pub fn install_unit(unit: &str) -> Result<PathBuf> {
+    if !has_systemd() {
+        bail!(
+            "systemd was not found, so no hook was installed. Run \"tt stop --all\" from a \
+             script which your system runs on logout instead, e.g. ~/.bash_logout or \
+             ~/.zlogout"
+        );
+    }
+    let dir = unit_dir()?;
+    std::fs::create_dir_all(&dir).with_context(|| format!("could not create {}", dir.display()))?;
+    let path = dir.join(UNIT_NAME);
+    std::fs::write(&path, unit).with_context(|| format!("could not write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_unit() {
+        assert_eq!(
+            "[Unit]\n\
+             Description=Stop the time tracking of tt when the session ends\n\
+             \n\
+             [Service]\n\
+             Type=oneshot\n\
+             RemainAfterExit=yes\n\
+             ExecStop=/usr/local/bin/tt stop --all\n\
+             \n\
+             [Install]\n\
+             WantedBy=default.target\n",
+            systemd_unit(Path::new("/usr/local/bin/tt"), None)
+        );
+        assert!(systemd_unit(
+            Path::new("/opt/time tracking/tt"),
+            Some("/home/me/100%.data")
+        )
+        .contains(
+            "ExecStop=\"/opt/time tracking/tt\" --data-file /home/me/100%%.data stop --all\n"
+        ));
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!("tt", quote("tt"));
+        assert_eq!("\"\"", quote(""));
+        assert_eq!("\"a \\\"b\\\"\"", quote("a \"b\""));
+        assert_eq!("\"a\\\\ b\"", quote("a\\ b"));
+    }
+}
@@ -0,0 +1,44 @@
+/// locales which show clock times with AM and PM, by language and territory or by language only
+const TWELVE_HOUR_LOCALES: [&str; 14] = [
+    "en_US", "en_CA", "en_AU", "en_NZ", "en_PH", "en_IN", "es_US", "es_MX", "hi_IN", "ko_KR",
+    "zh_TW", "ar", "bn", "ur",
+];
+
+/// the locale of times as given by LC_ALL, LC_TIME or LANG, in the order in which they override
+/// each other. None for the POSIX locale, which has no preference
+pub fn from_env() -> Option<String> {
+    ["LC_ALL", "LC_TIME", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|locale| !locale.is_empty())
+        .filter(|locale| locale != "C" && locale != "POSIX" && !locale.starts_with("C."))
+}
+
+/// whether the locale, e.g. "en_US.UTF-8", shows clock times with AM and PM
+pub fn uses_12_hours(locale: &str) -> bool {
+    let name = locale.split(&['.', '@'][..]).next().unwrap_or_default();
+    let language = name.split('_').next().unwrap_or_default();
+    TWELVE_HOUR_LOCALES.contains(&name) || TWELVE_HOUR_LOCALES.contains(&language)
+}
+
+/// the strftime format of a clock time in the style of the locale. 24 hours if there is no locale
+pub fn clock_format(locale: Option<&str>) -> &'static str {
+    match locale {
+        Some(locale) if uses_12_hours(locale) => "%I:%M:%S %p",
+        _ => "%H:%M:%S",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_format() {
+        assert_eq!("%I:%M:%S %p", clock_format(Some("en_US.UTF-8")));
+        assert_eq!("%I:%M:%S %p", clock_format(Some("ar_EG")));
+        assert_eq!("%H:%M:%S", clock_format(Some("en_GB.UTF-8")));
+        assert_eq!("%H:%M:%S", clock_format(Some("de_DE@euro")));
+        assert_eq!("%H:%M:%S", clock_format(None));
+    }
+}
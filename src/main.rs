@@ -1,51 +1,357 @@
 use anyhow::{Context, Result};
-use chrono::{prelude::*, serde::ts_seconds, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{
+    format::{Item, StrftimeItems},
+    prelude::*,
+    serde::ts_seconds,
+    Duration, IsoWeek, NaiveDate, NaiveDateTime, NaiveTime,
+};
+use chrono_tz::Tz;
 use iif::iif;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{fs::File, io::{self, Write}};
 use std::path::{Path, PathBuf};
+use std::{collections::BTreeMap, str::FromStr};
 use structopt::StructOpt;
 
+mod backup;
+mod hooks;
+mod locale;
+mod pager;
 mod settings;
+mod suspend;
+#[cfg(test)]
+mod temp_dir;
+#[cfg(any(feature = "tui", test))]
+mod tui;
+mod zone;
 
-use settings::Settings;
+use settings::{LineEnding, RoundingMode, SameSecondEvents, Settings, StreakFreeDays};
+use zone::Zone;
 
 #[derive(Debug, StructOpt)]
 struct Options {
     #[cfg(feature = "binary")]
-    /// which data file to use. [default: ~/timetracking.bin]
-    #[structopt(short, long)]
-    data_file: Option<PathBuf>,
+    /// which data file to use. commands which only read the entries, like show, summary or export,
+    /// accept several files or a glob like "~/tt/*.bin" and combine their entries. [default:
+    /// ~/timetracking.bin]
+    #[structopt(short, long, number_of_values = 1)]
+    data_file: Vec<PathBuf>,
 
     #[cfg(not(feature = "binary"))]
-    /// which data file to use. [default: ~/timetracking.json]
-    #[structopt(short, long)]
-    data_file: Option<PathBuf>,
+    /// which data file to use. commands which only read the entries, like show, summary or export,
+    /// accept several files or a glob like "~/tt/*.json" and combine their entries. [default:
+    /// ~/timetracking.json]
+    #[structopt(short, long, number_of_values = 1)]
+    data_file: Vec<PathBuf>,
 
-    /// which config file to use.
+    /// which config file to use. it is applied on top of the global, project and local config
+    /// files
     #[structopt(short, long)]
     config_file: Option<String>,
 
+    /// use only this config file on top of the defaults, without looking for the global, project
+    /// and local config files
+    #[structopt(long, env = "TT_CONFIG", conflicts_with = "config-file")]
+    config: Option<String>,
+
+    /// don't ask for confirmation before rewriting existing entries
+    #[structopt(short, long, alias = "force")]
+    yes: bool,
+
+    /// stop sessions which are running for longer than fill_gaps_after hours
+    /// after typical_day_length and save the result
+    #[structopt(long)]
+    fill_gaps: bool,
+
+    /// show the changes of a command instead of writing them to the data file
+    #[structopt(long)]
+    dry_run: bool,
+
+    /// don't confirm what start, stop and continue recorded
+    #[structopt(short, long)]
+    quiet: bool,
+
+    /// print the output of list and summary directly instead of using $PAGER
+    #[structopt(long)]
+    no_pager: bool,
+
+    /// don't color the output. colors are also disabled if NO_COLOR is set or stdout is no
+    /// terminal
+    #[structopt(long)]
+    no_color: bool,
+
+    /// keep the permissions of the data file. by default it is made readable and writable only by
+    /// its owner on unix
+    #[structopt(long)]
+    no_chmod: bool,
+
+    /// the IANA timezone used to display times and group entries by day, e.g. "Europe/Vienna".
+    /// [default: the timezone of the system]
+    #[structopt(long, parse(try_from_str = parse_timezone))]
+    tz: Option<Tz>,
+
+    /// show the clock times of list and status with 12 or 24 hours as usual in the locale given
+    /// by LC_ALL, LC_TIME or LANG, instead of list_time_format. with 24 hours if there is no
+    /// locale. only the output changes, times are parsed as before
+    #[structopt(long)]
+    locale_time: bool,
+
+    /// the decimal places of hours, e.g. of "show --preset decimal", "show --fte", the summary
+    /// tables and the csv export. they are rounded as set by rounding_mode
+    #[structopt(long, default_value = "2")]
+    precision: usize,
+
     #[structopt(subcommand)]
     command: Option<Command>,
 }
 
-#[derive(Default, Debug, StructOpt)]
+#[derive(Clone, Default, Debug, StructOpt)]
 struct FilterData {
     /// show all entries after this point in time [defaults to current day 00:00:00]
-    /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S"
+    /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S", RFC 3339 and
+    /// "@<unix timestamp>"
     #[structopt(short, long)]
     from: Option<String>,
 
     /// show all entries before this point in time [defaults to start day 23:59:59]
-    /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S"
+    /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S", RFC 3339 and
+    /// "@<unix timestamp>"
     #[structopt(short, long)]
     to: Option<String>,
 
+    /// also include entries without a description when filtering by part of the description
+    #[structopt(long)]
+    include_unlabeled: bool,
+
+    /// leave out sessions with a description containing this text. can be given multiple times
+    #[structopt(long, number_of_values = 1)]
+    exclude: Vec<String>,
+
+    /// only include sessions of this project
+    #[structopt(long)]
+    project: Option<String>,
+
+    /// only include sessions with this tag, with or without a leading "#". tags given with
+    /// "start --tag" are used, or else the words of the description starting with "#". can be
+    /// given multiple times, then the sessions need all of them
+    #[structopt(long, number_of_values = 1)]
+    tag: Vec<String>,
+
     /// filter entries. possible filter values: "week", "all" or part of the description
     filter: Option<String>,
 }
 
+#[derive(Default, Debug, StructOpt)]
+struct AssumeStopped {
+    /// treat a trailing running session as stopped, for this command only: at its start
+    /// ("start", the default), at the end of its day ("eod") or at the given time
+    #[structopt(long, require_equals = true)]
+    assume_stopped: Option<Option<AssumedStop>>,
+}
+
+#[derive(Default, Debug, StructOpt)]
+struct BillableFilter {
+    /// leave out the sessions tagged with nonbillable_tag, and those without billable_tag if it
+    /// is set
+    #[structopt(long, conflicts_with = "nonbillable-only")]
+    billable_only: bool,
+
+    /// use only the sessions which --billable-only leaves out
+    #[structopt(long)]
+    nonbillable_only: bool,
+}
+
+#[derive(Default, Debug, StructOpt)]
+struct SortOptions {
+    /// the order of the descriptions: by the total duration (longest first), by the time they
+    /// were first worked on in the range (earliest first) or alphabetically by description
+    #[structopt(
+        long,
+        default_value = "duration",
+        possible_values = &["duration", "time", "description"]
+    )]
+    sort: SummarySort,
+
+    /// reverse the order of the descriptions
+    #[structopt(long)]
+    reverse: bool,
+}
+
+#[derive(Default, Debug, StructOpt)]
+struct ShowOptions {
+    #[structopt(flatten)]
+    filter: FilterData,
+
+    #[structopt(flatten)]
+    assume_stopped: AssumeStopped,
+
+    #[structopt(flatten)]
+    billable: BillableFilter,
+
+    /// leave out the sessions which start on a day without a daily goal: the days after
+    /// last_day_of_work_week and holidays. averages are then per work day
+    #[structopt(long)]
+    workdays_only: bool,
+
+    /// show only the time with no additional text
+    #[structopt(short, long)]
+    plain: bool,
+
+    /// show time until the defined time goals are met.
+    #[structopt(short, long)]
+    remaining: bool,
+
+    /// use this goal as "HH:MM" for the day or week instead of the configured goals, e.g. "06:00"
+    /// when leaving early
+    #[structopt(long, parse(try_from_str = parse_target), requires = "remaining")]
+    target: Option<Duration>,
+
+    /// count --remaining towards this goal with the work time of its period, regardless of the
+    /// filter: "daily" for today, "weekly" for this week and "monthly" for this month, whose goal
+    /// is the sum of the daily goals of its days. without it, the filter decides and the remaining
+    /// time of the day is limited by the weekly goal
+    #[structopt(
+        long,
+        possible_values = &["daily", "weekly", "monthly"],
+        requires = "remaining",
+        conflicts_with_all = &["from", "to", "target"]
+    )]
+    goal: Option<GoalPeriod>,
+
+    /// round the remaining time to the nearest multiple of this many minutes, e.g. 5 to show
+    /// 1h35m instead of 1h37m. it is never rounded across zero, so remaining time doesn't turn
+    /// into overtime
+    #[structopt(long, parse(try_from_str = parse_round_remaining), requires = "remaining")]
+    round_remaining: Option<i64>,
+
+    /// include seconds in time calculation. without it, durations are cut off at the minute,
+    /// never rounded up
+    #[structopt(short)]
+    include_seconds: bool,
+
+    /// show only the time with no additional text. [default: "{hh}:{mm}:{ss}"]
+    /// available placeholders: {hh}, {mm}, {ss}, {h}, {m}, {s}, {pct} and {goal}
+    #[structopt(long)]
+    format: Option<String>,
+
+    /// use a predefined format: clock ("{hh}:{mm}:{ss}"), compact ("{h}h{mm}"),
+    /// decimal (hours with two decimals) or words ("X hours Y minutes")
+    #[structopt(
+        long,
+        possible_values = &["clock", "compact", "decimal", "words"],
+        conflicts_with = "format"
+    )]
+    preset: Option<Preset>,
+
+    /// show the period with the most work time instead of the total
+    #[structopt(
+        long,
+        possible_values = &["hour", "weekday", "day"],
+        conflicts_with = "remaining"
+    )]
+    busiest: Option<Busiest>,
+
+    /// show one total per week for the last N weeks, ending with the current week
+    #[structopt(long, conflicts_with_all = &["from", "to", "remaining", "busiest"])]
+    weeks: Option<u32>,
+
+    /// show the work time of the trailing window of this length, ending now, e.g. "7d" or "12h"
+    #[structopt(
+        long,
+        parse(try_from_str = parse_window),
+        conflicts_with_all = &["from", "to", "remaining", "weeks"]
+    )]
+    rolling: Option<Duration>,
+
+    /// show one total per range "<from>..<to>", with the formats of "from" and "to", e.g.
+    /// "2024-03-01..2024-03-07". can be given multiple times to compare periods
+    #[structopt(
+        long,
+        number_of_values = 1,
+        parse(try_from_str = parse_show_range),
+        conflicts_with_all = &["from", "to", "remaining", "busiest", "weeks", "rolling"]
+    )]
+    range: Vec<ShowRange>,
+
+    /// show the work time as full-time equivalent: the ratio to fte_day_length times the days in
+    /// the range which have a daily goal
+    #[structopt(
+        long,
+        conflicts_with_all = &["remaining", "busiest", "weeks", "rolling", "range", "format", "preset"]
+    )]
+    fte: bool,
+
+    /// show the work time per day: divided by the days with tracked time ("active", the default)
+    /// or by all days of the range ("calendar")
+    #[structopt(
+        long,
+        require_equals = true,
+        possible_values = &["active", "calendar"],
+        conflicts_with_all = &["remaining", "busiest", "weeks", "range", "fte"]
+    )]
+    average: Option<Option<AverageOver>>,
+}
+
+#[derive(Clone, Debug, StructOpt)]
+struct ExportOptions {
+    /// export only the matching entries. Exports everything if neither a filter nor "from" or
+    /// "to" is given. Sessions in the org and csv formats are clipped to the range
+    #[structopt(flatten)]
+    filter: FilterData,
+
+    /// the format of the output file. json, csv and readable can be imported. heatmap writes the worked
+    /// minutes of every day in the range
+    #[structopt(long, possible_values = &["json", "readable", "org", "csv", "heatmap"])]
+    format: Option<ExportFormat>,
+
+    /// nest the clock entries under a headline per description (org format only)
+    #[structopt(long)]
+    headlines: bool,
+
+    /// the character which separates the fields (csv format only)
+    #[structopt(long, default_value = ",")]
+    csv_delimiter: char,
+
+    /// use a comma as decimal separator (csv format only)
+    #[structopt(long)]
+    decimal_comma: bool,
+
+    /// write the sessions with this template instead of a built-in format. the part between
+    /// {{#each sessions}} and {{/each}} is repeated for every session with {{start}}, {{stop}},
+    /// {{duration}}, {{minutes}} and {{description}}. the text around it can use {{total}} and
+    /// {{count}}. other placeholders are an error
+    #[structopt(long, conflicts_with = "format")]
+    template: Option<PathBuf>,
+
+    /// write one file per day, week or month into the directory given as path, e.g.
+    /// "2024-03-05.json", "2024-W10.json" or "2024-03.json". sessions crossing a boundary are
+    /// clipped to each file
+    #[structopt(
+        long,
+        possible_values = &["day", "week", "month"],
+        conflicts_with = "template"
+    )]
+    split_by: Option<SplitBy>,
+
+    /// merge sessions with the same description which are less than this apart into one, e.g.
+    /// "1m". only the export is merged, the data file stays as it is
+    #[structopt(long, parse(try_from_str = parse_window))]
+    merge_adjacent: Option<Duration>,
+
+    /// round the start of every session down and its stop up to a multiple of this many minutes
+    /// since midnight, e.g. 5, before the durations are computed. this can increase the total.
+    /// only the export is rounded, the data file stays as it is
+    #[structopt(long, parse(try_from_str = parse_boundaries))]
+    round_boundaries: Option<u32>,
+
+    /// replace the parts of descriptions which match this regular expression with "***", e.g.
+    /// "(?i)acme" to hide a client. can be given several times. only the export is redacted, the
+    /// data file stays as it is
+    #[structopt(long, number_of_values = 1, parse(try_from_str = parse_regex))]
+    redact: Vec<Regex>,
+}
+
 #[derive(Debug, StructOpt)]
 enum Command {
     // keep this at the top, otherwise rust analyzer will underline the whole struct until this
@@ -56,11 +362,36 @@ enum Command {
     Export {
         /// where to write the output file
         path: PathBuf,
+
+        #[structopt(flatten)]
+        options: ExportOptions,
     },
 
     /// show info from the latest entry. Returns the exit code 0, if the time tracking is currently
-    /// active and -1 if not.
-    Status,
+    /// active and 3 if not.
+    Status {
+        /// print a single line using prompt_format while active and idle_format while stopped.
+        /// Always returns the exit code 0
+        #[structopt(short, long)]
+        short: bool,
+
+        /// wait until the time tracking is active, reading the data file every --interval. exits
+        /// with 0 once it is active, without printing anything
+        #[structopt(long, conflicts_with_all = &["short", "wait-inactive"])]
+        wait_active: bool,
+
+        /// wait until the time tracking is not active, like --wait-active
+        #[structopt(long, conflicts_with = "short")]
+        wait_inactive: bool,
+
+        /// stop waiting after this time and exit with an error, e.g. "30m" [default: wait forever]
+        #[structopt(long, parse(try_from_str = parse_window))]
+        timeout: Option<Duration>,
+
+        /// how often the data file is read while waiting, e.g. "5s"
+        #[structopt(long, default_value = "1s", parse(try_from_str = parse_window))]
+        interval: Duration,
+    },
 
     /// starts an interactive cleanup session
     Cleanup,
@@ -71,9 +402,35 @@ enum Command {
         description: Option<String>,
 
         /// the time at which the event happend.
-        /// format: "HH:MM:SS" or "YY-mm-dd HH:MM:SS" [defaults to current time]
+        /// format: "HH:MM:SS", "YY-mm-dd HH:MM:SS", RFC 3339 or "@<unix timestamp>"
+        /// [defaults to current time]
         #[structopt(short, long)]
         at: Option<String>,
+
+        /// compose the description in $VISUAL or $EDITOR
+        #[structopt(long, conflicts_with = "description")]
+        edit: bool,
+
+        /// the description, as an alternative to the positional argument. "-" reads it from stdin
+        #[structopt(short, long, conflicts_with = "edit")]
+        message: Option<String>,
+
+        /// keep an empty or whitespace-only description instead of removing it
+        #[structopt(long)]
+        keep_empty_description: bool,
+
+        /// do nothing if a session with the same description is already running, instead of
+        /// splitting it. [default: resume_if_same from settings]
+        #[structopt(long)]
+        resume_if_same: bool,
+
+        /// the project of the session
+        #[structopt(long)]
+        project: Option<String>,
+
+        /// a tag of the session, with or without a leading "#". can be given multiple times
+        #[structopt(long, number_of_values = 1)]
+        tag: Vec<String>,
     },
 
     /// stop time tracking
@@ -82,49 +439,283 @@ enum Command {
         description: Option<String>,
 
         /// the time at which the event happend.
-        /// format: "HH:MM:SS" or "YY-mm-dd HH:MM:SS" [defaults to current time]
+        /// format: "HH:MM:SS", "YY-mm-dd HH:MM:SS", RFC 3339 or "@<unix timestamp>"
+        /// [defaults to current time]
         #[structopt(short, long)]
         at: Option<String>,
+
+        /// compose the description in $VISUAL or $EDITOR
+        #[structopt(long, conflicts_with = "description")]
+        edit: bool,
+
+        /// the description, as an alternative to the positional argument. "-" reads it from stdin
+        #[structopt(short, long, conflicts_with = "edit")]
+        message: Option<String>,
+
+        /// keep an empty or whitespace-only description instead of removing it
+        #[structopt(long)]
+        keep_empty_description: bool,
+
+        /// stop every running session at the current time. does nothing if nothing is running
+        #[structopt(long, conflicts_with_all = &["at", "edit"])]
+        all: bool,
     },
 
     /// continue time tracking with last description
-    Continue,
+    Continue {
+        /// the time at which the work was resumed. must be after the last stop and not in the
+        /// future. format: "HH:MM:SS", "YY-mm-dd HH:MM:SS", RFC 3339 or "@<unix timestamp>"
+        /// [defaults to current time]
+        #[structopt(short, long)]
+        at: Option<String>,
+
+        /// start a new session with this description instead of the last one. like start, but
+        /// only if the time tracking is stopped
+        #[structopt(long)]
+        new: Option<String>,
+    },
 
     /// list all entries
     List {
         #[structopt(flatten)]
         filter: FilterData,
+
+        /// how to display the time of each entry as chrono strftime format.
+        /// [default: list_time_format from settings]
+        #[structopt(long, parse(try_from_str = parse_time_format))]
+        time_format: Option<String>,
+
+        /// print the entries as a JSON array of objects with kind, time (RFC 3339) and description
+        #[structopt(long, conflicts_with = "time-format")]
+        json: bool,
+
+        /// print the times in the JSON as seconds since the unix epoch
+        #[structopt(long, requires = "json")]
+        epoch: bool,
+
+        /// list only start or stop entries, or one line per session with its duration.
+        /// sessions can't be printed as JSON
+        #[structopt(long, possible_values = &["starts", "stops", "sessions"])]
+        only: Option<ListOnly>,
+
+        /// list the newest entries first
+        #[structopt(long)]
+        reverse: bool,
+
+        /// list only this page of the entries, counted from 1, followed by a line like
+        /// "page 2/7 (312 entries)". pages after the last one are empty. [default: 1 with
+        /// --per-page]
+        #[structopt(long, parse(try_from_str = parse_page))]
+        page: Option<usize>,
+
+        /// the number of entries per page [default: 50 with --page]
+        #[structopt(long, parse(try_from_str = parse_page))]
+        per_page: Option<usize>,
     },
 
     /// show path to data file
     Path,
 
     /// show work time for given timespan
-    Show {
+    Show(ShowOptions),
+
+    /// show a day as a bar from 00:00 to 24:00 with a character per time slice. a slice shows the
+    /// description which was tracked longest in it, if that is at least half of the slice
+    Timeline {
+        /// the day to show, e.g. "2024-03-05" [default: today]
+        date: Option<String>,
+
+        /// the minutes per character. must divide an hour evenly [default: the shortest of 5, 10,
+        /// 15, 20, 30 and 60 minutes which fits the width of the terminal, or else of $COLUMNS]
+        #[structopt(long)]
+        slice: Option<u32>,
+    },
+
+    /// show the entries which are only in the data file ("-") or only in another data file ("+")
+    Diff {
+        /// the data file to compare with. entries are the same if they have the same kind, time and
+        /// description. "tt import --mode merge" adds the entries of the other file once exported
+        other: PathBuf,
+    },
+
+    /// send the goal status of today and this week as JSON to notify.url
+    Notify {
+        /// print the JSON instead of sending it
+        #[structopt(long)]
+        stdout: bool,
+    },
+
+    /// change existing entries
+    Edit {
         #[structopt(flatten)]
         filter: FilterData,
 
-        /// show only the time with no additional text
+        /// move all entries in the range by this amount of time, e.g. "+1h", "-30m" or "1h30m"
+        #[structopt(long, parse(try_from_str = parse_shift), allow_hyphen_values = true)]
+        shift: Duration,
+    },
+
+    /// delete all entries in a range, e.g. a day which was tracked wrongly. fails if a session
+    /// would be cut in half
+    Delete {
+        /// delete all entries after this point in time. allowed formats are: "%Y-%m-%d %H:%M:%S",
+        /// "%Y-%m-%d", "%H:%M:%S", RFC 3339 and "@<unix timestamp>"
         #[structopt(short, long)]
-        plain: bool,
+        from: String,
 
-        /// show time until the defined time goals are met.
+        /// delete all entries before this point in time [defaults to start day 23:59:59]
+        /// allowed formats are: "%Y-%m-%d %H:%M:%S", "%Y-%m-%d", "%H:%M:%S", RFC 3339 and
+        /// "@<unix timestamp>"
         #[structopt(short, long)]
-        remaining: bool,
+        to: Option<String>,
+    },
+
+    /// copy the data file into the backups directory, or list and restore backups
+    Backup {
+        /// list the available backups with their dates and sizes
+        #[structopt(long, conflicts_with = "restore")]
+        list: bool,
+
+        /// replace the data file with this backup, after backing up the current data file
+        #[structopt(long)]
+        restore: Option<String>,
+    },
+
+    /// manage config files
+    Config {
+        #[structopt(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// write a systemd user unit which runs "tt stop --all" when the session ends, e.g. on logout
+    /// or shutdown, and print how to enable it. with --dry-run the unit is only printed
+    InstallHooks {
+        /// the tt binary which the unit runs [default: this binary]
+        #[structopt(long)]
+        binary: Option<PathBuf>,
+    },
+
+    /// move the last entry back by the given amount of time, e.g. "5m"
+    Amend {
+        /// how much earlier the last entry happened, e.g. "5m" or "1h30m"
+        #[structopt(parse(try_from_str = parse_window))]
+        minus: Duration,
+    },
+
+    /// remove the last stop, so that the session it ended is running again
+    Reopen,
 
-        /// include seconds in time calculation
-        #[structopt(short)]
-        include_seconds: bool,
+    /// browse the sessions by day and change or delete them
+    #[cfg(feature = "tui")]
+    Tui,
 
-        /// show only the time with no additional text. [default: "{hh}:{mm}:{ss}"]
+    /// show statistics over all entries
+    Stats {
+        /// show the current and the longest streak of days which met the daily goal
         #[structopt(long)]
-        format: Option<String>,
+        streak: bool,
+
+        /// list the untracked periods within the work hours of the gaps setting on the days in the
+        /// range, which are longer than gaps.min_minutes
+        #[structopt(long, conflicts_with = "streak")]
+        gaps: bool,
+
+        /// show how often the work switched between descriptions: the sessions, their average
+        /// length, the switches between adjacent sessions of a day with different descriptions and
+        /// the distinct descriptions per day
+        #[structopt(long, conflicts_with_all = &["streak", "gaps"])]
+        focus: bool,
+
+        /// show the work time per hour of the day, summed over all days. sessions are split at
+        /// the full hours
+        #[structopt(long, conflicts_with_all = &["streak", "gaps", "focus"])]
+        by_hour_of_day: bool,
+
+        /// show the work time per hour of the day as a bar chart
+        #[structopt(long, requires = "by-hour-of-day")]
+        chart: bool,
+
+        /// leave out the sessions which start on a day without a daily goal: the days after
+        /// last_day_of_work_week and holidays. averages are then per work day
+        #[structopt(long, conflicts_with_all = &["streak", "gaps"])]
+        workdays_only: bool,
+
+        /// limit the statistics to the matching entries. --gaps uses only the range and defaults
+        /// to the current day, the other statistics default to all entries
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        #[structopt(flatten)]
+        assume_stopped: AssumeStopped,
+    },
+
+    /// show the work time per description
+    Summary {
+        #[structopt(flatten)]
+        filter: FilterData,
+
+        #[structopt(flatten)]
+        assume_stopped: AssumeStopped,
+
+        #[structopt(flatten)]
+        billable: BillableFilter,
+
+        #[structopt(flatten)]
+        order: SortOptions,
+
+        /// merge sessions with the same description which are less than this apart into one, e.g.
+        /// "1m". the gaps between them count as work time. the data file stays as it is
+        #[structopt(long, parse(try_from_str = parse_window))]
+        merge_adjacent: Option<Duration>,
+
+        /// merge descriptions with less than this percentage of the total time into "Other"
+        #[structopt(long)]
+        min_percent: Option<f64>,
+
+        /// group the summary and the matrix by description, by project or by tag. a session with
+        /// several tags counts for each of them
+        #[structopt(
+            long,
+            default_value = "description",
+            possible_values = &["description", "project", "tag"],
+            conflicts_with = "goals"
+        )]
+        group_by: GroupBy,
+
+        /// show a table of the hours per description and ISO week, with totals
+        #[structopt(long, conflicts_with = "min-percent")]
+        matrix: bool,
+
+        /// show a table of the daily goal, the work time and their difference per day, with totals
+        #[structopt(long, conflicts_with_all = &["min-percent", "matrix"])]
+        goals: bool,
+
+        /// leave out days without a daily goal and without work time in the goals table
+        #[structopt(long, requires = "goals")]
+        skip_empty: bool,
+
+        /// the format of the summary, the matrix and the goals table. the csv and markdown
+        /// summary has the columns description, seconds, hours and percent
+        #[structopt(
+            long,
+            default_value = "text",
+            possible_values = &["text", "csv", "markdown"]
+        )]
+        format: TableFormat,
+
+        /// the character which separates the fields (csv format only)
+        #[structopt(long, default_value = ",")]
+        csv_delimiter: char,
+
+        /// use a comma as decimal separator (csv format only)
+        #[structopt(long)]
+        decimal_comma: bool,
     },
     #[cfg(feature = "binary")]
     /// export data to file
     Export {
-        /// export in a human readable format. This format is for human reading only and cannot be
-        /// imported
+        /// export in a human readable format. It can be imported again as long as list_time_format
+        /// includes the date
         #[structopt(short, long)]
         readable: bool,
         /// pretty print json
@@ -132,879 +723,8846 @@ enum Command {
         pretty: bool,
         /// where to write the output file
         path: PathBuf,
+        #[structopt(flatten)]
+        options: ExportOptions,
     },
     #[cfg(feature = "binary")]
-    /// import data from json file
+    /// import data from a json, ndjson, csv or readable file
     Import {
         /// which file to import
         path: PathBuf,
-    },
-}
 
-impl Default for Command {
-    fn default() -> Self {
-        Self::Show {
-            filter: FilterData::default(),
-            format: None,
-            include_seconds: false,
-            plain: false,
-            remaining: false,
-        }
-    }
-}
+        /// the format of the file. detected from its first line if not given: "[" or an object
+        /// with "events" is json, one object per line ndjson, a header with "start" csv and
+        /// "Start at" or "Stop  at" readable. readable times are read with list_time_format
+        #[structopt(long, possible_values = &["json", "ndjson", "csv", "readable"])]
+        format: Option<ImportFormat>,
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-struct TrackingData {
-    description: Option<String>,
+        /// replace all existing entries after a confirmation, or merge the imported entries into
+        /// them, skipping duplicates
+        #[structopt(long, default_value = "replace", possible_values = &["replace", "merge"])]
+        mode: ImportMode,
 
-    #[serde(with = "ts_seconds")]
-    time: DateTime<Utc>,
+        /// import the valid entries and report the invalid ones at the end, instead of stopping at
+        /// the first invalid entry. exits with an error if any entry was invalid
+        #[structopt(long)]
+        continue_on_error: bool,
+    },
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-enum TrackingEvent {
-    Start(TrackingData),
-    Stop(TrackingData),
+#[derive(Debug, StructOpt)]
+enum ConfigCommand {
+    /// update a config file to the current version of the settings format. comments in the file
+    /// are not kept
+    Migrate {
+        /// which config file to update [default: ~/.config/timetracking/config.toml]
+        path: Option<PathBuf>,
+    },
 }
 
-impl TrackingEvent {
-    fn time(&self, include_seconds: bool) -> DateTime<Utc> {
+impl Command {
+    /// whether the command never changes the data file, so it can combine several data files
+    fn is_read_only(&self) -> bool {
         match self {
-            Self::Start(TrackingData { time, .. }) | Self::Stop(TrackingData { time, .. }) => {
-                let time = *time;
-                if include_seconds {
-                    time
-                } else {
-                    time.with_second(0).expect("could not set seconds to zero")
-                }
-            }
+            Self::Status {
+                wait_active,
+                wait_inactive,
+                ..
+            } => !wait_active && !wait_inactive,
+            Self::List { .. }
+            | Self::Stats { .. }
+            | Self::Notify { .. }
+            | Self::Summary { .. }
+            | Self::Path
+            | Self::Show(_)
+            | Self::Timeline { .. }
+            | Self::Diff { .. }
+            | Self::Config { .. }
+            | Self::Export { .. } => true,
+            _ => false,
         }
     }
+}
 
-    fn description(&self) -> Option<String> {
-        match self {
-            Self::Start(TrackingData { description, .. })
-            | Self::Stop(TrackingData { description, .. }) => description.clone(),
-        }
+impl Default for Command {
+    fn default() -> Self {
+        Self::Show(ShowOptions::default())
     }
+}
 
-    fn is_start(&self) -> bool {
-        match self {
-            Self::Start(_) => true,
-            Self::Stop(_) => false,
-        }
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Preset {
+    Clock,
+    Compact,
+    Decimal,
+    Words,
+}
 
-    fn is_stop(&self) -> bool {
-        match self {
-            Self::Start(_) => false,
-            Self::Stop(_) => true,
+impl FromStr for Preset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "clock" => Ok(Self::Clock),
+            "compact" => Ok(Self::Compact),
+            "decimal" => Ok(Self::Decimal),
+            "words" => Ok(Self::Words),
+            _ => anyhow::bail!("unknown preset: \"{}\"", s),
         }
     }
 }
 
-#[cfg_attr(test, derive(PartialEq, Eq))]
-#[derive(Debug, Clone, Copy)]
-enum DateOrDateTime {
-    Date(NaiveDate),
-    DateTime(NaiveDateTime),
+#[cfg(feature = "binary")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImportFormat {
+    Json,
+    Ndjson,
+    Csv,
+    Readable,
 }
 
-impl From<NaiveDate> for DateOrDateTime {
-    fn from(date: NaiveDate) -> Self {
-        Self::Date(date)
+#[cfg(feature = "binary")]
+impl ImportFormat {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+            Self::Readable => "readable",
+        }
     }
 }
 
-impl From<NaiveDateTime> for DateOrDateTime {
-    fn from(date_time: NaiveDateTime) -> Self {
-        Self::DateTime(date_time)
+#[cfg(feature = "binary")]
+impl FromStr for ImportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            "readable" => Ok(Self::Readable),
+            _ => anyhow::bail!("unknown import format: \"{}\"", s),
+        }
     }
 }
 
 #[cfg(feature = "binary")]
-fn read_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
-    let data = std::fs::read(&path)?;
-    Ok(bincode::deserialize(&data)?)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImportMode {
+    Replace,
+    Merge,
 }
 
-#[cfg(not(feature = "binary"))]
-fn read_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
-    read_json_data(path)
-}
+#[cfg(feature = "binary")]
+impl FromStr for ImportMode {
+    type Err = anyhow::Error;
 
-fn read_json_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
-    let data = std::fs::read_to_string(&path)?;
-    Ok(serde_json::from_str(&data)?)
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "replace" => Ok(Self::Replace),
+            "merge" => Ok(Self::Merge),
+            _ => anyhow::bail!("unknown import mode: \"{}\"", s),
+        }
+    }
 }
 
-fn write_with_flush<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
-    let mut f = File::create(path)?;
-    f.write_all(contents.as_ref())?;
-    f.flush()?;
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GoalPeriod {
+    Daily,
+    Weekly,
+    Monthly,
 }
 
-#[cfg(feature = "binary")]
-fn write_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent]) -> Result<()> {
-
-    let data = bincode::serialize(data).expect("could not serialize data");
-
-    let temp_path = path.as_ref().with_extension("bin.bak");
+impl FromStr for GoalPeriod {
+    type Err = anyhow::Error;
 
-    match write_with_flush(&temp_path, &data) {
-        Ok(_) => {
-            Ok(std::fs::rename(temp_path, path.as_ref())?)
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            _ => anyhow::bail!("unknown goal: \"{}\"", s),
         }
-        Err(e) => Err(e.into()),
     }
 }
 
-fn write_json_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent], pretty: bool) -> Result<()> {
-    let data = iif!(
-        pretty,
-        serde_json::to_string_pretty(data),
-        serde_json::to_string(data)
-    )
-    .expect("could not serialize data");
-    Ok(write_with_flush(&path, &data)?)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Busiest {
+    Hour,
+    Weekday,
+    Day,
 }
 
-#[cfg(not(feature = "binary"))]
-fn write_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent]) -> Result<()> {
-    write_json_data(path, data, false)
+impl FromStr for Busiest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "hour" => Ok(Self::Hour),
+            "weekday" => Ok(Self::Weekday),
+            "day" => Ok(Self::Day),
+            _ => anyhow::bail!("unknown period: \"{}\"", s),
+        }
+    }
 }
 
-fn start_tracking(
-    settings: &Settings,
-    data: &mut Vec<TrackingEvent>,
-    description: Option<String>,
-    at: Option<String>,
-) -> Result<()> {
-    let (should_add, last_description) = match data.last() {
-        None => (true, None),
-        Some(event) => (event.is_stop(), event.description()),
-    };
-    if should_add || at.is_some() {
-        data.push(TrackingEvent::Start(TrackingData {
-            description,
-            time: at.map_or_else(|| Ok(Local::now().into()), |at| parse_date_time(&at))?,
-        }));
-    } else if settings.auto_insert_stop && at.is_none() {
-        match (description, last_description) {
-            (Some(description), Some(last_description)) if description == last_description => {
-                eprintln!(
-                    "Timetracking with the description \"{}\" is already running!",
-                    description
-                )
-            }
-            (description, _) => {
-                data.push(TrackingEvent::Stop(TrackingData {
-                    description: None,
-                    time: Local::now().into(),
-                }));
-                data.push(TrackingEvent::Start(TrackingData {
-                    description,
-                    time: Local::now().into(),
-                }));
-            }
+/// the days which "show --average" divides the work time by
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AverageOver {
+    Active,
+    Calendar,
+}
+
+impl FromStr for AverageOver {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "active" => Ok(Self::Active),
+            "calendar" => Ok(Self::Calendar),
+            _ => anyhow::bail!("unknown average: \"{}\"", s),
         }
-    } else {
-        eprintln!("Time tracking is already running!");
     }
+}
 
-    Ok(())
+/// where a trailing running session ends with --assume-stopped. a time is kept as it was given
+/// until the timezone is known
+#[derive(Debug, Clone, PartialEq)]
+enum AssumedStop {
+    Start,
+    EndOfDay,
+    At(String),
 }
 
-fn stop_tracking(
-    data: &mut Vec<TrackingEvent>,
-    description: Option<String>,
-    at: Option<String>,
-) -> Result<()> {
-    let should_add = match data.last() {
-        None => true,
-        Some(event) => event.is_start(),
-    };
-    if should_add || at.is_some() {
-        data.push(TrackingEvent::Stop(TrackingData {
-            description,
-            time: at.map_or_else(|| Ok(Local::now().into()), |at| parse_date_time(&at))?,
-        }))
-    } else {
-        eprintln!("Time tracking is already stopped!");
+impl FromStr for AssumedStop {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "start" => Ok(Self::Start),
+            "eod" => Ok(Self::EndOfDay),
+            _ => Ok(Self::At(s.to_string())),
+        }
     }
+}
 
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ListOnly {
+    Starts,
+    Stops,
+    Sessions,
 }
 
-fn continue_tracking(data: &mut Vec<TrackingEvent>) {
-    if let Some(TrackingEvent::Stop { .. }) = data.last() {
-        if let Some(TrackingEvent::Start(TrackingData { description, .. })) =
-            data.iter().rev().find(|t| t.is_start()).cloned()
-        {
-            data.push(TrackingEvent::Start(TrackingData {
-                description,
-                time: Local::now().into(),
-            }))
+impl FromStr for ListOnly {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "starts" => Ok(Self::Starts),
+            "stops" => Ok(Self::Stops),
+            "sessions" => Ok(Self::Sessions),
+            _ => anyhow::bail!("unknown list mode: \"{}\"", s),
         }
-    } else {
-        eprintln!("Time tracking couldn't be continued, because there are no entries. Use the start command instead!");
     }
 }
 
-fn split_duration(duration: Duration) -> (i64, i64, i64) {
-    let hours = duration.num_hours();
-    let hours_in_minutes = hours * 60;
-    let hours_in_seconds = hours_in_minutes * 60;
-    let minutes = duration.num_minutes() - hours_in_minutes;
-    let minutes_in_seconds = minutes * 60;
-    let seconds = duration.num_seconds() - hours_in_seconds - minutes_in_seconds;
-    (hours, minutes, seconds)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum SummarySort {
+    #[default]
+    Duration,
+    Time,
+    Description,
 }
 
-fn filter_events(
-    data: &[TrackingEvent],
-    from: &Option<String>,
-    to: &Option<String>,
-    filter: &Option<String>,
-) -> Result<Vec<TrackingEvent>> {
-    let (filter, from, to) = match filter {
-        Some(from) if from == "week" => {
-            let now = Local::today();
-            let weekday = now.weekday();
-            let offset = weekday.num_days_from_monday();
-            let (monday_offset, sunday_offset) = (offset, 6 - offset);
-            let from = DateOrDateTime::Date(
-                (now - Duration::days(i64::from(monday_offset))).naive_local(),
-            );
-            let to = DateOrDateTime::Date(
-                (now + Duration::days(i64::from(sunday_offset))).naive_local(),
-            );
-            (None, Some(from), Some(to))
-        }
-        f => {
-            let from = from.as_deref().map_or_else(
-                || Ok(DateOrDateTime::Date(Local::today().naive_local())),
-                parse_date_or_date_time,
-            )?;
+impl FromStr for SummarySort {
+    type Err = anyhow::Error;
 
-            let to = to
-                .as_deref()
-                .map(parse_date_or_date_time)
-                .unwrap_or_else(|| {
-                    Ok(match from {
-                        DateOrDateTime::DateTime(from) => DateOrDateTime::Date(from.date()),
-                        from @ DateOrDateTime::Date(..) => from,
-                    })
-                })?;
-            (f.clone(), Some(from), Some(to))
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "duration" => Ok(Self::Duration),
+            "time" => Ok(Self::Time),
+            "description" => Ok(Self::Description),
+            _ => anyhow::bail!("unknown sort order: \"{}\"", s),
         }
-    };
-    let data_iterator = data
-        .iter()
-        .filter(|entry| {
-            iif!(
-                filter.clone().unwrap_or_default() == "all",
-                true,
-                match from {
-                    None => true,
-                    Some(DateOrDateTime::Date(from)) => {
-                        entry.time(true).timestamp_millis()
-                            >= TimeZone::from_local_date(&Local, &from)
-                                .unwrap()
-                                .and_time(NaiveTime::from_hms(0, 0, 0))
-                                .expect("Failed to add time from date")
-                                .timestamp_millis()
-                    }
-                    Some(DateOrDateTime::DateTime(from)) => {
-                        entry.time(true).timestamp_millis()
-                            >= TimeZone::from_local_datetime(&Local, &from)
-                                .unwrap()
-                                .timestamp_millis()
-                    }
-                }
-            )
-        })
-        .filter(|entry| {
-            iif!(
-                filter.clone().unwrap_or_default() == "all",
-                true,
-                match to {
-                    None => true,
-                    Some(DateOrDateTime::Date(to)) => {
-                        entry.time(true).timestamp_millis()
-                            <= TimeZone::from_local_date(&Local, &to)
-                                .unwrap()
-                                .and_time(NaiveTime::from_hms(23, 59, 59))
-                                .expect("Failed to add time from date")
-                                .timestamp_millis()
-                    }
-                    Some(DateOrDateTime::DateTime(to)) => {
-                        entry.time(true).timestamp_millis()
-                            <= TimeZone::from_local_datetime(&Local, &to)
-                                .unwrap()
-                                .timestamp_millis()
-                    }
-                }
-            )
-        })
-        .filter(|entry| match entry {
-            TrackingEvent::Start(TrackingData { description, .. })
-            | TrackingEvent::Stop(TrackingData { description, .. }) => match (&filter, description)
-            {
-                (Some(filter), Some(description)) => {
-                    filter == "all" || description.contains(filter)
-                }
-                (Some(filter), None) => filter == "all",
-                (None, _) => true,
-            },
-        })
-        .skip_while(|entry| TrackingEvent::is_stop(entry));
+    }
+}
 
-    Ok(data_iterator.cloned().collect())
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TableFormat {
+    Text,
+    Csv,
+    Markdown,
 }
 
-fn get_data_as_days(data: &[TrackingEvent]) -> Vec<Vec<TrackingEvent>> {
-    if data.is_empty() {
-        return vec![];
-    }
+impl FromStr for TableFormat {
+    type Err = anyhow::Error;
 
-    let mut current_day = data
-        .first()
-        .expect("Tracking event is empty")
-        .time(true)
-        .date();
-    let mut result = Vec::new();
-    let mut current = Vec::new();
-    for d in data {
-        let date = d.time(true).date();
-        if current_day == date {
-            current.push(d.clone());
-        } else {
-            result.push(current);
-            current = Vec::new();
-            current.push(d.clone());
-            current_day = date;
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "csv" => Ok(Self::Csv),
+            "markdown" => Ok(Self::Markdown),
+            _ => anyhow::bail!("unknown table format: \"{}\"", s),
         }
     }
-    if !current.is_empty() {
-        result.push(current);
-    }
-    return result;
 }
 
-const CHECKED_ADD_DURATION_ERROR: &str = "couldn't add up durations";
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum GroupBy {
+    Description,
+    Project,
+    Tag,
+}
 
-fn get_time_from_day(
-    settings: &Settings,
-    data: &[TrackingEvent],
-    include_seconds: bool,
-) -> Duration {
-    let mut data_iterator = data.iter();
-    let mut work_day = Duration::zero();
-    let mut first = None;
-    let mut last = None;
-    loop {
-        let start = data_iterator.find(|e| e.is_start());
-        let stop = data_iterator.find(|e| e.is_stop());
-        match (start, stop) {
-            (Some(start), Some(stop)) => {
-                if let None = first {
-                    first = Some(start.time(include_seconds));
-                }
-                last = Some(stop.time(include_seconds));
-                let duration = stop.time(include_seconds) - start.time(include_seconds);
-                work_day = work_day
-                    .checked_add(&duration)
-                    .expect(CHECKED_ADD_DURATION_ERROR);
-            }
-            (Some(start), None) => {
-                if let None = first {
-                    first = Some(start.time(include_seconds));
-                }
-                let now = if include_seconds {
-                    Utc::now()
-                } else {
-                    Utc::now().with_second(0).unwrap()
-                };
-                last = Some(now);
-                let duration = now - start.time(include_seconds);
-                work_day = work_day
-                    .checked_add(&duration)
-                    .expect(CHECKED_ADD_DURATION_ERROR);
-                break;
-            }
-            (_, _) => break,
-        }
-    }
-    if settings.min_daily_break > 0 {
-        let now = Utc::now();
-        let total = last.unwrap_or(now) - first.unwrap_or(now);
-        let pause = total - work_day;
-        let min_break_duration = Duration::minutes(i64::from(settings.min_daily_break));
-        if pause > Duration::zero() && pause < min_break_duration {
-            let difference = min_break_duration - pause;
-            work_day = work_day - difference;
+impl FromStr for GroupBy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "description" => Ok(Self::Description),
+            "project" => Ok(Self::Project),
+            "tag" => Ok(Self::Tag),
+            _ => anyhow::bail!("unknown grouping: \"{}\"", s),
         }
     }
-    work_day.max(Duration::zero())
 }
 
-fn get_time_from_events(
-    settings: &Settings,
-    data: &[TrackingEvent],
-    include_seconds: bool,
-) -> Duration {
-    let days = get_data_as_days(data);
-    let mut time = Duration::zero();
-    for day in days {
-        let time_for_day = get_time_from_day(&settings, &day, include_seconds);
-        time = time
-            .checked_add(&time_for_day)
-            .expect(CHECKED_ADD_DURATION_ERROR);
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExportFormat {
+    Json,
+    Readable,
+    Org,
+    Csv,
+    Heatmap,
+}
+
+impl ExportFormat {
+    /// the file extension for the files of --split-by
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Readable => "txt",
+            Self::Org => "org",
+            Self::Csv | Self::Heatmap => "csv",
+        }
     }
-    time
 }
 
-fn get_remaining_minutes(settings: &Settings, filter: &str, hours: i64, minutes: i64) -> i64 {
-    let total = minutes + (hours * 60);
-    let time_goal = if filter == "week" {
-        &settings.time_goal.weekly
-    } else {
-        &settings.time_goal.daily
-    };
-    let required = i64::from(time_goal.minutes) + (i64::from(time_goal.hours) * 60);
-    required - total
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SplitBy {
+    Day,
+    Week,
+    Month,
 }
 
-fn show(
-    settings: &Settings,
-    data: &[TrackingEvent],
-    filter: &FilterData,
-    format: Option<String>,
-    include_seconds: bool,
-    plain: bool,
-    remaining: bool,
-) -> Result<()> {
-    let FilterData { from, to, filter } = filter;
-    let filtered_data = filter_events(data, &from, &to, &filter)?;
-    let work_time = get_time_from_events(&settings, &filtered_data, include_seconds);
-    let (mut hours, mut minutes, mut seconds) = split_duration(work_time);
+impl FromStr for SplitBy {
+    type Err = anyhow::Error;
 
-    let filter = filter.clone().unwrap_or_default();
-    if remaining {
-        if (filter == "week" || filter.is_empty()) && from.is_none() && to.is_none() {
-            seconds = 0;
-            let mut remaining_minutes = get_remaining_minutes(&settings, &filter, hours, minutes);
-
-            if filter != "week" {
-                let filtered_data_week =
-                    filter_events(&data, &None, &None, &Some("week".to_string()))?;
-                let week_work_time =
-                    get_time_from_events(&settings, &filtered_data_week, include_seconds);
-                let (week_hours, week_minutes, _) = split_duration(week_work_time);
-                let remaining_minutes_week =
-                    get_remaining_minutes(&settings, "week", week_hours, week_minutes);
-
-                let today = Local::today().weekday();
-                
-                if today == settings.last_day_of_work_week {
-                    // on last day in a work week, always show remaining minutes for week
-                    remaining_minutes = remaining_minutes_week;
-                } else {
-                    // on all other days, show whichever is less
-                    remaining_minutes = remaining_minutes.min(remaining_minutes_week);
-                }
-            }
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "day" => Ok(Self::Day),
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            _ => anyhow::bail!("unknown period: \"{}\"", s),
+        }
+    }
+}
 
-            remaining_minutes = remaining_minutes.max(0);
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
 
-            hours = remaining_minutes / 60;
-            minutes = remaining_minutes - (hours * 60);
-        } else {
-            eprintln!("Remaining only works when \"from\" and \"to\" are not set and with no filter or filter \"week\"");
-            return Ok(());
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "readable" => Ok(Self::Readable),
+            "org" => Ok(Self::Org),
+            "csv" => Ok(Self::Csv),
+            "heatmap" => Ok(Self::Heatmap),
+            _ => anyhow::bail!("unknown export format: \"{}\"", s),
         }
     }
-    let seconds_final = if include_seconds { seconds } else { 0 };
-    let format = format.unwrap_or_else(|| "{hh}:{mm}:{ss}".to_string());
-    let time = format
-        .replace("{hh}", &format!("{:02}", hours))
-        .replace("{mm}", &format!("{:02}", minutes))
-        .replace("{ss}", &format!("{:02}", seconds_final))
-        .replace("{h}", &format!("{}", hours))
-        .replace("{m}", &format!("{}", minutes))
-        .replace("{s}", &format!("{}", seconds_final));
-    if plain {
-        println!("{}", time);
-    } else if remaining {
-        println!("Remaining Work Time: {}", time);
-    } else {
-        println!("Work Time: {}", time);
-    }
+}
 
-    Ok(())
+/// a tag without its leading "#", so that "#urgent" and "urgent" are the same tag
+fn strip_tag(tag: &str) -> String {
+    tag.trim_start_matches('#').to_string()
 }
 
-fn cleanup(data: &[TrackingEvent]) -> Vec<TrackingEvent> {
-    let mut cleaned = Vec::with_capacity(data.len());
+/// the project and tags given to a start with --project and --tag
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Labels {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
 
-    let mut data_iter = data.iter();
-    let mut conflicting = Vec::new();
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+}
 
-    let mut is_start = None;
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct TrackingData {
+    description: Option<String>,
 
-    let mut all_conflicting = Vec::new();
+    #[serde(with = "ts_seconds")]
+    time: DateTime<Utc>,
 
-    while let Some(e) = data_iter.next() {
-        match is_start {
-            None => {
-                is_start = Some(e.is_start());
-                cleaned.push(e);
-            }
-            Some(true) => {
-                if e.is_start() {
-                    if conflicting.is_empty() {
-                        if let Some(last_cleaned_e) = cleaned.pop() {
-                            conflicting.push(last_cleaned_e);
-                        }
-                    }
-                    conflicting.push(e);
+    /// data files of older versions have no labels
+    #[serde(flatten)]
+    labels: Labels,
+}
+
+impl TrackingData {
+    fn new(description: Option<String>, time: DateTime<Utc>) -> Self {
+        Self {
+            description,
+            time,
+            labels: Labels::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum TrackingEvent {
+    Start(TrackingData),
+    Stop(TrackingData),
+}
+
+impl TrackingEvent {
+    fn time(&self, include_seconds: bool) -> DateTime<Utc> {
+        match self {
+            Self::Start(TrackingData { time, .. }) | Self::Stop(TrackingData { time, .. }) => {
+                let time = *time;
+                if include_seconds {
+                    time
                 } else {
-                    if !conflicting.is_empty() {
-                        all_conflicting.push(conflicting);
-                        conflicting = Vec::new();
-                    }
-                    cleaned.push(e);
-                    is_start.replace(false);
+                    time.with_second(0).expect("could not set seconds to zero")
                 }
             }
-            Some(false) => {
-                if e.is_stop() {
-                    if conflicting.is_empty() {
-                        if let Some(last_cleaned_e) = cleaned.pop() {
-                            conflicting.push(last_cleaned_e);
-                        }
-                    }
-                    conflicting.push(e);
-                } else {
-                    if !conflicting.is_empty() {
-                        all_conflicting.push(conflicting);
-                        conflicting = Vec::new();
-                    }
-                    cleaned.push(e);
-                    is_start.replace(true);
+        }
+    }
+
+    fn description(&self) -> Option<String> {
+        match self {
+            Self::Start(TrackingData { description, .. })
+            | Self::Stop(TrackingData { description, .. }) => description.clone(),
+        }
+    }
+
+    fn labels(&self) -> &Labels {
+        match self {
+            Self::Start(TrackingData { labels, .. }) | Self::Stop(TrackingData { labels, .. }) => {
+                labels
+            }
+        }
+    }
+
+    fn is_start(&self) -> bool {
+        match self {
+            Self::Start(_) => true,
+            Self::Stop(_) => false,
+        }
+    }
+
+    fn is_stop(&self) -> bool {
+        match self {
+            Self::Start(_) => false,
+            Self::Stop(_) => true,
+        }
+    }
+}
+
+#[cfg_attr(test, derive(PartialEq, Eq))]
+#[derive(Debug, Clone, Copy)]
+enum DateOrDateTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl DateOrDateTime {
+    /// the first moment in the range, dates start at 00:00:00
+    fn range_start(self, zone: Zone) -> DateTime<Utc> {
+        let time = match self {
+            Self::Date(date) => date.and_hms(0, 0, 0),
+            Self::DateTime(time) => time,
+        };
+        zone.from_local_datetime(&time).unwrap().with_timezone(&Utc)
+    }
+
+    /// the end of the range. dates include the whole day, so this is the start of the next day
+    fn range_end(self, zone: Zone) -> DateTime<Utc> {
+        match self {
+            Self::Date(date) => Self::Date(date.succ()).range_start(zone),
+            time @ Self::DateTime(..) => time.range_start(zone),
+        }
+    }
+}
+
+/// a range of "show --range", labeled with the text it was given as. the ends are kept as they
+/// were given until the timezone is known
+#[derive(Debug, Clone)]
+struct ShowRange {
+    label: String,
+    from: String,
+    to: String,
+}
+
+impl From<NaiveDate> for DateOrDateTime {
+    fn from(date: NaiveDate) -> Self {
+        Self::Date(date)
+    }
+}
+
+impl From<NaiveDateTime> for DateOrDateTime {
+    fn from(date_time: NaiveDateTime) -> Self {
+        Self::DateTime(date_time)
+    }
+}
+
+/// the start of binary data files, followed by a byte with the version of the format.
+/// files of tt 1.5 and older have no header and start with the number of events
+#[cfg(feature = "binary")]
+const DATA_MAGIC: &[u8] = b"TTDF";
+
+/// the version of the binary format, which has to change with the layout of `BinaryData`
+#[cfg(feature = "binary")]
+const DATA_VERSION: u8 = 2;
+
+/// an event in the binary format. bincode can't skip fields like the json format does, so it has
+/// its own types with every field
+#[cfg(feature = "binary")]
+#[derive(Serialize, Deserialize)]
+enum BinaryEvent<D> {
+    Start(D),
+    Stop(D),
+}
+
+#[cfg(feature = "binary")]
+#[derive(Serialize, Deserialize)]
+struct BinaryData {
+    description: Option<String>,
+    #[serde(with = "ts_seconds")]
+    time: DateTime<Utc>,
+    project: Option<String>,
+    tags: Vec<String>,
+}
+
+/// the data of version 1 and of tt 1.5, which had no labels
+#[cfg(feature = "binary")]
+#[cfg_attr(test, derive(Serialize))]
+#[derive(Deserialize)]
+struct BinaryDataV1 {
+    description: Option<String>,
+    #[serde(with = "ts_seconds")]
+    time: DateTime<Utc>,
+}
+
+#[cfg(feature = "binary")]
+impl<D: Into<TrackingData>> From<BinaryEvent<D>> for TrackingEvent {
+    fn from(event: BinaryEvent<D>) -> Self {
+        match event {
+            BinaryEvent::Start(data) => Self::Start(data.into()),
+            BinaryEvent::Stop(data) => Self::Stop(data.into()),
+        }
+    }
+}
+
+#[cfg(feature = "binary")]
+impl From<&TrackingEvent> for BinaryEvent<BinaryData> {
+    fn from(event: &TrackingEvent) -> Self {
+        let data = |data: &TrackingData| BinaryData {
+            description: data.description.clone(),
+            time: data.time,
+            project: data.labels.project.clone(),
+            tags: data.labels.tags.clone(),
+        };
+        match event {
+            TrackingEvent::Start(start) => Self::Start(data(start)),
+            TrackingEvent::Stop(stop) => Self::Stop(data(stop)),
+        }
+    }
+}
+
+#[cfg(feature = "binary")]
+impl From<BinaryData> for TrackingData {
+    fn from(data: BinaryData) -> Self {
+        Self {
+            description: data.description,
+            time: data.time,
+            labels: Labels {
+                project: data.project,
+                tags: data.tags,
+            },
+        }
+    }
+}
+
+#[cfg(feature = "binary")]
+impl From<BinaryDataV1> for TrackingData {
+    fn from(data: BinaryDataV1) -> Self {
+        Self::new(data.description, data.time)
+    }
+}
+
+#[cfg(feature = "binary")]
+fn deserialize_events<D: serde::de::DeserializeOwned + Into<TrackingData>>(
+    data: &[u8],
+) -> bincode::Result<Vec<TrackingEvent>> {
+    let events: Vec<BinaryEvent<D>> = bincode::deserialize(data)?;
+    Ok(events.into_iter().map(TrackingEvent::from).collect())
+}
+
+#[cfg(feature = "binary")]
+fn read_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
+    let data = std::fs::read(&path)?;
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !data.starts_with(DATA_MAGIC) {
+        return deserialize_events::<BinaryDataV1>(&data)
+            .context("could not read the data file without a format header as data of tt 1.5");
+    }
+    let events = || &data[DATA_MAGIC.len() + 1..];
+    match data.get(DATA_MAGIC.len()) {
+        Some(&DATA_VERSION) => Ok(deserialize_events::<BinaryData>(events())?),
+        Some(1) => Ok(deserialize_events::<BinaryDataV1>(events())?),
+        Some(version) if *version > DATA_VERSION => anyhow::bail!(
+            "the data file uses version {} of the data format, but this version of tt only reads \
+             up to version {}. Please update tt, or export the data with \"tt export\" of the \
+             newer version and import the json here",
+            version,
+            DATA_VERSION
+        ),
+        _ => anyhow::bail!("the data file has an unknown data format version"),
+    }
+}
+
+#[cfg(not(feature = "binary"))]
+fn read_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
+    read_json_data(path)
+}
+
+/// json data is either a bare list of events or an object with user defined metadata
+#[cfg(any(not(feature = "binary"), test))]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    Events(Vec<TrackingEvent>),
+    WithMeta {
+        meta: serde_json::Value,
+        events: Vec<TrackingEvent>,
+    },
+}
+
+#[derive(Serialize)]
+struct JsonDataWithMeta<'a> {
+    meta: &'a serde_json::Value,
+    events: &'a [TrackingEvent],
+}
+
+/// returns the metadata, if the file has any, and the events
+#[cfg(any(not(feature = "binary"), test))]
+fn read_json_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<(Option<serde_json::Value>, Vec<TrackingEvent>)> {
+    let data = std::fs::read_to_string(&path)?;
+    Ok(match serde_json::from_str(&data)? {
+        JsonData::Events(events) => (None, events),
+        JsonData::WithMeta { meta, events } => (Some(meta), events),
+    })
+}
+
+#[cfg(any(not(feature = "binary"), test))]
+fn read_json_data<P: AsRef<Path>>(path: P) -> Result<Vec<TrackingEvent>> {
+    Ok(read_json_file(path)?.1)
+}
+
+fn write_with_flush<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let mut f = File::create(path)?;
+    f.write_all(contents.as_ref())?;
+    f.flush()?;
+    Ok(())
+}
+
+/// creates the file, if it doesn't exist, and makes it readable and writable only by its owner.
+/// the contents are kept
+#[cfg(unix)]
+fn make_private<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .mode(0o600)
+        .open(path)?;
+    let mut permissions = file.metadata()?.permissions();
+    if permissions.mode() & 0o077 != 0 {
+        permissions.set_mode(0o600);
+        file.set_permissions(permissions)?;
+    }
+    Ok(())
+}
+
+/// there are no unix permissions to restrict
+#[cfg(not(unix))]
+fn make_private<P: AsRef<Path>>(_path: P) -> io::Result<()> {
+    Ok(())
+}
+
+/// writes the data file. with `private` only its owner can read it on unix
+#[cfg(feature = "binary")]
+fn write_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent], private: bool) -> Result<()> {
+    let mut contents = DATA_MAGIC.to_vec();
+    contents.push(DATA_VERSION);
+    let events: Vec<_> = data.iter().map(BinaryEvent::from).collect();
+    contents.extend(bincode::serialize(&events).expect("could not serialize data"));
+    let data = contents;
+
+    let temp_path = path.as_ref().with_extension("bin.bak");
+    if private {
+        make_private(&temp_path).with_context(|| {
+            format!(
+                "could not restrict the permissions of {}",
+                temp_path.display()
+            )
+        })?;
+    }
+
+    write_with_flush(&temp_path, &data)
+        .with_context(|| format!("could not write {}", temp_path.display()))?;
+    // the temp file replaces the data file, so it keeps the permissions the user gave it
+    if !private {
+        if let Ok(metadata) = std::fs::metadata(&path) {
+            std::fs::set_permissions(&temp_path, metadata.permissions()).with_context(|| {
+                format!("could not set the permissions of {}", temp_path.display())
+            })?;
+        }
+    }
+    std::fs::rename(&temp_path, path.as_ref()).with_context(|| {
+        format!(
+            "could not move {} to {}",
+            temp_path.display(),
+            path.as_ref().display()
+        )
+    })
+}
+
+/// describes the difference between the stored and the changed data for --dry-run
+fn get_dry_run_report(
+    previous: &[TrackingEvent],
+    data: &[TrackingEvent],
+    time_format: &str,
+    zone: Zone,
+) -> Vec<String> {
+    let removed: Vec<_> = previous
+        .iter()
+        .filter(|event| !data.contains(event))
+        .cloned()
+        .collect();
+    let added: Vec<_> = data
+        .iter()
+        .filter(|event| !previous.contains(event))
+        .cloned()
+        .collect();
+
+    let mut lines = vec![format!(
+        "Dry run: {} entries would be written ({} before). Nothing was changed.",
+        data.len(),
+        previous.len()
+    )];
+    lines.extend(
+        get_human_readable(&removed, time_format, zone)
+            .into_iter()
+            .map(|line| format!("- {}", line)),
+    );
+    lines.extend(
+        get_human_readable(&added, time_format, zone)
+            .into_iter()
+            .map(|line| format!("+ {}", line)),
+    );
+    lines
+}
+
+/// writes the data, or with `dry_run` only prints how it differs from `previous`
+fn save_data<P: AsRef<Path>>(
+    path: P,
+    previous: &[TrackingEvent],
+    data: &[TrackingEvent],
+    dry_run: bool,
+    private: bool,
+    time_format: &str,
+    zone: Zone,
+) -> Result<()> {
+    if !dry_run {
+        return write_data(path, data, private);
+    }
+    for line in get_dry_run_report(previous, data, time_format, zone) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// writes a bare list of events, or an object with the events and `meta` if given
+fn write_json_data<P: AsRef<Path>>(
+    path: P,
+    data: &[TrackingEvent],
+    meta: Option<&serde_json::Value>,
+    pretty: bool,
+) -> Result<()> {
+    let data = match meta {
+        Some(meta) => iif!(
+            pretty,
+            serde_json::to_string_pretty(&JsonDataWithMeta { meta, events: data }),
+            serde_json::to_string(&JsonDataWithMeta { meta, events: data })
+        ),
+        None => iif!(
+            pretty,
+            serde_json::to_string_pretty(data),
+            serde_json::to_string(data)
+        ),
+    }
+    .expect("could not serialize data");
+    Ok(write_with_flush(&path, &data)?)
+}
+
+/// writes the data file. with `private` only its owner can read it on unix
+#[cfg(not(feature = "binary"))]
+fn write_data<P: AsRef<Path>>(path: P, data: &[TrackingEvent], private: bool) -> Result<()> {
+    // tt can't change the metadata, so keep what is already in the file
+    let meta = read_json_file(&path).ok().and_then(|(meta, _)| meta);
+    if private {
+        make_private(&path).with_context(|| {
+            format!(
+                "could not restrict the permissions of {}",
+                path.as_ref().display()
+            )
+        })?;
+    }
+    write_json_data(path, data, meta.as_ref(), false)
+}
+
+/// events are stored with second precision, so two events in the same second have no
+/// well-defined order. moves the time one second past the last event or fails, depending on the
+/// same_second_events setting
+fn resolve_same_second(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    time: DateTime<Utc>,
+) -> Result<DateTime<Utc>> {
+    match data.last() {
+        Some(last) if last.time(true).timestamp() == time.timestamp() => {
+            match settings.same_second_events {
+                SameSecondEvents::Nudge => Ok(last.time(true) + Duration::seconds(1)),
+                SameSecondEvents::Error => anyhow::bail!(
+                    "An event was already recorded at {}. Please try again in a second.",
+                    time.with_timezone(&settings.zone)
+                        .format(&settings.list_time_format)
+                ),
+            }
+        }
+        _ => Ok(time),
+    }
+}
+
+/// removes empty or whitespace-only descriptions unless `keep_empty` is set
+fn normalize_description(description: Option<String>, keep_empty: bool) -> Option<String> {
+    description.filter(|description| keep_empty || !description.trim().is_empty())
+}
+
+/// starts a session. a session continued by auto_continue keeps its labels unless new ones are
+/// given. returns whether an event was added, nothing is when the session is already running
+fn start_tracking(
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    description: Option<String>,
+    labels: Labels,
+    at: Option<String>,
+    keep_empty_description: bool,
+    resume_if_same: bool,
+) -> Result<bool> {
+    let description = normalize_description(description, keep_empty_description);
+    let (should_add, last_description, last_labels) = match data.last() {
+        None => (true, None, Labels::default()),
+        Some(event) => (event.is_stop(), event.description(), event.labels().clone()),
+    };
+    let (description, labels) = match description {
+        None if settings.auto_continue && should_add => {
+            match data.iter().rev().find(|event| event.is_start()) {
+                Some(start) if labels == Labels::default() => {
+                    (start.description(), start.labels().clone())
                 }
+                Some(start) => (start.description(), labels),
+                None => (None, labels),
+            }
+        }
+        description => (description, labels),
+    };
+    if resume_if_same && !should_add && last_description == description && last_labels == labels {
+        return Ok(false);
+    }
+    if should_add || at.is_some() {
+        let time = at.map_or_else(|| Ok(now()), |at| parse_date_time(&at, settings.zone))?;
+        let time = resolve_same_second(settings, data, time)?;
+        data.push(TrackingEvent::Start(TrackingData {
+            description,
+            time,
+            labels,
+        }));
+    } else if settings.auto_insert_stop && at.is_none() {
+        match (description, last_description) {
+            (Some(description), Some(last_description))
+                if description == last_description && labels == last_labels =>
+            {
+                eprintln!(
+                    "Timetracking with the description \"{}\" is already running!",
+                    description
+                );
+                return Ok(false);
+            }
+            (description, _) => {
+                // the new session starts now, the running one stops a second earlier unless
+                // it only just started
+                let now = now();
+                let stop = data
+                    .last()
+                    .map_or(now, |last| last.time(true))
+                    .max(now - Duration::seconds(1));
+                let stop = resolve_same_second(settings, data, stop)?;
+                data.push(TrackingEvent::Stop(TrackingData::new(None, stop)));
+                data.push(TrackingEvent::Start(TrackingData {
+                    description,
+                    time: now.max(stop + Duration::seconds(1)),
+                    labels,
+                }));
             }
         }
+    } else {
+        eprintln!("Time tracking is already running!");
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// stops the running session. returns whether an event was added, nothing is when the time
+/// tracking is already stopped
+fn stop_tracking(
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    description: Option<String>,
+    at: Option<String>,
+    keep_empty_description: bool,
+) -> Result<bool> {
+    let description = normalize_description(description, keep_empty_description);
+    let should_add = match data.last() {
+        None => true,
+        Some(event) => event.is_start(),
+    };
+    if should_add || at.is_some() {
+        let time = at.map_or_else(|| Ok(now()), |at| parse_date_time(&at, settings.zone))?;
+        let time = resolve_same_second(settings, data, time)?;
+        data.push(TrackingEvent::Stop(TrackingData::new(description, time)));
+        Ok(true)
+    } else {
+        eprintln!("Time tracking is already stopped!");
+        Ok(false)
+    }
+}
+
+/// a line per event from index `added` on, e.g. `Started "coding" at 2021-03-29 09:00:00`. a stop
+/// without a description names the session it ended, a start marked as `inherited` took its
+/// description from an earlier session
+fn get_confirmations(
+    data: &[TrackingEvent],
+    added: usize,
+    inherited: bool,
+    time_format: &str,
+    zone: Zone,
+) -> Vec<String> {
+    (added..data.len())
+        .map(|index| {
+            let event = &data[index];
+            let description = event.description().or_else(|| {
+                data[..index]
+                    .iter()
+                    .rev()
+                    .find(|event| event.is_start())
+                    .filter(|_| event.is_stop())
+                    .and_then(TrackingEvent::description)
+            });
+            let description = match description {
+                Some(description) if event.is_start() && inherited => {
+                    format!(" \"{}\" (inherited)", description)
+                }
+                Some(description) => format!(" \"{}\"", description),
+                None => String::new(),
+            };
+            format!(
+                "{}{} at {}",
+                iif!(event.is_start(), "Started", "Stopped"),
+                description,
+                event.time(true).with_timezone(&zone).format(time_format)
+            )
+        })
+        .collect()
+}
+
+/// a warning if the session which was just stopped ran longer than the smallest session limit
+/// matching its description
+fn get_session_limit_warning(settings: &Settings, data: &[TrackingEvent]) -> Option<String> {
+    let (stop, rest) = data.split_last()?;
+    let start = rest
+        .last()
+        .filter(|event| event.is_start() && stop.is_stop())?;
+    let description = start.description()?;
+    let lowercase = description.to_lowercase();
+    let limit = settings
+        .session_limits
+        .iter()
+        .filter(|(part, _)| lowercase.contains(&part.to_lowercase()))
+        .map(|(_, limit)| Duration::minutes(i64::from(*limit)))
+        .min()?;
+    let duration = stop.time(true) - start.time(true);
+    iif!(
+        duration > limit,
+        Some(format!(
+            "the \"{}\" session ran {}, over the {} soft limit",
+            description,
+            format_window(duration),
+            format_window(limit)
+        )),
+        None
+    )
+}
+
+/// a copy of the data in which a trailing running session is stopped as given by
+/// --assume-stopped. the data file is not changed
+fn with_assumed_stop(
+    data: &[TrackingEvent],
+    option: &AssumeStopped,
+    zone: Zone,
+) -> Result<Vec<TrackingEvent>> {
+    let mut data = data.to_vec();
+    let assumed = match &option.assume_stopped {
+        Some(assumed) => assumed.clone().unwrap_or(AssumedStop::Start),
+        None => return Ok(data),
+    };
+    let start = match data.last() {
+        Some(event) if event.is_start() => event.time(true),
+        _ => return Ok(data),
+    };
+    let time = match assumed {
+        AssumedStop::Start => start,
+        AssumedStop::EndOfDay => start
+            .with_timezone(&zone)
+            .date()
+            .and_hms(23, 59, 59)
+            .with_timezone(&Utc),
+        AssumedStop::At(time) => {
+            let time = parse_date_time(&time, zone)?;
+            if time < start {
+                anyhow::bail!("the running session cannot be stopped before it started")
+            }
+            time
+        }
+    };
+    data.push(TrackingEvent::Stop(TrackingData::new(None, time)));
+    Ok(data)
+}
+
+/// moves the last event back by `minus`. it has to stay after the event before it
+fn amend_last_event(
+    data: &mut [TrackingEvent],
+    minus: Duration,
+    time_format: &str,
+    zone: Zone,
+) -> Result<()> {
+    let (last, previous) = match data.split_last_mut() {
+        Some((last, rest)) => (last, rest.last()),
+        None => anyhow::bail!("there are no entries to amend"),
+    };
+    let amended = last.time(true) - minus;
+    if let Some(previous) = previous {
+        if amended <= previous.time(true) {
+            anyhow::bail!(
+                "the entry would move before the entry at {}",
+                previous.time(true).with_timezone(&zone).format(time_format)
+            );
+        }
+    }
+    match last {
+        TrackingEvent::Start(TrackingData { time, .. })
+        | TrackingEvent::Stop(TrackingData { time, .. }) => *time = amended,
+    }
+    Ok(())
+}
+
+/// removes the last stop, so that the session it ended is running again. fails if the last entry
+/// is no stop or if the stop is older than `max_age`
+fn reopen_session(
+    data: &mut Vec<TrackingEvent>,
+    max_age: Duration,
+    now: DateTime<Utc>,
+    time_format: &str,
+    zone: Zone,
+) -> Result<()> {
+    let stop = match data.last() {
+        Some(event) if event.is_stop() => event.time(true),
+        Some(_) => anyhow::bail!("the time tracking is running, there is no stop to remove"),
+        None => anyhow::bail!("there are no entries to reopen"),
+    };
+    if now - stop > max_age {
+        anyhow::bail!(
+            "the last stop at {} is older than {} minutes (reopen_max_minutes). use continue instead",
+            stop.with_timezone(&zone).format(time_format),
+            max_age.num_minutes()
+        );
+    }
+    data.pop();
+    Ok(())
+}
+
+/// moves all events in the range by `shift` and returns how many were moved, together with a
+/// warning for every neighbor outside of the range which would change places with them
+fn shift_events(
+    data: &mut [TrackingEvent],
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    shift: Duration,
+    time_format: &str,
+    zone: Zone,
+) -> (usize, Vec<String>) {
+    let in_range = |event: &TrackingEvent| {
+        let time = event.time(true);
+        from.into_iter().all(|from| time >= from) && to.into_iter().all(|to| time < to)
+    };
+    let first = match data.iter().position(in_range) {
+        Some(first) => first,
+        None => return (0, Vec::new()),
+    };
+    let last = data.iter().rposition(in_range).unwrap_or(first);
+
+    for event in &mut data[first..=last] {
+        match event {
+            TrackingEvent::Start(TrackingData { time, .. })
+            | TrackingEvent::Stop(TrackingData { time, .. }) => *time = *time + shift,
+        }
+    }
+
+    let format_time = |event: &TrackingEvent| {
+        event
+            .time(true)
+            .with_timezone(&zone)
+            .format(time_format)
+            .to_string()
+    };
+    let mut warnings = Vec::new();
+    if let Some(previous) = first.checked_sub(1).map(|index| &data[index]) {
+        if data[first].time(true) <= previous.time(true) {
+            warnings.push(format!(
+                "The shifted entries move before the entry at {}",
+                format_time(previous)
+            ));
+        }
+    }
+    if let Some(next) = data.get(last + 1) {
+        if data[last].time(true) >= next.time(true) {
+            warnings.push(format!(
+                "The shifted entries move after the entry at {}",
+                format_time(next)
+            ));
+        }
+    }
+    (last - first + 1, warnings)
+}
+
+/// removes all entries in the range and returns how many were removed. a stop at the beginning of
+/// the range belongs to a session before it and is kept. fails without changing anything if a
+/// session would be left without its start or its stop
+fn delete_events(
+    data: &mut Vec<TrackingEvent>,
+    from: DateOrDateTime,
+    to: DateOrDateTime,
+    time_format: &str,
+    zone: Zone,
+) -> Result<usize> {
+    let in_range = |event: &TrackingEvent| is_in_range(event, Some(from), Some(to), zone);
+    let first = match data
+        .iter()
+        .position(|event| in_range(event) && event.is_start())
+    {
+        Some(first) => first,
+        None => return Ok(0),
+    };
+    let last = data.iter().rposition(in_range).unwrap_or(first);
+
+    let format_event = |event: &TrackingEvent| {
+        to_human_readable(
+            iif!(event.is_start(), "start", "stop"),
+            &event.time(true).with_timezone(&zone),
+            event.description(),
+            time_format,
+        )
+    };
+    if let Some(previous) = first.checked_sub(1).map(|index| &data[index]) {
+        if previous.is_start() {
+            anyhow::bail!(
+                "the session of the {} would lose its end. move --from before it",
+                format_event(previous)
+            );
+        }
+    }
+    if let Some(next) = data.get(last + 1) {
+        if next.is_stop() {
+            anyhow::bail!(
+                "the {} would lose its start. move --to after it",
+                format_event(next)
+            );
+        }
+    }
+    data.drain(first..=last);
+    Ok(last - first + 1)
+}
+
+/// stops all running sessions and returns how many were stopped. unlike stop_tracking it is
+/// fine if nothing is running
+fn stop_all(
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    description: Option<String>,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    match data.last() {
+        Some(event) if event.is_start() => {
+            let time = resolve_same_second(settings, data, now)?;
+            data.push(TrackingEvent::Stop(TrackingData::new(description, time)));
+            Ok(1)
+        }
+        _ => Ok(0),
+    }
+}
+
+const EDITOR_TEMPLATE: &str = "
+# Please enter the description for this entry. Only the first line is used.
+# Lines starting with '#' will be ignored and an empty description aborts.
+";
+
+/// returns the first line which is neither empty nor a comment
+fn parse_editor_description(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+}
+
+/// the description given with --message, read from `stdin` for "-", or else the positional one.
+/// fails if both are given and differ
+fn resolve_description(
+    description: Option<String>,
+    message: Option<String>,
+    mut stdin: impl io::Read,
+) -> Result<Option<String>> {
+    let message = match message.as_deref() {
+        Some("-") => {
+            let mut text = String::new();
+            stdin
+                .read_to_string(&mut text)
+                .context("could not read the description from stdin")?;
+            Some(text.trim_end_matches(&['\r', '\n'][..]).to_string())
+        }
+        _ => message,
+    };
+    match (description, message) {
+        (Some(description), Some(message)) if description != message => anyhow::bail!(
+            "the description \"{}\" and --message \"{}\" differ",
+            description,
+            message
+        ),
+        (description, message) => Ok(message.or(description)),
+    }
+}
+
+fn read_description_from_editor() -> Result<Option<String>> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_default();
+    let mut editor_args = editor.split_whitespace();
+    let program = match editor_args.next() {
+        Some(program) if atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stdout) => program,
+        _ => anyhow::bail!(
+            "No editor available. Please set $VISUAL or $EDITOR or pass the description directly."
+        ),
+    };
+
+    let path = std::env::temp_dir().join(format!("tt-description-{}.txt", std::process::id()));
+    std::fs::write(&path, EDITOR_TEMPLATE)?;
+    let status = std::process::Command::new(program)
+        .args(editor_args)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("could not start editor \"{}\"", editor));
+    let text = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+    if !status?.success() {
+        anyhow::bail!("Editor \"{}\" exited with an error", editor);
+    }
+
+    Ok(parse_editor_description(&text?))
+}
+
+/// starts a session with the description of the last one, or with `new`, if the time tracking
+/// is stopped. an empty `new` starts a session without description
+fn continue_tracking(
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    new: Option<String>,
+    at: Option<String>,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let stopped = match data.last() {
+        Some(TrackingEvent::Stop(TrackingData { time, .. })) => *time,
+        Some(TrackingEvent::Start(_)) => anyhow::bail!("the time tracking is already running"),
+        None => anyhow::bail!(
+            "time tracking couldn't be continued, because there are no entries. Use the start command instead"
+        ),
+    };
+    let (description, labels) = match (new, data.iter().rev().find(|t| t.is_start())) {
+        (Some(new), _) => (normalize_description(Some(new), false), Labels::default()),
+        (None, Some(start)) => (start.description(), start.labels().clone()),
+        (None, None) => anyhow::bail!(
+            "time tracking couldn't be continued, because there is no start before the last stop. Run cleanup to repair the entries"
+        ),
+    };
+    let time = match at {
+        Some(at) => parse_date_time(&at, settings.zone)?,
+        None => now,
+    };
+    if time > now {
+        anyhow::bail!("cannot continue in the future");
+    }
+    if time < stopped {
+        anyhow::bail!("cannot continue before the last stop");
+    }
+    let time = resolve_same_second(settings, data, time)?;
+    data.push(TrackingEvent::Start(TrackingData {
+        description,
+        time,
+        labels,
+    }));
+    Ok(())
+}
+
+const AUTO_INSERTED_DESCRIPTION: &str = "auto-inserted";
+
+/// inserts a stop for every start which is older than `fill_gaps_after` hours and not followed
+/// by a stop. the stop is placed `typical_day_length` after the start, but before the next event.
+/// returns the number of inserted stops.
+fn fill_dangling_sessions(
+    settings: &Settings,
+    data: &mut Vec<TrackingEvent>,
+    now: DateTime<Utc>,
+) -> usize {
+    let threshold = Duration::hours(i64::from(settings.fill_gaps_after));
+    let day_length = Duration::minutes(
+        i64::from(settings.typical_day_length.hours) * 60
+            + i64::from(settings.typical_day_length.minutes),
+    );
+    let mut filled = 0;
+    for i in (0..data.len()).rev() {
+        let next = data.get(i + 1);
+        if !data[i].is_start() || matches!(next, Some(next) if next.is_stop()) {
+            continue;
+        }
+        let start = data[i].time(true);
+        if now - start < threshold {
+            continue;
+        }
+        let mut time = start + day_length;
+        if let Some(next) = next {
+            time = time.min(next.time(true) - Duration::seconds(1));
+        }
+        data.insert(
+            i + 1,
+            TrackingEvent::Stop(TrackingData::new(
+                Some(AUTO_INSERTED_DESCRIPTION.to_string()),
+                time,
+            )),
+        );
+        filled += 1;
+    }
+    filled
+}
+
+/// splits a duration into hours, minutes and seconds of its absolute value and whether it is
+/// negative, e.g. -1:30:05 is split into (1, 30, 5, true)
+fn split_duration(duration: Duration) -> (u64, u64, u64, bool) {
+    let total_seconds = duration.num_seconds();
+    let negative = total_seconds < 0;
+    let total_seconds = total_seconds.unsigned_abs();
+    let hours = total_seconds / 3600;
+    let minutes = total_seconds % 3600 / 60;
+    let seconds = total_seconds % 60;
+    (hours, minutes, seconds, negative)
+}
+
+/// drops the seconds of a duration. times without seconds are always cut off at the minute, so
+/// 1:59:45 is shown as 1:59, never rounded to 2:00 or to an inconsistent 1:60
+fn truncate_to_minutes(duration: Duration) -> Duration {
+    Duration::minutes(duration.num_minutes())
+}
+
+fn format_duration(duration: Duration) -> String {
+    let (hours, minutes, seconds, negative) = split_duration(duration);
+    format!(
+        "{}{:02}:{:02}:{:02}",
+        iif!(negative, "-", ""),
+        hours,
+        minutes,
+        seconds
+    )
+}
+
+type ResolvedFilter = (
+    Option<String>,
+    Option<DateOrDateTime>,
+    Option<DateOrDateTime>,
+);
+
+/// turns the filter arguments into the description filter and the range of the entries
+fn resolve_filter(
+    from: &Option<String>,
+    to: &Option<String>,
+    filter: &Option<String>,
+    zone: Zone,
+) -> Result<ResolvedFilter> {
+    Ok(match filter {
+        Some(from) if from == "week" => {
+            let (monday, sunday) = week_bounds(today(zone).naive_local());
+            (
+                None,
+                Some(DateOrDateTime::Date(monday)),
+                Some(DateOrDateTime::Date(sunday)),
+            )
+        }
+        f => {
+            let from = from.as_deref().map_or_else(
+                || Ok(DateOrDateTime::Date(today(zone).naive_local())),
+                |from| parse_date_or_date_time(from, zone),
+            )?;
+
+            let to = to
+                .as_deref()
+                .map(|to| parse_date_or_date_time(to, zone))
+                .unwrap_or_else(|| {
+                    Ok(match from {
+                        DateOrDateTime::DateTime(from) => DateOrDateTime::Date(from.date()),
+                        from @ DateOrDateTime::Date(..) => from,
+                    })
+                })?;
+            (f.clone(), Some(from), Some(to))
+        }
+    })
+}
+
+fn filter_events(
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    zone: Zone,
+) -> Result<Vec<TrackingEvent>> {
+    let (include_unlabeled, exclude) = (filter.include_unlabeled, &filter.exclude);
+    let (project, tags) = (&filter.project, &filter.tag);
+    let (filter, from, to) = resolve_filter(&filter.from, &filter.to, &filter.filter, zone)?;
+    let events = filter_events_in_range(data, &filter, from, to, include_unlabeled, zone);
+    Ok(filter_labels(
+        exclude_sessions(events, exclude),
+        project,
+        tags,
+    ))
+}
+
+fn is_excluded(description: &Option<String>, exclude: &[String]) -> bool {
+    match description {
+        Some(description) => exclude.iter().any(|term| description.contains(term)),
+        None => false,
+    }
+}
+
+/// the tags given with --tag, or else the words of the description starting with "#". tags are
+/// returned without the "#"
+fn get_tags(description: &Option<String>, labels: &Labels) -> Vec<String> {
+    if !labels.tags.is_empty() {
+        return labels.tags.iter().map(|tag| strip_tag(tag)).collect();
+    }
+    description
+        .iter()
+        .flat_map(|description| description.split_whitespace())
+        .filter(|word| word.len() > 1 && word.starts_with('#'))
+        .map(strip_tag)
+        .collect()
+}
+
+/// whether the session has the tag. tags given with --tag are used instead of the words of the
+/// description if there are any
+fn has_tag(description: &Option<String>, labels: &Labels, tag: &str) -> bool {
+    if !labels.tags.is_empty() {
+        return labels
+            .tags
+            .iter()
+            .any(|label| strip_tag(label) == strip_tag(tag));
+    }
+    description
+        .iter()
+        .flat_map(|description| description.split_whitespace())
+        .any(|word| word == tag)
+}
+
+fn is_billable(settings: &Settings, description: &Option<String>, labels: &Labels) -> bool {
+    !has_tag(description, labels, &settings.nonbillable_tag)
+        && (settings.billable_tag.is_empty()
+            || has_tag(description, labels, &settings.billable_tag))
+}
+
+/// keeps the sessions of the project and with all the tags of the filter
+fn filter_labels(
+    data: Vec<TrackingEvent>,
+    project: &Option<String>,
+    tags: &[String],
+) -> Vec<TrackingEvent> {
+    if project.is_none() && tags.is_empty() {
+        return data;
+    }
+    keep_sessions(&data, |start| {
+        let session_tags = get_tags(&start.description(), start.labels());
+        project
+            .iter()
+            .all(|project| start.labels().project.as_ref() == Some(project))
+            && tags
+                .iter()
+                .all(|tag| session_tags.contains(&strip_tag(tag)))
+    })
+}
+
+/// keeps only the billable or only the non-billable sessions, as chosen by the flags
+fn filter_billable(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &BillableFilter,
+) -> Vec<TrackingEvent> {
+    let billable = match (filter.billable_only, filter.nonbillable_only) {
+        (false, false) => return data.to_vec(),
+        (billable_only, _) => billable_only,
+    };
+    keep_sessions(data, |start| {
+        is_billable(settings, &start.description(), start.labels()) == billable
+    })
+}
+
+/// leaves out the sessions which start on a day without a daily goal, the days after
+/// last_day_of_work_week and holidays, if `workdays_only` is set
+fn filter_workdays(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    workdays_only: bool,
+) -> Vec<TrackingEvent> {
+    if !workdays_only {
+        return data.to_vec();
+    }
+    let last_day = settings.last_day_of_work_week.num_days_from_monday();
+    keep_sessions(data, |start| {
+        let day = work_day(settings, start.time(true));
+        day.weekday().num_days_from_monday() <= last_day && !is_holiday(settings, day)
+    })
+}
+
+/// keeps the sessions whose start matches `keep`. a removed session which ended the previous one
+/// by starting is replaced by a stop, so that the previous session keeps its end
+fn keep_sessions(
+    data: &[TrackingEvent],
+    keep: impl Fn(&TrackingEvent) -> bool,
+) -> Vec<TrackingEvent> {
+    let mut result: Vec<TrackingEvent> = Vec::new();
+    let mut skipping = false;
+    for event in data {
+        if event.is_start() {
+            skipping = !keep(event);
+            if !skipping {
+                result.push(event.clone());
+            } else if matches!(result.last(), Some(last) if last.is_start()) {
+                result.push(TrackingEvent::Stop(TrackingData::new(
+                    None,
+                    event.time(true),
+                )));
+            }
+        } else if !std::mem::replace(&mut skipping, false) {
+            result.push(event.clone());
+        }
+    }
+    result
+}
+
+/// removes every session with an excluded description, see `keep_sessions`
+fn exclude_sessions(data: Vec<TrackingEvent>, exclude: &[String]) -> Vec<TrackingEvent> {
+    if exclude.is_empty() {
+        return data;
+    }
+    keep_sessions(&data, |start| !is_excluded(&start.description(), exclude))
+}
+
+/// whether the event lies between `from` and `to`, both included. dates include the whole day
+fn is_in_range(
+    event: &TrackingEvent,
+    from: Option<DateOrDateTime>,
+    to: Option<DateOrDateTime>,
+    zone: Zone,
+) -> bool {
+    let after_from = match from {
+        None => true,
+        Some(DateOrDateTime::Date(from)) => {
+            event.time(true).timestamp_millis()
+                >= TimeZone::from_local_date(&zone, &from)
+                    .unwrap()
+                    .and_time(NaiveTime::from_hms(0, 0, 0))
+                    .expect("Failed to add time from date")
+                    .timestamp_millis()
+        }
+        Some(DateOrDateTime::DateTime(from)) => {
+            event.time(true).timestamp_millis()
+                >= TimeZone::from_local_datetime(&zone, &from)
+                    .unwrap()
+                    .timestamp_millis()
+        }
+    };
+    let before_to = match to {
+        None => true,
+        Some(DateOrDateTime::Date(to)) => {
+            event.time(true).timestamp_millis()
+                <= TimeZone::from_local_date(&zone, &to)
+                    .unwrap()
+                    .and_time(NaiveTime::from_hms(23, 59, 59))
+                    .expect("Failed to add time from date")
+                    .timestamp_millis()
+        }
+        Some(DateOrDateTime::DateTime(to)) => {
+            event.time(true).timestamp_millis()
+                <= TimeZone::from_local_datetime(&zone, &to)
+                    .unwrap()
+                    .timestamp_millis()
+        }
+    };
+    after_from && before_to
+}
+
+fn filter_events_in_range(
+    data: &[TrackingEvent],
+    filter: &Option<String>,
+    from: Option<DateOrDateTime>,
+    to: Option<DateOrDateTime>,
+    include_unlabeled: bool,
+    zone: Zone,
+) -> Vec<TrackingEvent> {
+    let data_iterator = data
+        .iter()
+        .filter(|entry| {
+            filter.clone().unwrap_or_default() == "all" || is_in_range(entry, from, to, zone)
+        })
+        .filter(|entry| match entry {
+            TrackingEvent::Start(TrackingData { description, .. })
+            | TrackingEvent::Stop(TrackingData { description, .. }) => match (&filter, description)
+            {
+                (Some(filter), Some(description)) => {
+                    filter == "all" || description.contains(filter)
+                }
+                (Some(filter), None) => filter == "all" || include_unlabeled,
+                (None, _) => true,
+            },
+        })
+        .skip_while(|entry| TrackingEvent::is_stop(entry));
+
+    data_iterator.cloned().collect()
+}
+
+/// returns the monday and sunday of the week containing the given date
+fn week_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let offset = i64::from(date.weekday().num_days_from_monday());
+    (
+        date - Duration::days(offset),
+        date + Duration::days(6 - offset),
+    )
+}
+
+/// the ISO week, which can belong to the previous or next year around new year
+fn iso_week_label(week: IsoWeek) -> String {
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+/// the events between `from` and `to` which pass the description filter, --exclude, --project and
+/// --tag. for the modes of show which select their own range
+fn filter_events_between(
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    from: DateOrDateTime,
+    to: DateOrDateTime,
+    zone: Zone,
+) -> Vec<TrackingEvent> {
+    // "week" and "all" select a range themselves, only a part of a description filters here
+    let description = filter
+        .filter
+        .clone()
+        .filter(|filter| filter != "week" && filter != "all");
+    let events = filter_events_in_range(
+        data,
+        &description,
+        Some(from),
+        Some(to),
+        filter.include_unlabeled,
+        zone,
+    );
+    filter_labels(
+        exclude_sessions(events, &filter.exclude),
+        &filter.project,
+        &filter.tag,
+    )
+}
+
+/// the total work time of every range. the description filter, --exclude, --project and --tag
+/// apply to all ranges
+fn get_range_totals(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    ranges: &[ShowRange],
+    include_seconds: bool,
+) -> Result<Vec<(String, Duration)>> {
+    ranges
+        .iter()
+        .map(|range| {
+            let events = filter_events_between(
+                data,
+                filter,
+                parse_date_or_date_time(&range.from, settings.zone)?,
+                parse_date_or_date_time(&range.to, settings.zone)?,
+                settings.zone,
+            );
+            Ok((
+                range.label.clone(),
+                get_time_from_events(settings, &events, include_seconds),
+            ))
+        })
+        .collect()
+}
+
+/// the total work time of the last `weeks` weeks, ending with the week containing `today`. the
+/// filters apply like for ranges
+fn get_weekly_totals(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    today: NaiveDate,
+    weeks: u32,
+    include_seconds: bool,
+) -> Vec<(IsoWeek, Duration)> {
+    (0..weeks)
+        .rev()
+        .map(|weeks_ago| {
+            let date = today - Duration::weeks(i64::from(weeks_ago));
+            let (monday, sunday) = week_bounds(date);
+            let events = filter_events_between(
+                data,
+                filter,
+                DateOrDateTime::Date(monday),
+                DateOrDateTime::Date(sunday),
+                settings.zone,
+            );
+            (
+                date.iso_week(),
+                get_time_from_events(settings, &events, include_seconds),
+            )
+        })
+        .collect()
+}
+
+/// the day the given time counts towards. days start at `day_start` instead of midnight.
+fn work_day(settings: &Settings, time: DateTime<Utc>) -> NaiveDate {
+    let since_midnight = settings.day_start - NaiveTime::from_hms(0, 0, 0);
+    (time.with_timezone(&settings.zone).naive_local() - since_midnight).date()
+}
+
+fn get_data_as_days(settings: &Settings, data: &[TrackingEvent]) -> Vec<Vec<TrackingEvent>> {
+    if data.is_empty() {
+        return vec![];
+    }
+
+    let mut current_day = work_day(
+        settings,
+        data.first().expect("Tracking event is empty").time(true),
+    );
+    let mut result = Vec::new();
+    let mut current = Vec::new();
+    for d in data {
+        let date = work_day(settings, d.time(true));
+        if current_day == date {
+            current.push(d.clone());
+        } else {
+            result.push(current);
+            current = vec![d.clone()];
+            current_day = date;
+        }
+    }
+    if !current.is_empty() {
+        result.push(current);
+    }
+    result
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Session {
+    start: DateTime<Utc>,
+    stop: Option<DateTime<Utc>>,
+    description: Option<String>,
+    labels: Labels,
+}
+
+impl Session {
+    /// the end of the session, or `now` if it is still running
+    fn end(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        self.stop.unwrap_or(now)
+    }
+}
+
+/// keeps the sessions overlapping the range and cuts them off at its edges.
+/// dates in "from" start at 00:00:00 and dates in "to" include the whole day
+fn clip_sessions(
+    sessions: Vec<Session>,
+    from: Option<DateOrDateTime>,
+    to: Option<DateOrDateTime>,
+    now: DateTime<Utc>,
+    zone: Zone,
+) -> Vec<Session> {
+    let from = from.map(|from| from.range_start(zone));
+    let to = to.map(|to| to.range_end(zone));
+
+    sessions
+        .into_iter()
+        .filter(|session| {
+            to.into_iter().all(|to| session.start < to)
+                && from.into_iter().all(|from| session.end(now) > from)
+        })
+        .map(|session| Session {
+            start: from.map_or(session.start, |from| session.start.max(from)),
+            stop: match (session.stop, to) {
+                (Some(stop), Some(to)) => Some(stop.min(to)),
+                (None, Some(to)) if now > to => Some(to),
+                (stop, _) => stop,
+            },
+            description: session.description,
+            labels: session.labels,
+        })
+        .collect()
+}
+
+fn is_unfiltered(filter: &FilterData) -> bool {
+    filter.from.is_none()
+        && filter.to.is_none()
+        && filter.filter.is_none()
+        && filter.exclude.is_empty()
+        && filter.project.is_none()
+        && filter.tag.is_empty()
+}
+
+/// the events for the json and readable exports, limited to the filter of the export
+fn get_export_events(
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    zone: Zone,
+) -> Result<Vec<TrackingEvent>> {
+    let mut events = if is_unfiltered(filter) {
+        data.to_vec()
+    } else {
+        filter_events(data, filter, zone)?
+    };
+    // the same data always exports the same file, even if it was edited out of order
+    events.sort_by_key(|e| e.time(true));
+    Ok(events)
+}
+
+/// the sessions for the org and csv exports, limited to the filter of the export
+fn get_export_sessions(
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    include_seconds: bool,
+    now: DateTime<Utc>,
+    zone: Zone,
+) -> Result<Vec<Session>> {
+    let data = filter_labels(data.to_vec(), &filter.project, &filter.tag);
+    let sessions: Vec<_> = get_sessions(&data, include_seconds)
+        .into_iter()
+        .filter(|session| !is_excluded(&session.description, &filter.exclude))
+        .collect();
+    if is_unfiltered(filter) {
+        return Ok(sessions);
+    }
+    let include_unlabeled = filter.include_unlabeled;
+    let (filter, from, to) = resolve_filter(&filter.from, &filter.to, &filter.filter, zone)?;
+    Ok(match filter {
+        Some(filter) if filter == "all" => sessions,
+        Some(filter) => clip_sessions(sessions, from, to, now, zone)
+            .into_iter()
+            .filter(|session| match &session.description {
+                Some(description) => description.contains(&filter),
+                None => include_unlabeled,
+            })
+            .collect(),
+        None => clip_sessions(sessions, from, to, now, zone),
+    })
+}
+
+/// the first and last date of the export filter, None if it is not limited
+fn get_export_dates(
+    filter: &FilterData,
+    zone: Zone,
+) -> Result<(Option<NaiveDate>, Option<NaiveDate>)> {
+    if is_unfiltered(filter) {
+        return Ok((None, None));
+    }
+    let date = |date: Option<DateOrDateTime>| match date {
+        Some(DateOrDateTime::Date(date)) => Some(date),
+        Some(DateOrDateTime::DateTime(time)) => Some(time.date()),
+        None => None,
+    };
+    Ok(
+        match resolve_filter(&filter.from, &filter.to, &filter.filter, zone)? {
+            (Some(filter), ..) if filter == "all" => (None, None),
+            (_, from, to) => (date(from), date(to)),
+        },
+    )
+}
+
+fn get_sessions(data: &[TrackingEvent], include_seconds: bool) -> Vec<Session> {
+    let mut data_iterator = data.iter();
+    let mut sessions = Vec::new();
+    while let Some(start) = data_iterator.find(|e| e.is_start()) {
+        let stop = data_iterator.find(|e| e.is_stop());
+        sessions.push(Session {
+            start: start.time(include_seconds),
+            stop: stop.map(|stop| stop.time(include_seconds)),
+            description: start.description(),
+            labels: start.labels().clone(),
+        });
+        if stop.is_none() {
+            break;
+        }
+    }
+    sessions
+}
+
+/// the sessions with their project or each of their tags as description, for grouping them with
+/// --group-by. sessions without a project or tag have no description
+fn group_sessions(sessions: Vec<Session>, group_by: GroupBy) -> Vec<Session> {
+    match group_by {
+        GroupBy::Description => sessions,
+        GroupBy::Project => sessions
+            .into_iter()
+            .map(|session| Session {
+                description: session.labels.project.clone(),
+                ..session
+            })
+            .collect(),
+        GroupBy::Tag => sessions
+            .into_iter()
+            .flat_map(|session| {
+                let tags = get_tags(&session.description, &session.labels);
+                let tags = iif!(
+                    tags.is_empty(),
+                    vec![None],
+                    tags.into_iter().map(Some).collect()
+                );
+                tags.into_iter().map(move |tag| Session {
+                    description: tag,
+                    ..session.clone()
+                })
+            })
+            .collect(),
+    }
+}
+
+/// the local time of `naive`, or the first one after it if the clocks skip it, e.g. when they are
+/// set forward at midnight. a time which happens twice resolves to the first one
+fn first_local_time_from(zone: Zone, naive: NaiveDateTime) -> DateTime<Zone> {
+    (0..=24 * 60)
+        .find_map(|minutes| {
+            zone.from_local_datetime(&(naive + Duration::minutes(minutes)))
+                .earliest()
+        })
+        .expect("the clocks never skip a whole day")
+}
+
+/// the start of the period after the one `time` lies in. the next hour is counted from `time`
+/// itself, so an hour which happens twice when the clocks are set back is split in two. days
+/// start at `day_start`
+fn next_boundary(time: DateTime<Zone>, period: Busiest, day_start: NaiveTime) -> DateTime<Zone> {
+    match period {
+        Busiest::Hour => {
+            let into_hour = Duration::minutes(time.minute().into())
+                + Duration::seconds(time.second().into())
+                + Duration::nanoseconds(time.nanosecond().into());
+            time - into_hour + Duration::hours(1)
+        }
+        Busiest::Weekday | Busiest::Day => {
+            let work_day = (time.naive_local() - (day_start - NaiveTime::from_hms(0, 0, 0))).date();
+            first_local_time_from(time.timezone(), work_day.succ().and_time(day_start))
+        }
+    }
+}
+
+/// splits the time between `start` and `end` at every boundary of the given period,
+/// so every part lies completely within one period.
+fn split_interval(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    period: Busiest,
+    day_start: NaiveTime,
+    zone: Zone,
+) -> Vec<(DateTime<Zone>, Duration)> {
+    let mut parts = Vec::new();
+    let end = end.with_timezone(&zone);
+    let mut current = start.with_timezone(&zone);
+    while current < end {
+        let next = next_boundary(current, period, day_start).min(end);
+        parts.push((current, next - current));
+        current = next;
+    }
+    parts
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Bucket {
+    Hour(u32),
+    Weekday(u32),
+    Day(NaiveDate),
+}
+
+impl std::fmt::Display for Bucket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hour(hour) => write!(f, "{:02}:00 - {:02}:00", hour, (hour + 1) % 24),
+            Self::Weekday(day) => {
+                let weekday = (0..*day).fold(Weekday::Mon, |weekday, _| weekday.succ());
+                write!(f, "{:?}", weekday)
+            }
+            Self::Day(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+        }
+    }
+}
+
+/// the time worked per period. days and weekdays are work days, which begin at day_start
+fn get_buckets(
+    settings: &Settings,
+    sessions: &[Session],
+    period: Busiest,
+    now: DateTime<Utc>,
+) -> BTreeMap<Bucket, Duration> {
+    let mut buckets = BTreeMap::new();
+    for session in sessions {
+        let parts = split_interval(
+            session.start,
+            session.end(now),
+            period,
+            settings.day_start,
+            settings.zone,
+        );
+        for (time, duration) in parts {
+            let day = work_day(settings, time.with_timezone(&Utc));
+            let key = match period {
+                Busiest::Hour => Bucket::Hour(time.hour()),
+                Busiest::Weekday => Bucket::Weekday(day.weekday().num_days_from_monday()),
+                Busiest::Day => Bucket::Day(day),
+            };
+            let bucket = buckets.entry(key).or_insert_with(Duration::zero);
+            *bucket = *bucket + duration;
+        }
+    }
+    buckets
+}
+
+/// the worked minutes for every day between `from` and `to` as "date,minutes", including days
+/// without any work. defaults to the first and last day with tracked time
+fn get_heatmap_lines(
+    settings: &Settings,
+    sessions: &[Session],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let days: BTreeMap<NaiveDate, Duration> = get_buckets(settings, sessions, Busiest::Day, now)
+        .into_iter()
+        .filter_map(|(bucket, duration)| match bucket {
+            Bucket::Day(date) => Some((date, duration)),
+            _ => None,
+        })
+        .collect();
+
+    let mut lines = vec!["date,minutes".to_string()];
+    let first = from.or_else(|| days.keys().next().copied());
+    let last = to.or_else(|| days.keys().next_back().copied());
+    if let (Some(mut date), Some(last)) = (first, last) {
+        while date <= last {
+            let minutes = days.get(&date).map(Duration::num_minutes).unwrap_or(0);
+            lines.push(format!("{},{}", date.format("%Y-%m-%d"), minutes));
+            date = date.succ();
+        }
+    }
+    lines
+}
+
+fn get_busiest(
+    settings: &Settings,
+    sessions: &[Session],
+    period: Busiest,
+    now: DateTime<Utc>,
+) -> Option<(Bucket, Duration)> {
+    let mut busiest: Option<(Bucket, Duration)> = None;
+    for (bucket, duration) in get_buckets(settings, sessions, period, now) {
+        match busiest {
+            Some((_, max)) if duration <= max => {}
+            _ => busiest = Some((bucket, duration)),
+        }
+    }
+    busiest
+}
+
+/// a table of the hours per description and ISO week of the start of each session, with a
+/// total column and row. descriptions are sorted by their total time, descending
+fn get_matrix(
+    settings: &Settings,
+    sessions: &[Session],
+    order: &SortOptions,
+    decimals: Decimals,
+    now: DateTime<Utc>,
+) -> Vec<Vec<String>> {
+    let mut times: BTreeMap<String, BTreeMap<String, Duration>> = BTreeMap::new();
+    let mut weeks: BTreeMap<String, Duration> = BTreeMap::new();
+    let mut first_starts: BTreeMap<String, DateTime<Utc>> = BTreeMap::new();
+    for session in sessions {
+        let description = session
+            .description
+            .clone()
+            .unwrap_or_else(|| settings.unlabeled_description.clone());
+        let first_start = first_starts
+            .entry(description.clone())
+            .or_insert(session.start);
+        *first_start = session.start.min(*first_start);
+        let week = iso_week_label(work_day(settings, session.start).iso_week());
+        let duration = session.end(now) - session.start;
+        let time = times
+            .entry(description)
+            .or_default()
+            .entry(week.clone())
+            .or_insert_with(Duration::zero);
+        *time = *time + duration;
+        let total = weeks.entry(week).or_insert_with(Duration::zero);
+        *total = *total + duration;
+    }
+    let sum = |times: &BTreeMap<String, Duration>| {
+        times
+            .values()
+            .fold(Duration::zero(), |total, time| total + *time)
+    };
+    let cell = |time: Option<&Duration>| match time {
+        Some(time) if !time.is_zero() => to_decimal_hours(*time, decimals, false),
+        _ => String::new(),
+    };
+
+    let total = "Total".to_string();
+    let mut rows: Vec<_> = times.iter().collect();
+    rows.sort_by(|(a, a_times), (b, b_times)| {
+        compare_summary_rows(
+            order,
+            (a, sum(a_times), first_starts[*a]),
+            (b, sum(b_times), first_starts[*b]),
+        )
+    });
+    rows.push((&total, &weeks));
+
+    let mut header = vec!["Description".to_string()];
+    header.extend(weeks.keys().cloned());
+    header.push(total.clone());
+    let mut table = vec![header];
+    for (description, times) in rows {
+        let mut row = vec![description.clone()];
+        row.extend(weeks.keys().map(|week| cell(times.get(week))));
+        row.push(cell(Some(&sum(times))));
+        table.push(row);
+    }
+    table
+}
+
+/// the daily goal, the work time and the difference in hours for every day from `from` to `to`,
+/// with a row of totals. `skip_empty` leaves out days without goal and work time
+fn get_goal_table(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    from: NaiveDate,
+    to: NaiveDate,
+    skip_empty: bool,
+    decimals: Decimals,
+) -> Vec<Vec<String>> {
+    let minutes = get_daily_minutes(settings, data);
+    let hours = |minutes: i64| to_decimal_hours(Duration::minutes(minutes), decimals, false);
+    let diff = |minutes: i64| iif!(minutes > 0, format!("+{}", hours(minutes)), hours(minutes));
+    let mut table = vec![vec![
+        "Date".to_string(),
+        "Goal".to_string(),
+        "Actual".to_string(),
+        "Diff".to_string(),
+    ]];
+    let (mut total_goal, mut total_actual) = (0, 0);
+    let mut date = from;
+    while date <= to {
+        let goal = get_daily_goal_minutes(settings, date);
+        let actual = minutes.get(&date).copied().unwrap_or(0);
+        if !(skip_empty && goal == 0 && actual == 0) {
+            let label = iif!(is_holiday(settings, date), " Holiday", "");
+            table.push(vec![
+                format!("{}{}", date.format("%Y-%m-%d %a"), label),
+                hours(goal),
+                hours(actual),
+                diff(actual - goal),
+            ]);
+        }
+        total_goal += goal;
+        total_actual += actual;
+        date = date.succ();
+    }
+    table.push(vec![
+        "Total".to_string(),
+        hours(total_goal),
+        hours(total_actual),
+        diff(total_actual - total_goal),
+    ]);
+    table
+}
+
+/// renders a table whose first row is the header. text aligns the columns, with the first one
+/// left aligned and the others right aligned. csv separates the fields with `delimiter` and
+/// writes the numbers after the first column with a decimal comma if `decimal_comma` is set
+fn render_table(
+    table: &[Vec<String>],
+    format: TableFormat,
+    delimiter: char,
+    decimal_comma: bool,
+) -> Vec<String> {
+    match format {
+        TableFormat::Text => {
+            let widths: Vec<_> = (0..table[0].len())
+                .map(|column| {
+                    table
+                        .iter()
+                        .map(|row| row[column].chars().count())
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+            table
+                .iter()
+                .map(|row| {
+                    let cells: Vec<_> = row
+                        .iter()
+                        .zip(&widths)
+                        .enumerate()
+                        .map(|(column, (cell, width))| {
+                            iif!(
+                                column == 0,
+                                format!("{:<width$}", cell, width = width),
+                                format!("{:>width$}", cell, width = width)
+                            )
+                        })
+                        .collect();
+                    cells.join("  ").trim_end().to_string()
+                })
+                .collect()
+        }
+        TableFormat::Csv => table
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        let is_number = column > 0 && cell.parse::<f64>().is_ok();
+                        let cell = iif!(
+                            decimal_comma && is_number,
+                            cell.replace('.', ","),
+                            cell.to_string()
+                        );
+                        to_csv_field(&cell, delimiter)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&delimiter.to_string())
+            })
+            .collect(),
+        TableFormat::Markdown => {
+            let line = |row: &[String]| format!("| {} |", row.join(" | "));
+            let separator: Vec<_> = (0..table[0].len())
+                .map(|column| iif!(column == 0, "---", "---:").to_string())
+                .collect();
+            std::iter::once(line(&table[0]))
+                .chain(std::iter::once(line(&separator)))
+                .chain(table[1..].iter().map(|row| line(row)))
+                .collect()
+        }
+    }
+}
+
+/// the order of two rows of the summary, given as description, total time and first start.
+/// rows which are equal in the sort order are ordered by description
+fn compare_summary_rows(
+    order: &SortOptions,
+    a: (&str, Duration, DateTime<Utc>),
+    b: (&str, Duration, DateTime<Utc>),
+) -> std::cmp::Ordering {
+    let by_description = a.0.cmp(b.0);
+    let ordering = match order.sort {
+        SummarySort::Duration => b.1.cmp(&a.1).then(by_description),
+        SummarySort::Time => a.2.cmp(&b.2).then(by_description),
+        SummarySort::Description => by_description,
+    };
+    iif!(order.reverse, ordering.reverse(), ordering)
+}
+
+/// the time per description in the given order. descriptions below `min_percent` of the total
+/// time are merged into a last row "Other"
+fn get_summary(
+    sessions: &[Session],
+    unlabeled: &str,
+    min_percent: Option<f64>,
+    order: &SortOptions,
+    now: DateTime<Utc>,
+) -> Vec<(String, Duration)> {
+    let mut times: BTreeMap<String, (Duration, DateTime<Utc>)> = BTreeMap::new();
+    for session in sessions {
+        let description = session
+            .description
+            .clone()
+            .unwrap_or_else(|| unlabeled.to_string());
+        let (time, first_start) = times
+            .entry(description)
+            .or_insert((Duration::zero(), session.start));
+        *time = *time + (session.end(now) - session.start);
+        *first_start = session.start.min(*first_start);
+    }
+    let total = times
+        .values()
+        .fold(Duration::zero(), |total, (time, _)| total + *time);
+
+    let mut rows = Vec::new();
+    let mut other = (0, Duration::zero());
+    for (description, (time, first_start)) in times {
+        let percent = time.num_seconds() as f64 * 100.0 / total.num_seconds().max(1) as f64;
+        match min_percent {
+            Some(min_percent) if percent < min_percent => {
+                other = (other.0 + 1, other.1 + time);
+            }
+            _ => rows.push((description, time, first_start)),
+        }
+    }
+    rows.sort_by(|a, b| compare_summary_rows(order, (&a.0, a.1, a.2), (&b.0, b.1, b.2)));
+    let mut rows: Vec<_> = rows
+        .into_iter()
+        .map(|(description, time, _)| (description, time))
+        .collect();
+    if other.0 > 0 {
+        rows.push((format!("Other ({} tasks)", other.0), other.1));
+    }
+    rows
+}
+
+/// the rows of the summary as a table with the time in seconds and hours and its share of the
+/// total time
+fn get_summary_table(rows: &[(String, Duration)], decimals: Decimals) -> Vec<Vec<String>> {
+    let total = rows
+        .iter()
+        .fold(Duration::zero(), |total, (_, time)| total + *time);
+    let mut table = vec![vec![
+        "description".to_string(),
+        "seconds".to_string(),
+        "hours".to_string(),
+        "percent".to_string(),
+    ]];
+    for (description, time) in rows {
+        let percent = time.num_seconds() as f64 * 100.0 / total.num_seconds().max(1) as f64;
+        table.push(vec![
+            description.clone(),
+            time.num_seconds().to_string(),
+            decimals.format(time.num_seconds() as f64 / 3600.0),
+            decimals.format(percent),
+        ]);
+    }
+    table
+}
+
+/// adds two durations, saturating at the largest or smallest duration instead of overflowing
+fn saturating_add(a: Duration, b: Duration) -> Duration {
+    a.checked_add(&b).unwrap_or_else(|| {
+        iif!(
+            b < Duration::zero(),
+            Duration::min_value(),
+            Duration::max_value()
+        )
+    })
+}
+
+/// subtracts `b` from `a`, saturating at the largest or smallest duration instead of overflowing
+fn saturating_sub(a: Duration, b: Duration) -> Duration {
+    a.checked_sub(&b).unwrap_or_else(|| {
+        iif!(
+            b > Duration::zero(),
+            Duration::min_value(),
+            Duration::max_value()
+        )
+    })
+}
+
+fn get_time_from_day(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    include_seconds: bool,
+) -> Duration {
+    let mut data_iterator = data.iter();
+    let mut work_day = Duration::zero();
+    let mut first = None;
+    let mut last = None;
+    loop {
+        let start = data_iterator.find(|e| e.is_start());
+        let stop = data_iterator.find(|e| e.is_stop());
+        match (start, stop) {
+            (Some(start), Some(stop)) => {
+                if first.is_none() {
+                    first = Some(start.time(include_seconds));
+                }
+                last = Some(stop.time(include_seconds));
+                let duration = stop.time(include_seconds) - start.time(include_seconds);
+                work_day = saturating_add(work_day, duration);
+            }
+            (Some(start), None) => {
+                if first.is_none() {
+                    first = Some(start.time(include_seconds));
+                }
+                let now = if include_seconds {
+                    now()
+                } else {
+                    now().with_second(0).unwrap()
+                };
+                last = Some(now);
+                let mut duration = now - start.time(include_seconds);
+                if settings.subtract_suspend {
+                    let periods = suspend::suspended_periods(start.time(true));
+                    let suspended = suspend::suspended_duration(&periods, start.time(true), now);
+                    duration = saturating_sub(duration, suspended);
+                }
+                work_day = saturating_add(work_day, duration);
+                break;
+            }
+            (_, _) => break,
+        }
+    }
+    if settings.min_daily_break > 0 {
+        let now = now();
+        let total = last.unwrap_or(now) - first.unwrap_or(now);
+        let pause = saturating_sub(total, work_day);
+        let min_break_duration = Duration::minutes(i64::from(settings.min_daily_break));
+        if pause > Duration::zero() && pause < min_break_duration {
+            let difference = min_break_duration - pause;
+            work_day = saturating_sub(work_day, difference);
+        }
+    }
+    work_day.max(Duration::zero())
+}
+
+fn get_time_from_events(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    include_seconds: bool,
+) -> Duration {
+    let days = get_data_as_days(settings, data);
+    let mut time = Duration::zero();
+    for day in days {
+        let time_for_day = get_time_from_day(settings, &day, include_seconds);
+        time = saturating_add(time, time_for_day);
+    }
+    time
+}
+
+fn get_configured_goal_minutes(settings: &Settings, filter: &str) -> i64 {
+    let time_goal = if filter == "week" {
+        &settings.time_goal.weekly
+    } else {
+        &settings.time_goal.daily
+    };
+    i64::from(time_goal.minutes) + (i64::from(time_goal.hours) * 60)
+}
+
+fn is_holiday(settings: &Settings, date: NaiveDate) -> bool {
+    settings.holidays.contains(&date)
+}
+
+/// the goal of the day, or with filter "week" of the week, containing `date`. a holiday has no
+/// goal and every holiday in the work week lowers the weekly goal by the daily goal
+fn get_goal_minutes(settings: &Settings, filter: &str, date: NaiveDate) -> i64 {
+    let daily = get_configured_goal_minutes(settings, "");
+    if filter == "week" {
+        let (monday, _) = week_bounds(date);
+        let last_day = settings.last_day_of_work_week.num_days_from_monday();
+        let holidays = (0..=i64::from(last_day))
+            .filter(|days| is_holiday(settings, monday + Duration::days(*days)))
+            .count() as i64;
+        (get_configured_goal_minutes(settings, "week") - holidays * daily).max(0)
+    } else {
+        iif!(is_holiday(settings, date), 0, daily)
+    }
+}
+
+fn get_remaining_minutes(
+    settings: &Settings,
+    filter: &str,
+    date: NaiveDate,
+    hours: i64,
+    minutes: i64,
+) -> i64 {
+    let total = minutes + (hours * 60);
+    get_goal_minutes(settings, filter, date) - total
+}
+
+/// the remaining minutes of the day, which can neither exceed the daily nor the weekly remaining
+/// minutes and are zero once a goal is met
+fn get_binding_remaining_minutes(daily_remaining: i64, weekly_remaining: i64) -> i64 {
+    daily_remaining.min(weekly_remaining).max(0)
+}
+
+/// the daily goal for the given date. days after last_day_of_work_week and holidays have no goal
+fn get_daily_goal_minutes(settings: &Settings, date: NaiveDate) -> i64 {
+    let last_day = settings.last_day_of_work_week.num_days_from_monday();
+    iif!(
+        date.weekday().num_days_from_monday() <= last_day,
+        get_goal_minutes(settings, "", date),
+        0
+    )
+}
+
+/// the first and last day of the range of the filter. "all" ranges from the first to the last entry
+fn get_range_dates(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &FilterData,
+) -> Result<Option<(NaiveDate, NaiveDate)>> {
+    let date = |date: DateOrDateTime| match date {
+        DateOrDateTime::Date(date) => date,
+        DateOrDateTime::DateTime(time) => time.date(),
+    };
+    Ok(
+        match resolve_filter(&filter.from, &filter.to, &filter.filter, settings.zone)? {
+            (Some(filter), ..) if filter == "all" => match (data.first(), data.last()) {
+                (Some(first), Some(last)) => Some((
+                    work_day(settings, first.time(true)),
+                    work_day(settings, last.time(true)),
+                )),
+                _ => None,
+            },
+            (_, Some(from), Some(to)) => Some((date(from), date(to))),
+            _ => None,
+        },
+    )
+}
+
+/// the work time per day with tracked time in `data`, or with `Calendar` per day from the first to
+/// the last day of `range`, which defaults to the tracked days. cut off at the second, and zero
+/// without any days
+fn get_average(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    work_time: Duration,
+    over: AverageOver,
+    range: Option<(NaiveDate, NaiveDate)>,
+) -> Duration {
+    let active_days = get_daily_minutes(settings, data);
+    let range = range.or_else(|| {
+        let first = active_days.keys().next()?;
+        let last = active_days.keys().next_back()?;
+        Some((*first, *last))
+    });
+    let days = match (over, range) {
+        (AverageOver::Calendar, Some((from, to))) => (to - from).num_days() + 1,
+        (AverageOver::Calendar, None) => 0,
+        (AverageOver::Active, _) => active_days.len() as i64,
+    };
+    Duration::seconds(work_time.num_seconds() / days.max(1))
+}
+
+/// the work time as ratio to a full-time work day on every day from `from` to `to` with a daily
+/// goal. None if none of the days has a goal
+fn get_fte(
+    settings: &Settings,
+    work_time: Duration,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Option<f64> {
+    let day_minutes =
+        i64::from(settings.fte_day_length.hours) * 60 + i64::from(settings.fte_day_length.minutes);
+    let mut work_days = 0;
+    let mut date = from;
+    while date <= to {
+        if get_daily_goal_minutes(settings, date) > 0 {
+            work_days += 1;
+        }
+        date = date.succ();
+    }
+    let full_time = work_days * day_minutes;
+    iif!(
+        full_time > 0,
+        Some(work_time.num_seconds() as f64 / 60.0 / full_time as f64),
+        None
+    )
+}
+
+/// the periods within the work hours of the gaps setting on the days from `from` to `to` with a
+/// daily goal, in which nothing was tracked and which are longer than gaps.min_minutes.
+/// work hours after `now` don't count
+fn get_gaps(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    from: NaiveDate,
+    to: NaiveDate,
+    now: DateTime<Utc>,
+) -> Vec<(DateTime<Zone>, DateTime<Zone>)> {
+    let sessions = get_sessions(data, true);
+    let min_length = Duration::minutes(i64::from(settings.gaps.min_minutes));
+    let local = |date: NaiveDate, time| {
+        settings
+            .zone
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|time| time.with_timezone(&Utc))
+    };
+    let mut gaps = Vec::new();
+    let mut date = from;
+    while date <= to {
+        let day = date;
+        date = date.succ();
+        if get_daily_goal_minutes(settings, day) == 0 {
+            continue;
+        }
+        let (mut cursor, end) = match (
+            local(day, settings.gaps.start),
+            local(day, settings.gaps.end),
+        ) {
+            (Some(start), Some(end)) => (start, end.min(now)),
+            _ => continue,
+        };
+        for session in &sessions {
+            if session.start >= end {
+                break;
+            }
+            if session.end(now) <= cursor {
+                continue;
+            }
+            if session.start > cursor {
+                gaps.push((cursor, session.start));
+            }
+            cursor = session.end(now);
+        }
+        if end > cursor {
+            gaps.push((cursor, end));
+        }
+    }
+    gaps.into_iter()
+        .filter(|(start, end)| *end - *start > min_length)
+        .map(|(start, end)| {
+            (
+                start.with_timezone(&settings.zone),
+                end.with_timezone(&settings.zone),
+            )
+        })
+        .collect()
+}
+
+/// the surplus (positive) or deficit (negative) of the days in the week before `today`
+fn get_carryover_minutes(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    today: NaiveDate,
+    include_seconds: bool,
+) -> i64 {
+    let (monday, _) = week_bounds(today);
+    let mut balance = 0;
+    let mut date = monday;
+    while date < today {
+        let events = filter_events_in_range(
+            data,
+            &None,
+            Some(DateOrDateTime::Date(date)),
+            Some(DateOrDateTime::Date(date)),
+            false,
+            settings.zone,
+        );
+        let worked = get_time_from_events(settings, &events, include_seconds).num_minutes();
+        balance += worked - get_daily_goal_minutes(settings, date);
+        date = date.succ();
+    }
+    balance
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct GoalStatus {
+    worked: i64,
+    goal: i64,
+    remaining: i64,
+    percentage: i64,
+}
+
+/// the payload for notify. the text is sent as "text" and "content", so it shows up in Slack
+/// and Discord webhooks. all times are in minutes.
+#[derive(Debug, Serialize)]
+struct Notification {
+    text: String,
+    content: String,
+    today: GoalStatus,
+    week: GoalStatus,
+}
+
+fn get_goal_status(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> GoalStatus {
+    let events = filter_events_in_range(
+        data,
+        &None,
+        Some(DateOrDateTime::Date(from)),
+        Some(DateOrDateTime::Date(to)),
+        false,
+        settings.zone,
+    );
+    let worked = get_time_from_events(settings, &events, false).num_minutes();
+    GoalStatus {
+        worked,
+        goal: get_goal_minutes(settings, filter, from),
+        remaining: get_remaining_minutes(settings, filter, from, 0, worked).max(0),
+        percentage: get_goal_percentage(settings, filter, from, 0, worked),
+    }
+}
+
+fn get_notification(settings: &Settings, data: &[TrackingEvent], today: NaiveDate) -> Notification {
+    let (monday, sunday) = week_bounds(today);
+    let today = get_goal_status(settings, data, "", today, today);
+    let week = get_goal_status(settings, data, "week", monday, sunday);
+    let minutes = |minutes: i64| format!("{}:{:02}", minutes / 60, minutes % 60);
+    let text = settings
+        .notify
+        .template
+        .replace("{today_worked}", &minutes(today.worked))
+        .replace("{today_goal}", &minutes(today.goal))
+        .replace("{today_remaining}", &minutes(today.remaining))
+        .replace("{today_pct}", &today.percentage.to_string())
+        .replace("{week_worked}", &minutes(week.worked))
+        .replace("{week_goal}", &minutes(week.goal))
+        .replace("{week_remaining}", &minutes(week.remaining))
+        .replace("{week_pct}", &week.percentage.to_string());
+    Notification {
+        content: text.clone(),
+        text,
+        today,
+        week,
+    }
+}
+
+#[cfg(feature = "webhook")]
+fn send_notification(url: &str, notification: &Notification) -> Result<()> {
+    if url.is_empty() {
+        anyhow::bail!("notify.url is not set. Use --stdout to print the notification instead.");
+    }
+    let response = ureq::post(url).send_json(serde_json::to_value(notification)?);
+    if let Some(error) = response.synthetic_error() {
+        anyhow::bail!("Could not send the notification: {}", error);
+    }
+    if response.error() {
+        anyhow::bail!("The webhook responded with status {}", response.status());
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "webhook"))]
+fn send_notification(_url: &str, _notification: &Notification) -> Result<()> {
+    anyhow::bail!(
+        "tt was built without webhook support. Use --stdout to print the notification instead."
+    )
+}
+
+/// the worked minutes of every tracked day
+fn get_daily_minutes(settings: &Settings, data: &[TrackingEvent]) -> BTreeMap<NaiveDate, i64> {
+    get_data_as_days(settings, data)
+        .iter()
+        .map(|day| {
+            (
+                work_day(settings, day[0].time(true)),
+                get_time_from_day(settings, day, false).num_minutes(),
+            )
+        })
+        .collect()
+}
+
+/// the work time per local hour of the day. sessions which span several hours are split at the
+/// full hours
+fn get_hourly_work_time(sessions: &[Session], now: DateTime<Utc>, zone: Zone) -> [Duration; 24] {
+    let mut hours = [Duration::zero(); 24];
+    for session in sessions {
+        let end = session.end(now);
+        let mut start = session.start;
+        while start < end {
+            let next_hour = round_to_boundary(start, 60, false, zone) + Duration::hours(1);
+            let hour = start.with_timezone(&zone).hour() as usize;
+            hours[hour] = hours[hour] + (next_hour.min(end) - start);
+            start = next_hour;
+        }
+    }
+    hours
+}
+
+/// one line per hour of the day with its work time, and a bar as long as the work time relative
+/// to the longest hour if `chart` is set
+fn get_hourly_lines(hours: &[Duration; 24], chart: bool) -> Vec<String> {
+    const WIDTH: i64 = 40;
+    let longest = hours.iter().max().copied().unwrap_or_else(Duration::zero);
+    hours
+        .iter()
+        .enumerate()
+        .map(|(hour, time)| {
+            let bar = match longest.num_seconds() {
+                seconds if chart && seconds > 0 => format!(
+                    " {:<width$}",
+                    "#".repeat((time.num_seconds() * WIDTH / seconds) as usize),
+                    width = WIDTH as usize
+                ),
+                _ => String::new(),
+            };
+            format!("{:02}:00{} {}", hour, bar, format_duration(*time))
+        })
+        .collect()
+}
+
+/// the statistics of `stats --focus`
+#[derive(Debug, PartialEq)]
+struct Focus {
+    days: usize,
+    sessions: usize,
+    average_session: Duration,
+    switches: usize,
+    descriptions_per_day: f64,
+}
+
+/// how fragmented the sessions are. a switch is a session with another description than the
+/// session before it on the same work day
+fn get_focus(settings: &Settings, sessions: &[Session], now: DateTime<Utc>) -> Focus {
+    let mut descriptions: BTreeMap<NaiveDate, Vec<&Option<String>>> = BTreeMap::new();
+    let mut switches = 0;
+    let mut previous: Option<(NaiveDate, &Option<String>)> = None;
+    for session in sessions {
+        let day = work_day(settings, session.start);
+        if let Some((previous_day, description)) = previous {
+            if previous_day == day && *description != session.description {
+                switches += 1;
+            }
+        }
+        previous = Some((day, &session.description));
+        let day = descriptions.entry(day).or_default();
+        if !day.contains(&&session.description) {
+            day.push(&session.description);
+        }
+    }
+    let total = sessions.iter().fold(Duration::zero(), |total, session| {
+        total + (session.end(now) - session.start)
+    });
+    let distinct: usize = descriptions.values().map(Vec::len).sum();
+    Focus {
+        days: descriptions.len(),
+        sessions: sessions.len(),
+        average_session: total / (sessions.len() as i32).max(1),
+        switches,
+        descriptions_per_day: distinct as f64 / (descriptions.len() as f64).max(1.0),
+    }
+}
+
+/// the current and the longest streak of days which met the daily goal. today only counts once
+/// the goal is met, but doesn't break the current streak before that
+fn get_streaks(settings: &Settings, data: &[TrackingEvent], today: NaiveDate) -> (u32, u32) {
+    let minutes = get_daily_minutes(settings, data);
+    let mut date = match minutes.keys().next() {
+        Some(first) => *first,
+        None => return (0, 0),
+    };
+    let (mut current, mut longest) = (0, 0);
+    while date <= today {
+        let goal = get_daily_goal_minutes(settings, date);
+        let met = minutes.get(&date).copied().unwrap_or(0) >= goal;
+        match settings.streak_free_days {
+            StreakFreeDays::Skip if goal == 0 => {}
+            _ if met => {
+                current += 1;
+                longest = longest.max(current);
+            }
+            _ if date != today => current = 0,
+            _ => {}
+        }
+        date = date.succ();
+    }
+    (current, longest)
+}
+
+fn get_goal_percentage(
+    settings: &Settings,
+    filter: &str,
+    date: NaiveDate,
+    hours: i64,
+    minutes: i64,
+) -> i64 {
+    let total = minutes + (hours * 60);
+    let required = get_goal_minutes(settings, filter, date);
+    if required == 0 {
+        return 100;
+    }
+    total * 100 / required
+}
+
+fn pluralize(count: i64, unit: &str) -> String {
+    format!("{} {}{}", count, unit, iif!(count == 1, "", "s"))
+}
+
+/// puts a minus in front of the first time placeholder of a "show --format", so a negative time
+/// reads "Worked -01h 30m" instead of "-Worked 01h 30m"
+fn negate_format(format: &str) -> String {
+    let first = ["{hh}", "{mm}", "{ss}", "{h}", "{m}", "{s}"]
+        .iter()
+        .filter_map(|placeholder| format.find(placeholder))
+        .min();
+    match first {
+        Some(index) => format!("{}-{}", &format[..index], &format[index..]),
+        None => format.to_string(),
+    }
+}
+
+fn format_preset(
+    preset: Preset,
+    decimals: Decimals,
+    hours: u64,
+    minutes: u64,
+    seconds: u64,
+) -> String {
+    match preset {
+        Preset::Clock => format!("{:02}:{:02}:{:02}", hours, minutes, seconds),
+        Preset::Compact => format!("{}h{:02}", hours, minutes),
+        Preset::Decimal => decimals.format((hours * 3600 + minutes * 60 + seconds) as f64 / 3600.0),
+        Preset::Words => format!(
+            "{} {}",
+            pluralize(hours as i64, "hour"),
+            pluralize(minutes as i64, "minute")
+        ),
+    }
+}
+
+fn show(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    options: &ShowOptions,
+    decimals: Decimals,
+) -> Result<()> {
+    let ShowOptions {
+        filter,
+        plain,
+        remaining,
+        goal,
+        include_seconds,
+        format,
+        preset,
+        busiest,
+        weeks,
+        rolling,
+        range,
+        fte,
+        target,
+        average,
+        round_remaining,
+        ..
+    } = options;
+    let (plain, remaining, include_seconds) = (*plain, *remaining, *include_seconds);
+    let FilterData {
+        from, to, filter, ..
+    } = filter;
+    if let Some(weeks) = *weeks {
+        let today = today(settings.zone).naive_local();
+        for (week, time) in get_weekly_totals(
+            settings,
+            data,
+            &options.filter,
+            today,
+            weeks,
+            include_seconds,
+        ) {
+            if plain {
+                println!("{} {}", iso_week_label(week), format_duration(time));
+            } else {
+                println!(
+                    "Work Time {}: {}",
+                    iso_week_label(week),
+                    format_duration(time)
+                );
+            }
+        }
+        return Ok(());
+    }
+    if !range.is_empty() {
+        for (label, time) in
+            get_range_totals(settings, data, &options.filter, range, include_seconds)?
+        {
+            let time = format_duration(iif!(include_seconds, time, truncate_to_minutes(time)));
+            if plain {
+                println!("{} {}", label, time);
+            } else {
+                println!("Work Time {}: {}", label, time);
+            }
+        }
+        return Ok(());
+    }
+    let filtered_data = match *rolling {
+        Some(window) => get_rolling_events(
+            data,
+            &options.filter,
+            window,
+            now().with_timezone(&settings.zone).naive_local(),
+            settings.zone,
+        ),
+        None => filter_events(data, &options.filter, settings.zone)?,
+    };
+    if let Some(period) = *busiest {
+        let sessions = get_sessions(&filtered_data, include_seconds);
+        match get_busiest(settings, &sessions, period, now()) {
+            Some((bucket, duration)) => {
+                let time = format_duration(duration);
+                if plain {
+                    println!("{} {}", bucket, time);
+                } else {
+                    println!("Busiest {:?}: {} ({})", period, bucket, time);
+                }
+            }
+            None => eprintln!("No tracked time found!"),
+        }
+        return Ok(());
+    }
+    let work_time = get_time_from_events(settings, &filtered_data, include_seconds);
+    let work_time = iif!(include_seconds, work_time, truncate_to_minutes(work_time));
+
+    let filter = filter.clone().unwrap_or_default();
+    let format = format
+        .clone()
+        .unwrap_or_else(|| "{hh}:{mm}:{ss}".to_string());
+    let uses_goal = format.contains("{pct}") || format.contains("{goal}");
+    let has_goal = (filter == "week" || filter.is_empty()) && from.is_none() && to.is_none();
+    if uses_goal && !has_goal {
+        anyhow::bail!(
+            "{} and {} only work when \"from\" and \"to\" are not set and with no filter or \
+             filter \"week\"",
+            "{pct}",
+            "{goal}"
+        );
+    }
+    let today = today(settings.zone).naive_local();
+    let worked = work_time.num_minutes();
+    let percentage = get_goal_percentage(settings, &filter, today, 0, worked);
+    let goal_minutes = get_goal_minutes(settings, &filter, today);
+
+    let mut shown = work_time;
+    if let Some(over) = *average {
+        let range = get_range_dates(settings, &filtered_data, &options.filter)?;
+        let over = over.unwrap_or(AverageOver::Active);
+        let average = get_average(settings, &filtered_data, work_time, over, range);
+        shown = iif!(include_seconds, average, truncate_to_minutes(average));
+    }
+    if remaining {
+        if let Some(goal) = goal {
+            shown = Duration::minutes(get_goal_period_remaining_minutes(
+                settings,
+                data,
+                *goal,
+                today,
+                include_seconds,
+            ));
+        } else if has_goal {
+            shown = Duration::minutes(get_show_remaining_minutes(
+                settings,
+                data,
+                &filter,
+                worked,
+                include_seconds,
+                *target,
+            )?);
+        } else {
+            eprintln!("Remaining only works when \"from\" and \"to\" are not set and with no filter or filter \"week\"");
+            return Ok(());
+        }
+        if let Some(step) = *round_remaining {
+            shown = Duration::minutes(round_remaining_minutes(shown.num_minutes(), step));
+        }
+    }
+    let (hours, minutes, seconds, negative) = split_duration(shown);
+    let time = match preset {
+        Some(preset) => format!(
+            "{}{}",
+            iif!(negative, "-", ""),
+            format_preset(*preset, decimals, hours, minutes, seconds)
+        ),
+        None => iif!(negative, negate_format(&format), format)
+            .replace("{hh}", &format!("{:02}", hours))
+            .replace("{mm}", &format!("{:02}", minutes))
+            .replace("{ss}", &format!("{:02}", seconds))
+            .replace("{h}", &format!("{}", hours))
+            .replace("{m}", &format!("{}", minutes))
+            .replace("{s}", &format!("{}", seconds))
+            .replace("{pct}", &format!("{}", percentage))
+            .replace(
+                "{goal}",
+                &format!("{}:{:02}", goal_minutes / 60, goal_minutes % 60),
+            ),
+    };
+    let week = match rolling {
+        Some(window) => format!(" (last {})", format_window(*window)),
+        None if filter == "week" => {
+            format!(" {}", iso_week_label(today.iso_week()))
+        }
+        None => String::new(),
+    };
+    if *fte {
+        let fte = get_range_dates(settings, data, &options.filter)?
+            .and_then(|(from, to)| get_fte(settings, work_time, from, to));
+        match fte {
+            Some(fte) if plain => println!("{}", decimals.format(fte)),
+            Some(fte) => println!("FTE{}: {}", week, decimals.format(fte)),
+            None => eprintln!("There are no days with a daily goal in the range!"),
+        }
+        return Ok(());
+    }
+    if plain {
+        println!("{}", time);
+    } else if remaining {
+        println!("Remaining Work Time{}: {}", week, time);
+    } else if average.is_some() {
+        println!("Average Work Time{}: {}", week, time);
+    } else {
+        println!("Work Time{}: {}", week, time);
+    }
+
+    Ok(())
+}
+
+/// rounds the remaining minutes to the nearest multiple of `step`, halves away from zero. the
+/// result has the same sign, or is zero
+fn round_remaining_minutes(remaining: i64, step: i64) -> i64 {
+    remaining.signum() * ((remaining.abs() + step / 2) / step * step)
+}
+
+/// the remaining minutes for "show --remaining" of the day or, with filter "week", the week, when
+/// `worked` minutes are done. `target` replaces the configured goals
+fn get_show_remaining_minutes(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    filter: &str,
+    worked: i64,
+    include_seconds: bool,
+    target: Option<Duration>,
+) -> Result<i64> {
+    if let Some(target) = target {
+        return Ok((target.num_minutes() - worked).max(0));
+    }
+    let today = today(settings.zone).naive_local();
+    let mut remaining_minutes = get_remaining_minutes(settings, filter, today, 0, worked);
+    if settings.carryover && filter != "week" {
+        remaining_minutes -= get_carryover_minutes(settings, data, today, include_seconds);
+    }
+
+    if filter != "week" {
+        let filtered_data_week = filter_events(
+            data,
+            &FilterData {
+                filter: Some("week".to_string()),
+                ..FilterData::default()
+            },
+            settings.zone,
+        )?;
+        let week_work_time = get_time_from_events(settings, &filtered_data_week, include_seconds);
+        let remaining_minutes_week =
+            get_remaining_minutes(settings, "week", today, 0, week_work_time.num_minutes());
+        remaining_minutes =
+            get_binding_remaining_minutes(remaining_minutes, remaining_minutes_week);
+    }
+
+    Ok(remaining_minutes.max(0))
+}
+
+/// the remaining minutes of the goal of `period` for "show --remaining --goal", with the work time
+/// of the day, week or month containing `today`. the daily goal includes the carryover
+fn get_goal_period_remaining_minutes(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    period: GoalPeriod,
+    today: NaiveDate,
+    include_seconds: bool,
+) -> i64 {
+    let (from, to) = match period {
+        GoalPeriod::Daily => (today, today),
+        GoalPeriod::Weekly => week_bounds(today),
+        GoalPeriod::Monthly => {
+            let first = today.with_day(1).expect("every month has a first day");
+            let next = NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+                .unwrap_or_else(|| NaiveDate::from_ymd(first.year() + 1, 1, 1));
+            (first, next.pred())
+        }
+    };
+    let events = filter_events_in_range(
+        data,
+        &None,
+        Some(DateOrDateTime::Date(from)),
+        Some(DateOrDateTime::Date(to)),
+        true,
+        settings.zone,
+    );
+    let worked = get_time_from_events(settings, &events, include_seconds).num_minutes();
+    let goal = match period {
+        GoalPeriod::Daily => get_goal_minutes(settings, "", today),
+        GoalPeriod::Weekly => get_goal_minutes(settings, "week", today),
+        GoalPeriod::Monthly => from
+            .iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| get_daily_goal_minutes(settings, date))
+            .sum(),
+    };
+    let carryover = iif!(
+        settings.carryover && period == GoalPeriod::Daily,
+        get_carryover_minutes(settings, data, today, include_seconds),
+        0
+    );
+    (goal - worked - carryover).max(0)
+}
+
+/// the events between `now - window` and `now`, filtered like the other events of show
+fn get_rolling_events(
+    data: &[TrackingEvent],
+    filter: &FilterData,
+    window: Duration,
+    now: NaiveDateTime,
+    zone: Zone,
+) -> Vec<TrackingEvent> {
+    filter_events_between(
+        data,
+        filter,
+        DateOrDateTime::DateTime(now - window),
+        DateOrDateTime::DateTime(now),
+        zone,
+    )
+}
+
+/// formats the length of a rolling window like it is given, e.g. "7d" or "1d12h"
+fn format_window(window: Duration) -> String {
+    let (hours, minutes, seconds, _) = split_duration(window);
+    let parts = [
+        (hours / 24, "d"),
+        (hours % 24, "h"),
+        (minutes, "m"),
+        (seconds, "s"),
+    ];
+    parts
+        .iter()
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect()
+}
+
+fn cleanup(data: &[TrackingEvent], time_format: &str, zone: Zone) -> Vec<TrackingEvent> {
+    let mut cleaned = Vec::with_capacity(data.len());
+
+    let mut conflicting = Vec::new();
+
+    let mut is_start = None;
+
+    let mut all_conflicting = Vec::new();
+
+    for e in data {
+        match is_start {
+            None => {
+                is_start = Some(e.is_start());
+                cleaned.push(e);
+            }
+            Some(true) => {
+                if e.is_start() {
+                    if conflicting.is_empty() {
+                        if let Some(last_cleaned_e) = cleaned.pop() {
+                            conflicting.push(last_cleaned_e);
+                        }
+                    }
+                    conflicting.push(e);
+                } else {
+                    if !conflicting.is_empty() {
+                        all_conflicting.push(conflicting);
+                        conflicting = Vec::new();
+                    }
+                    cleaned.push(e);
+                    is_start.replace(false);
+                }
+            }
+            Some(false) => {
+                if e.is_stop() {
+                    if conflicting.is_empty() {
+                        if let Some(last_cleaned_e) = cleaned.pop() {
+                            conflicting.push(last_cleaned_e);
+                        }
+                    }
+                    conflicting.push(e);
+                } else {
+                    if !conflicting.is_empty() {
+                        all_conflicting.push(conflicting);
+                        conflicting = Vec::new();
+                    }
+                    cleaned.push(e);
+                    is_start.replace(true);
+                }
+            }
+        }
+    }
+
+    for mut conflicting in all_conflicting {
+        let event_type = iif!(
+            conflicting
+                .first()
+                .expect("Nothing first tracking event founded")
+                .is_start(),
+            "start",
+            "stop"
+        );
+        println!("Repeated {} events found:", event_type);
+        for (i, event) in conflicting.iter().enumerate() {
+            println!(
+                "({}) {}",
+                i,
+                to_human_readable(
+                    &format!("S{}", &event_type[1..]),
+                    &event.time(true).with_timezone(&zone),
+                    event.description(),
+                    time_format,
+                )
+            );
+        }
+        loop {
+            println!();
+            println!("Please enter the number of the entry to keep (<num>|skip) [default: skip]: ");
+            let mut input = String::new();
+            match io::stdin().read_line(&mut input) {
+                Ok(_) => {
+                    let text = input.trim();
+                    if text == "skip" || text.is_empty() {
+                        cleaned.append(&mut conflicting);
+                        break;
+                    } else {
+                        let parsed: Result<usize, _> = text.parse();
+                        match parsed {
+                            Ok(n) => match conflicting.get(n) {
+                                Some(value) => {
+                                    cleaned.push(value);
+                                    break;
+                                }
+                                None => println!("Please use one of the numbers given above!"),
+                            },
+                            Err(_) => println!("Could not parse number!"),
+                        }
+                    }
+                }
+                Err(_) => println!("Could not read from stdin!"),
+            }
+        }
+    }
+
+    cleaned.iter().map(Clone::clone).cloned().collect()
+}
+
+#[cfg(feature = "binary")]
+/// a json value as it is named in the messages about invalid events
+fn describe_json_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "null".to_string(),
+        serde_json::Value::Bool(value) => value.to_string(),
+        serde_json::Value::Number(number) => number.to_string(),
+        serde_json::Value::String(text) => format!("the string \"{}\"", text),
+        serde_json::Value::Array(_) => "a list".to_string(),
+        serde_json::Value::Object(_) => "an object".to_string(),
+    }
+}
+
+#[cfg(feature = "binary")]
+/// the event of an imported json value. the error names the field which doesn't fit and what it
+/// should be, as the errors of serde only say that no variant of the event matched
+fn parse_json_event(value: serde_json::Value) -> std::result::Result<TrackingEvent, String> {
+    let (kind, data) = match value.as_object() {
+        Some(object) if object.len() == 1 => object.iter().next().expect("there is one entry"),
+        _ => {
+            return Err(format!(
+                "expected an object with \"Start\" or \"Stop\", found {}",
+                describe_json_value(&value)
+            ))
+        }
+    };
+    if kind != "Start" && kind != "Stop" {
+        return Err(format!(
+            "unknown kind \"{}\", expected \"Start\" or \"Stop\"",
+            kind
+        ));
+    }
+    let data = data.as_object().ok_or_else(|| {
+        format!(
+            "\"{}\" should be an object, found {}",
+            kind,
+            describe_json_value(data)
+        )
+    })?;
+    if !data.contains_key("time") {
+        return Err(format!("field \"{}.time\" is missing", kind));
+    }
+    type IsValid = fn(&serde_json::Value) -> bool;
+    let fields: [(&str, &str, IsValid); 4] = [
+        (
+            "time",
+            "an integer of seconds since the unix epoch",
+            |value| value.is_i64(),
+        ),
+        ("description", "a string or null", |value| {
+            value.is_null() || value.is_string()
+        }),
+        ("project", "a string or null", |value| {
+            value.is_null() || value.is_string()
+        }),
+        (
+            "tags",
+            "a list of strings",
+            |value| matches!(value.as_array(), Some(tags) if tags.iter().all(serde_json::Value::is_string)),
+        ),
+    ];
+    for (field, expected, is_valid) in fields.iter() {
+        match data.get(*field) {
+            Some(value) if !is_valid(value) => {
+                return Err(format!(
+                    "field \"{}.{}\" should be {}, found {}",
+                    kind,
+                    field,
+                    expected,
+                    describe_json_value(value)
+                ))
+            }
+            _ => {}
+        }
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "binary")]
+/// reads the events of json data one by one. returns the valid events and, for every invalid
+/// one, its position in the list with the reason
+fn parse_json_events_leniently(contents: &str) -> Result<(Vec<TrackingEvent>, Vec<String>)> {
+    let entries = match serde_json::from_str(contents)? {
+        serde_json::Value::Array(entries) => entries,
+        serde_json::Value::Object(mut object) => match object.remove("events") {
+            Some(serde_json::Value::Array(entries)) => entries,
+            _ => anyhow::bail!("the object has no list of \"events\""),
+        },
+        _ => anyhow::bail!("expected a list of events or an object with \"events\""),
+    };
+    let mut events = Vec::new();
+    let mut failures = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        match parse_json_event(entry) {
+            Ok(event) => events.push(event),
+            Err(e) => failures.push(format!("entry {}: {}", index + 1, e)),
+        }
+    }
+    Ok((events, failures))
+}
+
+#[cfg(feature = "binary")]
+/// guesses the format of an import file from its first line. fails if no format or more than one
+/// fits, so --format has to be given
+fn detect_import_format(contents: &str) -> Result<ImportFormat> {
+    let first_line = contents
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let with_events = matches!(
+        serde_json::from_str(contents),
+        Ok(serde_json::Value::Object(object)) if object.contains_key("events")
+    );
+    let header = first_line.to_lowercase();
+    let guesses: Vec<_> = [
+        (
+            ImportFormat::Json,
+            first_line.starts_with('[') || with_events,
+        ),
+        (
+            ImportFormat::Ndjson,
+            first_line.starts_with('{') && first_line.ends_with('}') && !with_events,
+        ),
+        (
+            ImportFormat::Csv,
+            header.contains(',') && header.split(',').any(|field| field.trim() == "start"),
+        ),
+        (
+            ImportFormat::Readable,
+            first_line.starts_with("Start at ") || first_line.starts_with("Stop  at "),
+        ),
+    ]
+    .iter()
+    .filter(|(_, fits)| *fits)
+    .map(|(format, _)| *format)
+    .collect();
+    match guesses[..] {
+        [format] => Ok(format),
+        [] => {
+            anyhow::bail!("could not detect the format. use --format json, ndjson, csv or readable")
+        }
+        _ => anyhow::bail!(
+            "the file could be {}. choose one with --format",
+            guesses
+                .iter()
+                .map(|format| format.name())
+                .collect::<Vec<_>>()
+                .join(" or ")
+        ),
+    }
+}
+
+#[cfg(feature = "binary")]
+/// the events of a file with one json event per line, and a failure per invalid line
+fn parse_ndjson_events(contents: &str) -> (Vec<TrackingEvent>, Vec<String>) {
+    let mut events = Vec::new();
+    let mut failures = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line)
+            .map_err(|e| e.to_string())
+            .and_then(parse_json_event)
+        {
+            Ok(event) => events.push(event),
+            Err(e) => failures.push(format!("line {}: {}", index + 1, e)),
+        }
+    }
+    (events, failures)
+}
+
+#[cfg(feature = "binary")]
+/// the fields of a csv line, the counterpart of `to_csv_field`. quoted fields can't span lines
+fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().expect("there is always a field");
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(String::new()),
+            c => field.push(c),
+        }
+    }
+    fields
+}
+
+#[cfg(feature = "binary")]
+/// the events of a csv file with a header, like the csv export. the columns start, stop and
+/// description are read, in any order, the others are ignored
+fn parse_csv_events(contents: &str, zone: Zone) -> Result<(Vec<TrackingEvent>, Vec<String>)> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = lines.next().map(|(_, line)| line).unwrap_or_default();
+    let header: Vec<_> = split_csv_line(header, ',')
+        .iter()
+        .map(|field| field.trim().to_lowercase())
+        .collect();
+    let column = |name| header.iter().position(|field| field == name);
+    let start = column("start").context("the csv header has no column \"start\"")?;
+    let (stop, description) = (column("stop"), column("description"));
+    let mut events = Vec::new();
+    let mut failures = Vec::new();
+    for (index, line) in lines {
+        let fields = split_csv_line(line, ',');
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| fields.get(column))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let time =
+            |field: &str| parse_date_or_date_time(field, zone).map(|time| time.range_start(zone));
+        let session = match field(Some(start)) {
+            Some(start) => time(start).and_then(|start| {
+                let stop = field(stop).map(time).transpose()?;
+                Ok((start, stop))
+            }),
+            None => Err(anyhow::anyhow!("the start is empty")),
+        };
+        match session {
+            Ok((start, stop)) => {
+                events.push(TrackingEvent::Start(TrackingData::new(
+                    field(description).map(str::to_string),
+                    start,
+                )));
+                events.extend(stop.map(|time| TrackingEvent::Stop(TrackingData::new(None, time))));
+            }
+            Err(e) => failures.push(format!("line {}: {}", index + 1, e)),
+        }
+    }
+    Ok((events, failures))
+}
+
+#[cfg(feature = "binary")]
+/// the events of a readable export, with the times written in `time_format`
+fn parse_readable_events(
+    contents: &str,
+    time_format: &str,
+    zone: Zone,
+) -> (Vec<TrackingEvent>, Vec<String>) {
+    let parse_line = |line: &str| -> Result<TrackingEvent> {
+        let (is_start, rest) = match (
+            line.strip_prefix("Start at "),
+            line.strip_prefix("Stop  at "),
+        ) {
+            (Some(rest), _) => (true, rest),
+            (_, Some(rest)) => (false, rest),
+            _ => anyhow::bail!("expected \"Start at\" or \"Stop  at\""),
+        };
+        let (time, description) = match rest.split_once(" \"") {
+            Some((time, description)) => (
+                time,
+                Some(
+                    description
+                        .strip_suffix('"')
+                        .unwrap_or(description)
+                        .to_string(),
+                ),
+            ),
+            None => (rest, None),
+        };
+        let time = match DateTime::parse_from_str(time, time_format) {
+            Ok(time) => time.with_timezone(&Utc),
+            Err(_) => NaiveDateTime::parse_from_str(time, time_format)
+                .map(|time| DateOrDateTime::DateTime(time).range_start(zone))
+                .with_context(|| {
+                    format!(
+                        "\"{}\" doesn't match list_time_format \"{}\"",
+                        time, time_format
+                    )
+                })?,
+        };
+        let data = TrackingData::new(description, time);
+        Ok(iif!(
+            is_start,
+            TrackingEvent::Start(data),
+            TrackingEvent::Stop(data)
+        ))
+    };
+    let mut events = Vec::new();
+    let mut failures = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(event) => events.push(event),
+            Err(e) => failures.push(format!("line {}: {}", index + 1, e)),
+        }
+    }
+    (events, failures)
+}
+
+#[cfg(feature = "binary")]
+/// the events of an import file and a failure for every entry which could not be read
+fn parse_import_events(
+    contents: &str,
+    format: ImportFormat,
+    time_format: &str,
+    zone: Zone,
+) -> Result<(Vec<TrackingEvent>, Vec<String>)> {
+    match format {
+        ImportFormat::Json => parse_json_events_leniently(contents),
+        ImportFormat::Ndjson => Ok(parse_ndjson_events(contents)),
+        ImportFormat::Csv => parse_csv_events(contents, zone),
+        ImportFormat::Readable => Ok(parse_readable_events(contents, time_format, zone)),
+    }
+}
+
+#[cfg(feature = "binary")]
+/// the entries after the import. merging sorts all entries and removes duplicates
+fn import_events(
+    data: &[TrackingEvent],
+    imported: Vec<TrackingEvent>,
+    mode: ImportMode,
+) -> Vec<TrackingEvent> {
+    match mode {
+        ImportMode::Replace => imported,
+        ImportMode::Merge => {
+            let mut merged = data.to_vec();
+            merged.extend(imported);
+            merged.sort_by_key(|e| e.time(true));
+            merged.dedup();
+            merged
+        }
+    }
+}
+
+#[cfg(feature = "binary")]
+/// sorts the events chronologically and returns a warning for every event that directly
+/// follows an event of the same kind, as the pairing of starts and stops relies on both.
+fn sort_and_validate(data: &mut [TrackingEvent], time_format: &str, zone: Zone) -> Vec<String> {
+    data.sort_by_key(|e| e.time(true));
+    data.windows(2)
+        .filter(|pair| pair[0].is_start() == pair[1].is_start())
+        .map(|pair| {
+            let event = &pair[1];
+            format!(
+                "Repeated {} (run cleanup to resolve)",
+                to_human_readable(
+                    iif!(event.is_start(), "start", "stop"),
+                    &event.time(true).with_timezone(&zone),
+                    event.description(),
+                    time_format,
+                )
+            )
+        })
+        .collect()
+}
+
+/// the single line for shell prompts, None if the time tracking is not active
+fn get_prompt_line(
+    prompt_format: &str,
+    data: &[TrackingEvent],
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let event = data.last().filter(|event| event.is_start())?;
+    let elapsed = format_elapsed(now - event.time(true));
+    let line = prompt_format
+        .replace("{elapsed}", &elapsed)
+        .replace("{description}", &event.description().unwrap_or_default());
+    Some(line.trim().to_string())
+}
+
+/// how long the time tracking has been stopped, None if it is active or there are no entries
+fn get_idle(data: &[TrackingEvent], now: DateTime<Utc>) -> Option<Duration> {
+    let event = data.last().filter(|event| event.is_stop())?;
+    Some((now - event.time(true)).max(Duration::zero()))
+}
+
+/// the line for shell prompts while the time tracking is stopped. None if it is active or the
+/// format is empty
+fn get_idle_line(idle_format: &str, data: &[TrackingEvent], now: DateTime<Utc>) -> Option<String> {
+    let idle = get_idle(data, now)?;
+    let line = idle_format.replace("{idle}", &format_elapsed(idle));
+    Some(line.trim().to_string()).filter(|line| !line.is_empty())
+}
+
+/// a duration as h:mm
+fn format_elapsed(elapsed: Duration) -> String {
+    format!("{}:{:02}", elapsed.num_hours(), elapsed.num_minutes() % 60)
+}
+
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// the characters and colors of the descriptions in the timeline, in the order they appear
+const TIMELINE_SYMBOLS: [char; 8] = ['#', '=', '*', '+', '%', '@', '&', '~'];
+const TIMELINE_COLORS: [&str; 6] = [GREEN, "\x1b[34m", YELLOW, "\x1b[35m", "\x1b[36m", RED];
+
+fn use_color(no_color: bool) -> bool {
+    !no_color && std::env::var_os("NO_COLOR").is_none() && atty::is(atty::Stream::Stdout)
+}
+
+/// the width of the terminal as given by $COLUMNS, or 80 columns
+fn columns_from_env() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(80)
+}
+
+/// the width of the terminal, or of $COLUMNS if tt doesn't run in one
+#[cfg(feature = "tui")]
+fn terminal_width() -> usize {
+    crossterm::terminal::size().map_or_else(|_| columns_from_env(), |(columns, _)| columns.into())
+}
+
+/// the terminal can only be queried with the tui feature
+#[cfg(not(feature = "tui"))]
+fn terminal_width() -> usize {
+    columns_from_env()
+}
+
+/// the shortest slice for which the timeline of a day and its borders fit into `width` columns
+fn get_timeline_slice(width: usize) -> u32 {
+    [5, 10, 15, 20, 30]
+        .iter()
+        .copied()
+        .find(|slice| (24 * 60 / slice) as usize + 2 <= width)
+        .unwrap_or(60)
+}
+
+/// the lines of the timeline of `day`: the hours, the bar with a character per slice of `slice`
+/// minutes and a legend with the character and work time of every description
+fn get_timeline(
+    settings: &Settings,
+    data: &[TrackingEvent],
+    day: NaiveDate,
+    slice: u32,
+    color: bool,
+    now: DateTime<Utc>,
+) -> Vec<String> {
+    let sessions = clip_sessions(
+        get_sessions(data, true),
+        Some(DateOrDateTime::Date(day)),
+        Some(DateOrDateTime::Date(day)),
+        now,
+        settings.zone,
+    );
+    let mut descriptions: Vec<(String, Duration)> = Vec::new();
+    for session in &sessions {
+        let description = session
+            .description
+            .clone()
+            .unwrap_or_else(|| settings.unlabeled_description.clone());
+        let duration = session.end(now) - session.start;
+        match descriptions
+            .iter_mut()
+            .find(|(known, _)| *known == description)
+        {
+            Some((_, total)) => *total = *total + duration,
+            None => descriptions.push((description, duration)),
+        }
+    }
+    let symbol = |index: usize| {
+        let symbol = TIMELINE_SYMBOLS[index % TIMELINE_SYMBOLS.len()];
+        if color {
+            let color = TIMELINE_COLORS[index % TIMELINE_COLORS.len()];
+            format!("{}{}{}", color, symbol, RESET)
+        } else {
+            symbol.to_string()
+        }
+    };
+
+    let slices = (24 * 60 / slice) as usize;
+    let midnight = DateOrDateTime::Date(day).range_start(settings.zone);
+    let mut bar = String::from("|");
+    for index in 0..slices {
+        let start = midnight + Duration::minutes(i64::from(slice) * index as i64);
+        let end = start + Duration::minutes(i64::from(slice));
+        let mut tracked = vec![Duration::zero(); descriptions.len()];
+        for session in &sessions {
+            let overlap = session.end(now).min(end) - session.start.max(start);
+            if overlap > Duration::zero() {
+                let description = session
+                    .description
+                    .as_ref()
+                    .unwrap_or(&settings.unlabeled_description);
+                let position = descriptions
+                    .iter()
+                    .position(|(known, _)| known == description)
+                    .expect("every description is known");
+                tracked[position] = tracked[position] + overlap;
+            }
+        }
+        let longest =
+            (0..tracked.len()).max_by_key(|position| (tracked[*position], -(*position as i64)));
+        match longest {
+            Some(position) if tracked[position] * 2 >= end - start => bar += &symbol(position),
+            _ => bar.push(' '),
+        }
+    }
+    bar.push('|');
+
+    // every label starts above the first slice of its hour, only "24" ends at the border
+    let mut hours = vec![' '; slices + 2];
+    for hour in (0..24).step_by(6).chain(std::iter::once(24)) {
+        let column = (1 + hour * 60 / slice as usize).min(slices);
+        for (offset, c) in format!("{:02}", hour).chars().enumerate() {
+            hours[column + offset] = c;
+        }
+    }
+    let mut lines = vec![
+        hours.into_iter().collect::<String>().trim_end().to_string(),
+        bar,
+    ];
+    lines.extend(
+        descriptions
+            .iter()
+            .enumerate()
+            .map(|(index, (description, total))| {
+                format!(
+                    "{} {} {}",
+                    symbol(index),
+                    format_duration(*total),
+                    description
+                )
+            }),
+    );
+    lines
+}
+
+/// reads the data file every `interval` until the time tracking is active, or inactive if
+/// `active` is false. returns false if `timeout` passes first. the file is read again for every
+/// check, so starts and stops of other tt processes are seen. `sleep` waits between the checks
+fn wait_for_state(
+    path: &Path,
+    active: bool,
+    interval: Duration,
+    timeout: Option<Duration>,
+    mut sleep: impl FnMut(std::time::Duration),
+) -> Result<bool> {
+    let mut waited = Duration::zero();
+    loop {
+        let data = iif!(path.exists(), read_data(path)?, Vec::new());
+        if matches!(data.last(), Some(TrackingEvent::Start(_))) == active {
+            return Ok(true);
+        }
+        if matches!(timeout, Some(timeout) if waited >= timeout) {
+            return Ok(false);
+        }
+        let step = timeout.map_or(interval, |timeout| interval.min(timeout - waited));
+        sleep(step.to_std().expect("the interval is not negative"));
+        waited = waited + step;
+    }
+}
+
+/// green once the daily goal is met, yellow while the time tracking is running towards it and
+/// red while it is stopped before reaching it
+fn get_goal_color(settings: &Settings, data: &[TrackingEvent], today: NaiveDate) -> &'static str {
+    let worked = get_daily_minutes(settings, data)
+        .get(&today)
+        .copied()
+        .unwrap_or(0);
+    let active = matches!(data.last(), Some(TrackingEvent::Start(_)));
+    if worked >= get_daily_goal_minutes(settings, today) {
+        GREEN
+    } else if active {
+        YELLOW
+    } else {
+        RED
+    }
+}
+
+/// prints the state of the time tracking. `clock_format` is the strftime format of the time of
+/// the last entry
+fn status(data: &[TrackingEvent], color: Option<&str>, clock_format: &str, zone: Zone) {
+    if let Some(event) = data.last() {
+        let time = event.time(true).with_timezone(&zone);
+        let active = event.is_start();
+        let text = iif!(active, "Start", "End");
+        let active_text = match color {
+            Some(color) => format!("{}{}{}", color, active, RESET),
+            None => active.to_string(),
+        };
+        println!("Active: {}", active_text);
+        if let Some(description) = event.description() {
+            println!("Description: {}", description,);
+        }
+        println!("{} Time: {}", text, time.format(clock_format));
+        if let Some(idle) = get_idle(data, now()) {
+            println!("Idle: {}", format_elapsed(idle));
+        }
+        std::process::exit(iif!(active, 0, EXIT_INACTIVE));
+    } else {
+        println!("No Events found!");
+        std::process::exit(EXIT_INACTIVE);
+    }
+}
+
+fn to_human_readable<Tz: TimeZone>(
+    prefix: &str,
+    time: &DateTime<Tz>,
+    description: Option<String>,
+    time_format: &str,
+) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let description = description
+        .map(|d| format!(" \"{}\"", d))
+        .unwrap_or_default();
+    format!("{} at {}{}", prefix, time.format(time_format), description)
+}
+
+fn get_human_readable(data: &[TrackingEvent], time_format: &str, zone: Zone) -> Vec<String> {
+    data.iter()
+        .map(|event| match event {
+            TrackingEvent::Start(TrackingData {
+                time, description, ..
+            }) => to_human_readable(
+                "Start",
+                &time.with_timezone(&zone),
+                description.clone(),
+                time_format,
+            ),
+            TrackingEvent::Stop(TrackingData {
+                time, description, ..
+            }) => to_human_readable(
+                "Stop ",
+                &time.with_timezone(&zone),
+                description.clone(),
+                time_format,
+            ),
+        })
+        .collect::<Vec<_>>()
+}
+
+/// a time in the JSON output, either as RFC 3339 or as seconds since the unix epoch
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+enum JsonTime {
+    Rfc3339(String),
+    Epoch(i64),
+}
+
+/// an event as printed by "list --json"
+#[derive(Debug, PartialEq, Serialize)]
+struct ListEntry {
+    kind: &'static str,
+    time: JsonTime,
+    description: Option<String>,
+}
+
+fn get_list_entries(data: &[TrackingEvent], epoch: bool, zone: Zone) -> Vec<ListEntry> {
+    data.iter()
+        .map(|event| {
+            let time = event.time(true);
+            ListEntry {
+                kind: iif!(event.is_start(), "start", "stop"),
+                time: iif!(
+                    epoch,
+                    JsonTime::Epoch(time.timestamp()),
+                    JsonTime::Rfc3339(time.with_timezone(&zone).to_rfc3339())
+                ),
+                description: event.description(),
+            }
+        })
+        .collect()
+}
+
+/// keeps only the starts or the stops for "list --only"
+fn get_list_events(data: Vec<TrackingEvent>, only: Option<ListOnly>) -> Vec<TrackingEvent> {
+    match only {
+        Some(ListOnly::Starts) => data.into_iter().filter(TrackingEvent::is_start).collect(),
+        Some(ListOnly::Stops) => data.into_iter().filter(TrackingEvent::is_stop).collect(),
+        Some(ListOnly::Sessions) | None => data,
+    }
+}
+
+/// the entries of the page, counted from 1, and the footer like "page 2/7 (312 entries)". pages
+/// after the last one are empty
+fn get_page<T>(entries: Vec<T>, page: usize, per_page: usize) -> (Vec<T>, String) {
+    let total = entries.len();
+    let pages = iif!(total == 0, 1, (total - 1) / per_page + 1);
+    let footer = format!("page {}/{} ({} entries)", page, pages, total);
+    let entries = entries
+        .into_iter()
+        .skip((page - 1).saturating_mul(per_page))
+        .take(per_page)
+        .collect();
+    (entries, footer)
+}
+
+/// the entries in the order and of the page of `list`, and the footer if it has pages
+fn paginate<T>(
+    mut entries: Vec<T>,
+    reverse: bool,
+    page: Option<usize>,
+    per_page: Option<usize>,
+) -> (Vec<T>, Option<String>) {
+    if reverse {
+        entries.reverse();
+    }
+    if page.is_none() && per_page.is_none() {
+        return (entries, None);
+    }
+    let (entries, footer) = get_page(entries, page.unwrap_or(1), per_page.unwrap_or(50));
+    (entries, Some(footer))
+}
+
+/// the human readable entries which are only in `data`, prefixed with "-", and only in `other`,
+/// prefixed with "+", sorted by time
+fn get_diff(
+    data: &[TrackingEvent],
+    other: &[TrackingEvent],
+    time_format: &str,
+    zone: Zone,
+) -> Vec<String> {
+    let key = |event: &TrackingEvent| (event.time(true), !event.is_start(), event.description());
+    let sorted = |data: &[TrackingEvent]| {
+        let mut data = data.to_vec();
+        data.sort_by_key(key);
+        data
+    };
+    let (data, other) = (sorted(data), sorted(other));
+    let line = |prefix, event: &TrackingEvent| {
+        format!(
+            "{}{}",
+            prefix,
+            get_human_readable(std::slice::from_ref(event), time_format, zone)[0]
+        )
+    };
+    let (mut data, mut other) = (data.iter().peekable(), other.iter().peekable());
+    let mut lines = Vec::new();
+    loop {
+        let order = match (data.peek(), other.peek()) {
+            (None, None) => return lines,
+            (Some(a), Some(b)) => key(a).cmp(&key(b)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+        };
+        match order {
+            std::cmp::Ordering::Less => lines.push(line("-", data.next().unwrap())),
+            std::cmp::Ordering::Greater => lines.push(line("+", other.next().unwrap())),
+            std::cmp::Ordering::Equal => {
+                data.next();
+                other.next();
+            }
+        }
+    }
+}
+
+/// one line per session with its duration. a running session is shown as ongoing
+fn get_session_lines(
+    data: &[TrackingEvent],
+    time_format: &str,
+    now: DateTime<Utc>,
+    zone: Zone,
+) -> Vec<String> {
+    get_sessions(data, true)
+        .into_iter()
+        .map(|session| {
+            let format_time =
+                |time: DateTime<Utc>| time.with_timezone(&zone).format(time_format).to_string();
+            let description = session
+                .description
+                .as_ref()
+                .map(|d| format!(" \"{}\"", d))
+                .unwrap_or_default();
+            format!(
+                "{} - {} ({}){}",
+                format_time(session.start),
+                session
+                    .stop
+                    .map_or_else(|| "ongoing".to_string(), format_time),
+                format_duration(session.end(now) - session.start),
+                description
+            )
+        })
+        .collect()
+}
+
+fn export_human_readable(
+    path: String,
+    data: &[TrackingEvent],
+    time_format: &str,
+    line_ending: LineEnding,
+    zone: Zone,
+) -> io::Result<()> {
+    let lines = get_human_readable(data, time_format, zone);
+    std::fs::write(path, join_lines(&lines, line_ending))
+}
+
+/// the lines of an exported text file, each ending with `line_ending`
+fn join_lines(lines: &[String], line_ending: LineEnding) -> String {
+    lines
+        .iter()
+        .map(|line| format!("{}{}", line, line_ending.as_str()))
+        .collect()
+}
+
+fn to_org_timestamp(time: DateTime<Utc>, zone: Zone) -> String {
+    time.with_timezone(&zone)
+        .format("[%Y-%m-%d %a %H:%M]")
+        .to_string()
+}
+
+fn to_org_clock(session: &Session, zone: Zone) -> String {
+    match session.stop {
+        Some(stop) => {
+            let minutes = (stop - session.start).num_minutes();
+            format!(
+                "CLOCK: {}--{} => {:>2}:{:02}",
+                to_org_timestamp(session.start, zone),
+                to_org_timestamp(stop, zone),
+                minutes / 60,
+                minutes % 60
+            )
+        }
+        None => format!("CLOCK: {}", to_org_timestamp(session.start, zone)),
+    }
+}
+
+fn get_org_clocks(
+    sessions: &[Session],
+    headlines: bool,
+    unlabeled: &str,
+    zone: Zone,
+) -> Vec<String> {
+    if !headlines {
+        return sessions
+            .iter()
+            .map(|session| to_org_clock(session, zone))
+            .collect();
+    }
+
+    let mut descriptions: Vec<Option<String>> = Vec::new();
+    for session in sessions {
+        if !descriptions.contains(&session.description) {
+            descriptions.push(session.description.clone());
+        }
+    }
+    let mut lines = Vec::new();
+    for description in descriptions {
+        lines.push(format!("* {}", description.as_deref().unwrap_or(unlabeled)));
+        lines.push(":LOGBOOK:".to_string());
+        lines.extend(
+            sessions
+                .iter()
+                .filter(|session| session.description == description)
+                .map(|session| to_org_clock(session, zone)),
+        );
+        lines.push(":END:".to_string());
+    }
+    lines
+}
+
+/// quotes the field if it contains the delimiter, quotes or line breaks
+fn to_csv_field(field: &str, delimiter: char) -> String {
+    if field.contains(&[delimiter, '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// how decimal numbers are printed: the decimal places from --precision and the rounding_mode
+#[derive(Debug, Clone, Copy)]
+struct Decimals {
+    precision: usize,
+    rounding: RoundingMode,
+}
+
+impl Default for Decimals {
+    fn default() -> Self {
+        Self {
+            precision: 2,
+            rounding: RoundingMode::HalfUp,
+        }
+    }
+}
+
+impl Decimals {
+    fn format(self, value: f64) -> String {
+        let factor = 10f64.powi(self.precision as i32);
+        // drop the error of the binary representation first, so that e.g. 0.145 is a half
+        let scaled = (value * factor * 1e6).round() / 1e6;
+        let rounded = match self.rounding {
+            RoundingMode::HalfUp => scaled.round(),
+            RoundingMode::HalfEven => {
+                let rounded = scaled.round();
+                let is_half = (scaled - scaled.trunc()).abs() == 0.5;
+                iif!(
+                    is_half && rounded % 2.0 != 0.0,
+                    rounded - scaled.signum(),
+                    rounded
+                )
+            }
+            RoundingMode::Floor => scaled.floor(),
+            RoundingMode::Ceil => scaled.ceil(),
+        };
+        format!("{:.*}", self.precision, rounded / factor)
+    }
+}
+
+fn to_decimal_hours(duration: Duration, decimals: Decimals, decimal_comma: bool) -> String {
+    let hours = decimals.format(duration.num_seconds() as f64 / 3600.0);
+    iif!(decimal_comma, hours.replace('.', ","), hours)
+}
+
+const TEMPLATE_EACH_START: &str = "{{#each sessions}}";
+const TEMPLATE_EACH_END: &str = "{{/each}}";
+const TEMPLATE_SESSION_FIELDS: [&str; 5] = ["start", "stop", "duration", "minutes", "description"];
+const TEMPLATE_SUMMARY_FIELDS: [&str; 2] = ["total", "count"];
+
+/// fails on a {{placeholder}} which isn't one of `names`, e.g. a typo. a "{" right before a
+/// placeholder is kept as it is, like in "\textbf{{{description}}}"
+fn check_placeholders(text: &str, names: &[&str], part: &str) -> Result<()> {
+    let mut rest = text;
+    while let Some(mut start) = rest.find("{{") {
+        while rest[start..].starts_with("{{{") {
+            start += 1;
+        }
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => anyhow::bail!("a {} in the template isn't closed with {}", "{{", "}}"),
+        };
+        let name = &rest[start + 2..end];
+        if !names.contains(&name) {
+            anyhow::bail!("unknown placeholder {{{{{}}}}} {}", name, part);
+        }
+        rest = &rest[end + 2..];
+    }
+    Ok(())
+}
+
+/// renders the export template. a line break directly after the start and the end of the
+/// {{#each sessions}} block is dropped, so the block can be written on lines of its own. fails
+/// on unknown placeholders and on more than one block
+fn render_template(
+    template: &str,
+    sessions: &[Session],
+    time_format: &str,
+    now: DateTime<Utc>,
+    zone: Zone,
+) -> Result<String> {
+    if template.matches("{{#each").count() > 1 || template.matches(TEMPLATE_EACH_END).count() > 1 {
+        anyhow::bail!(
+            "the template can only have one {} block",
+            TEMPLATE_EACH_START
+        );
+    }
+    let (header, body, footer) = match (
+        template.find(TEMPLATE_EACH_START),
+        template.find(TEMPLATE_EACH_END),
+    ) {
+        (Some(start), Some(end)) if start < end => (
+            &template[..start],
+            &template[start + TEMPLATE_EACH_START.len()..end],
+            &template[end + TEMPLATE_EACH_END.len()..],
+        ),
+        (None, None) => ("", template, ""),
+        _ => anyhow::bail!(
+            "the {} block of the template isn't closed with {}",
+            TEMPLATE_EACH_START,
+            TEMPLATE_EACH_END
+        ),
+    };
+    let body = body.strip_prefix('\n').unwrap_or(body);
+    let footer = footer.strip_prefix('\n').unwrap_or(footer);
+    let outside = "outside of the {{#each sessions}} block";
+    check_placeholders(header, &TEMPLATE_SUMMARY_FIELDS, outside)?;
+    check_placeholders(body, &TEMPLATE_SESSION_FIELDS, "in the template")?;
+    check_placeholders(footer, &TEMPLATE_SUMMARY_FIELDS, outside)?;
+
+    let total = sessions.iter().fold(Duration::zero(), |total, session| {
+        saturating_add(total, session.end(now) - session.start)
+    });
+    let summary = |text: &str| {
+        text.replace("{{total}}", &format_duration(total))
+            .replace("{{count}}", &sessions.len().to_string())
+    };
+
+    let mut output = summary(header);
+    for session in sessions {
+        let duration = session.end(now) - session.start;
+        let time = |time: DateTime<Utc>| time.with_timezone(&zone).format(time_format).to_string();
+        output += &body
+            .replace("{{start}}", &time(session.start))
+            .replace("{{stop}}", &session.stop.map(time).unwrap_or_default())
+            .replace("{{duration}}", &format_duration(duration))
+            .replace("{{minutes}}", &duration.num_minutes().to_string())
+            .replace(
+                "{{description}}",
+                session.description.as_deref().unwrap_or_default(),
+            );
+    }
+    Ok(output + &summary(footer))
+}
+
+/// one line per session with the columns start, stop, hours and description
+fn get_csv_lines(
+    sessions: &[Session],
+    delimiter: char,
+    decimals: Decimals,
+    decimal_comma: bool,
+    zone: Zone,
+) -> Vec<String> {
+    let format_time = |time: DateTime<Utc>| {
+        time.with_timezone(&zone)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    };
+    let header = ["start", "stop", "hours", "description"];
+    let mut lines = vec![header.join(&delimiter.to_string())];
+    for session in sessions {
+        let fields = [
+            format_time(session.start),
+            session.stop.map(format_time).unwrap_or_default(),
+            session
+                .stop
+                .map(|stop| to_decimal_hours(stop - session.start, decimals, decimal_comma))
+                .unwrap_or_default(),
+            session.description.clone().unwrap_or_default(),
+        ];
+        lines.push(
+            fields
+                .iter()
+                .map(|field| to_csv_field(field, delimiter))
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string()),
+        );
+    }
+    lines
+}
+
+/// the sessions per day, week or month, labeled like "2024-03-05", "2024-W10" or "2024-03".
+/// sessions crossing a boundary are clipped to each period. periods without sessions are left out
+fn split_sessions(
+    sessions: &[Session],
+    split_by: SplitBy,
+    now: DateTime<Utc>,
+    zone: Zone,
+) -> Vec<(String, Vec<Session>)> {
+    let date = |time: DateTime<Utc>| time.with_timezone(&zone).naive_local().date();
+    let (mut from, last) = match (
+        sessions.iter().map(|session| session.start).min(),
+        sessions.iter().map(|session| session.end(now)).max(),
+    ) {
+        (Some(first), Some(last)) => (date(first), date(last)),
+        _ => return Vec::new(),
+    };
+    let mut periods = Vec::new();
+    while from <= last {
+        let (label, to) = match split_by {
+            SplitBy::Day => (from.format("%Y-%m-%d").to_string(), from),
+            SplitBy::Week => (iso_week_label(from.iso_week()), week_bounds(from).1),
+            SplitBy::Month => {
+                let next = NaiveDate::from_ymd_opt(from.year(), from.month() + 1, 1)
+                    .unwrap_or_else(|| NaiveDate::from_ymd(from.year() + 1, 1, 1));
+                (from.format("%Y-%m").to_string(), next.pred())
+            }
+        };
+        let clipped = clip_sessions(
+            sessions.to_vec(),
+            Some(DateOrDateTime::Date(from)),
+            Some(DateOrDateTime::Date(to)),
+            now,
+            zone,
+        );
+        if !clipped.is_empty() {
+            periods.push((label, clipped));
+        }
+        from = to.succ();
+    }
+    periods
+}
+
+/// the start and stop events of the sessions. a running session has no stop
+fn get_session_events(sessions: &[Session]) -> Vec<TrackingEvent> {
+    sessions
+        .iter()
+        .flat_map(|session| {
+            let start = TrackingEvent::Start(TrackingData {
+                description: session.description.clone(),
+                time: session.start,
+                labels: session.labels.clone(),
+            });
+            let stop = session
+                .stop
+                .map(|time| TrackingEvent::Stop(TrackingData::new(None, time)));
+            std::iter::once(start).chain(stop)
+        })
+        .collect()
+}
+
+/// merges each session into the one before it if both have the same description and the gap
+/// between them is shorter than `gap`
+fn merge_adjacent_sessions(sessions: Vec<Session>, gap: Duration) -> Vec<Session> {
+    let mut merged: Vec<Session> = Vec::new();
+    for session in sessions {
+        match merged.last_mut() {
+            Some(previous)
+                if previous.description == session.description
+                    && matches!(previous.stop, Some(stop) if session.start - stop < gap) =>
+            {
+                // a running session keeps the merged session running
+                previous.stop = session.stop;
+            }
+            _ => merged.push(session),
+        }
+    }
+    merged
+}
+
+/// rounds the time down, or up, to a multiple of `minutes` since the local midnight
+fn round_to_boundary(time: DateTime<Utc>, minutes: u32, up: bool, zone: Zone) -> DateTime<Utc> {
+    let offset = i64::from(time.with_timezone(&zone).offset().fix().local_minus_utc());
+    let step = i64::from(minutes) * 60;
+    let remainder = (time.timestamp() + offset).rem_euclid(step);
+    let rounded = time.with_nanosecond(0).unwrap() - Duration::seconds(remainder);
+    iif!(
+        up && rounded < time,
+        rounded + Duration::seconds(step),
+        rounded
+    )
+}
+
+/// rounds the start of every session down and its stop up to a multiple of `minutes`. a start
+/// isn't rounded to before the stop of the session before it, so the sessions don't overlap
+fn round_session_boundaries(sessions: Vec<Session>, minutes: u32, zone: Zone) -> Vec<Session> {
+    let mut previous_stop = None;
+    sessions
+        .into_iter()
+        .map(|session| {
+            let start = round_to_boundary(session.start, minutes, false, zone);
+            let start = previous_stop.map_or(start, |stop: DateTime<Utc>| stop.max(start));
+            let stop = session
+                .stop
+                .map(|stop| round_to_boundary(stop, minutes, true, zone));
+            previous_stop = stop;
+            Session {
+                start,
+                stop,
+                ..session
+            }
+        })
+        .collect()
+}
+
+/// the events with the parts of their descriptions which match any of the patterns replaced by
+/// "***"
+fn redact_descriptions(data: &[TrackingEvent], patterns: &[Regex]) -> Vec<TrackingEvent> {
+    let redact = |description: &Option<String>| {
+        description.as_ref().map(|description| {
+            patterns
+                .iter()
+                .fold(description.clone(), |description, pattern| {
+                    pattern.replace_all(&description, "***").into_owned()
+                })
+        })
+    };
+    data.iter()
+        .map(|event| match event {
+            TrackingEvent::Start(data) => TrackingEvent::Start(TrackingData {
+                description: redact(&data.description),
+                ..data.clone()
+            }),
+            TrackingEvent::Stop(data) => TrackingEvent::Stop(TrackingData {
+                description: redact(&data.description),
+                ..data.clone()
+            }),
+        })
+        .collect()
+}
+
+/// the events with adjacent sessions merged as given by --merge-adjacent, or unchanged without it
+fn merge_adjacent_events(data: &[TrackingEvent], gap: Option<Duration>) -> Vec<TrackingEvent> {
+    match gap {
+        Some(gap) => get_session_events(&merge_adjacent_sessions(get_sessions(data, true), gap)),
+        None => data.to_vec(),
+    }
+}
+
+/// writes one file per period of --split-by into the directory `path`
+fn export_split(
+    settings: &Settings,
+    path: &str,
+    data: &[TrackingEvent],
+    format: ExportFormat,
+    pretty: bool,
+    options: &ExportOptions,
+    decimals: Decimals,
+) -> Result<()> {
+    let now = now();
+    let sessions = get_export_sessions(data, &options.filter, true, now, settings.zone)?;
+    std::fs::create_dir_all(path).with_context(|| format!("could not create {}", path))?;
+    let split_by = options.split_by.expect("only called with --split-by");
+    let options = ExportOptions {
+        filter: FilterData {
+            filter: Some("all".to_string()),
+            ..FilterData::default()
+        },
+        split_by: None,
+        ..options.clone()
+    };
+    for (label, sessions) in split_sessions(&sessions, split_by, now, settings.zone) {
+        let file = Path::new(path).join(format!("{}.{}", label, format.extension()));
+        let events = get_session_events(&sessions);
+        let file = file.to_string_lossy();
+        export(settings, &file, &events, format, pretty, &options, decimals)?;
+    }
+    Ok(())
+}
+
+fn export(
+    settings: &Settings,
+    path: &str,
+    data: &[TrackingEvent],
+    format: ExportFormat,
+    pretty: bool,
+    options: &ExportOptions,
+    decimals: Decimals,
+) -> Result<()> {
+    if options.merge_adjacent.is_some() {
+        let data = merge_adjacent_events(data, options.merge_adjacent);
+        let options = ExportOptions {
+            merge_adjacent: None,
+            ..options.clone()
+        };
+        return export(settings, path, &data, format, pretty, &options, decimals);
+    }
+    if !options.redact.is_empty() {
+        let data = redact_descriptions(data, &options.redact);
+        let options = ExportOptions {
+            redact: Vec::new(),
+            ..options.clone()
+        };
+        return export(settings, path, &data, format, pretty, &options, decimals);
+    }
+    if let Some(minutes) = options.round_boundaries {
+        let data = get_session_events(&round_session_boundaries(
+            get_sessions(data, true),
+            minutes,
+            settings.zone,
+        ));
+        let options = ExportOptions {
+            round_boundaries: None,
+            ..options.clone()
+        };
+        return export(settings, path, &data, format, pretty, &options, decimals);
+    }
+    if options.split_by.is_some() {
+        return export_split(settings, path, data, format, pretty, options, decimals);
+    }
+    if let Some(template) = &options.template {
+        let template = std::fs::read_to_string(template)
+            .with_context(|| format!("could not read template {}", template.display()))?;
+        let now = now();
+        let sessions = get_export_sessions(data, &options.filter, true, now, settings.zone)?;
+        let output = render_template(
+            &template,
+            &sessions,
+            &settings.list_time_format,
+            now,
+            settings.zone,
+        )?;
+        return Ok(write_with_flush(path, output)?);
+    }
+    match format {
+        ExportFormat::Json => write_json_data(
+            path,
+            &get_export_events(data, &options.filter, settings.zone)?,
+            None,
+            pretty,
+        ),
+        ExportFormat::Readable => {
+            let data = get_export_events(data, &options.filter, settings.zone)?;
+            export_human_readable(
+                path.to_string(),
+                &data,
+                &settings.list_time_format,
+                settings.export_line_ending,
+                settings.zone,
+            )?;
+            Ok(())
+        }
+        ExportFormat::Org => {
+            let sessions = get_export_sessions(data, &options.filter, false, now(), settings.zone)?;
+            let lines = get_org_clocks(
+                &sessions,
+                options.headlines,
+                &settings.unlabeled_description,
+                settings.zone,
+            );
+            Ok(write_with_flush(
+                path,
+                join_lines(&lines, settings.export_line_ending),
+            )?)
+        }
+        ExportFormat::Csv => {
+            let sessions = get_export_sessions(data, &options.filter, true, now(), settings.zone)?;
+            let lines = get_csv_lines(
+                &sessions,
+                options.csv_delimiter,
+                decimals,
+                options.decimal_comma,
+                settings.zone,
+            );
+            Ok(write_with_flush(
+                path,
+                join_lines(&lines, settings.export_line_ending),
+            )?)
+        }
+        ExportFormat::Heatmap => {
+            let now = now();
+            let sessions = get_export_sessions(data, &options.filter, true, now, settings.zone)?;
+            let (from, to) = get_export_dates(&options.filter, settings.zone)?;
+            let lines = get_heatmap_lines(settings, &sessions, from, to, now);
+            Ok(write_with_flush(
+                path,
+                join_lines(&lines, settings.export_line_ending),
+            )?)
+        }
+    }
+}
+
+/// sorts the events and removes exact duplicates of the event before them, with the same kind,
+/// time and description, as sync tools sometimes write an event twice. events which differ in
+/// anything are kept
+fn prepare_for_write(data: &mut Vec<TrackingEvent>) {
+    data.sort_by_key(|e| e.time(true));
+    data.dedup();
+}
+
+/// the data files of --data-file with their globs expanded, or the data_file setting without it
+fn get_data_files(data_files: &[PathBuf], default: &str) -> Result<Vec<String>> {
+    if data_files.is_empty() {
+        let path = shellexpand::full(default)
+            .with_context(|| format!("could not expand the data_file setting {}", default))?;
+        return Ok(vec![path.to_string()]);
+    }
+    let mut expanded = Vec::new();
+    for path in data_files {
+        let path = path.to_string_lossy();
+        let path = shellexpand::full(&path)
+            .with_context(|| format!("could not expand the data file {}", path))?
+            .to_string();
+        if !path.contains(&['*', '?', '['][..]) {
+            expanded.push(path);
+            continue;
+        }
+        let mut matches = glob::glob(&path)
+            .with_context(|| format!("invalid glob {}", path))?
+            .map(|file| file.map(|file| file.to_string_lossy().to_string()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        if matches.is_empty() {
+            anyhow::bail!("no data file matches {}", path);
+        }
+        matches.sort();
+        expanded.extend(matches);
+    }
+    Ok(expanded)
+}
+
+/// the entries of all data files. entries of several files are sorted and exact duplicates, e.g.
+/// from a copied file, are removed. files which don't exist yet have no entries
+fn read_data_files(paths: &[String]) -> Result<Vec<TrackingEvent>> {
+    let mut data = Vec::new();
+    for path in paths {
+        if Path::new(path).exists() {
+            data.extend(
+                read_data(path)
+                    .with_context(|| format!("could not read the data file {}", path))?,
+            );
+        }
+    }
+    if paths.len() > 1 {
+        prepare_for_write(&mut data);
+    }
+    Ok(data)
+}
+
+/// the exit code of status if the time tracking is not active. errors exit with 1
+const EXIT_INACTIVE: i32 = 3;
+
+fn main() {
+    if let Err(error) = run() {
+        // the alternate format prints the error and its causes on one line, without the debug
+        // representation which returning the error from main would print
+        eprintln!("Error: {:#}", error);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<()> {
+    let Options {
+        command,
+        data_file,
+        config_file,
+        config,
+        yes,
+        fill_gaps,
+        dry_run,
+        quiet,
+        no_pager,
+        no_color,
+        no_chmod,
+        tz,
+        locale_time,
+        precision,
+    } = Options::from_args();
+
+    if let Ok(now) = std::env::var("TT_NOW") {
+        DateTime::parse_from_rfc3339(&now).context("invalid TT_NOW, expected an RFC 3339 time")?;
+    }
+
+    let mut settings =
+        Settings::new(&config_file, &config).context("could not load the settings")?;
+    settings.zone = Zone::from(tz);
+    parse_time_format(&settings.list_time_format).context("invalid list_time_format setting")?;
+    let clock_format = iif!(
+        locale_time,
+        locale::clock_format(locale::from_env().as_deref()),
+        "%H:%M:%S"
+    );
+    let list_time_format = iif!(
+        locale_time,
+        format!("%Y-%m-%d {}", clock_format),
+        settings.list_time_format.clone()
+    );
+    let decimals = Decimals {
+        precision,
+        rounding: settings.rounding_mode,
+    };
+
+    let command = command.unwrap_or_default();
+    let data_files = get_data_files(&data_file, &settings.data_file)?;
+    if data_files.len() > 1 && (fill_gaps || !command.is_read_only()) {
+        anyhow::bail!(
+            "{} data files are given, but changes can only be written to one. use a single --data-file",
+            data_files.len()
+        );
+    }
+    let expanded_path = data_files[0].clone();
+    let mut data = read_data_files(&data_files)?;
+    let previous_data = iif!(dry_run, data.clone(), Vec::new());
+
+    let gaps_filled = if fill_gaps {
+        let filled = fill_dangling_sessions(&settings, &mut data, now());
+        if filled > 0 {
+            eprintln!("Stopped {} dangling session(s).", filled);
+        }
+        filled > 0
+    } else {
+        false
+    };
+
+    // whether a start without a description may have inherited the last one
+    let confirm = match &command {
+        Command::Start {
+            description: None,
+            message: None,
+            edit: false,
+            ..
+        }
+        | Command::Continue { new: None, .. } => Some(true),
+        Command::Start { .. } | Command::Stop { .. } | Command::Continue { .. } => Some(false),
+        _ => None,
+    };
+    let previous_len = data.len();
+
+    #[cfg(feature = "binary")]
+    let mut import_failures: Vec<String> = Vec::new();
+    let data_changed = match command {
+        Command::Start {
+            description,
+            message,
+            at,
+            edit,
+            keep_empty_description,
+            resume_if_same,
+            project,
+            tag,
+        } => {
+            let description = if edit {
+                read_description_from_editor()?
+            } else {
+                resolve_description(description, message, io::stdin())?
+            };
+            if edit && description.is_none() {
+                eprintln!("Aborting start due to empty description.");
+                false
+            } else {
+                let labels = Labels {
+                    project,
+                    tags: tag.iter().map(|tag| strip_tag(tag)).collect(),
+                };
+                start_tracking(
+                    &settings,
+                    &mut data,
+                    description,
+                    labels,
+                    at,
+                    keep_empty_description,
+                    resume_if_same || settings.resume_if_same,
+                )?
+            }
+        }
+        Command::Stop {
+            description,
+            message,
+            all: true,
+            keep_empty_description,
+            ..
+        } => {
+            let description = resolve_description(description, message, io::stdin())?;
+            let description = normalize_description(description, keep_empty_description);
+            let stopped = stop_all(&settings, &mut data, description, now())? > 0;
+            if stopped {
+                if let Some(warning) = get_session_limit_warning(&settings, &data) {
+                    eprintln!("Warning: {}", warning);
+                }
+            }
+            stopped
+        }
+        Command::Stop {
+            description,
+            message,
+            at,
+            edit,
+            keep_empty_description,
+            ..
+        } => {
+            let description = if edit {
+                read_description_from_editor()?
+            } else {
+                resolve_description(description, message, io::stdin())?
+            };
+            if edit && description.is_none() {
+                eprintln!("Aborting stop due to empty description.");
+                false
+            } else {
+                let stopped = stop_tracking(
+                    &settings,
+                    &mut data,
+                    description,
+                    at,
+                    keep_empty_description,
+                )?;
+                if stopped {
+                    if let Some(warning) = get_session_limit_warning(&settings, &data) {
+                        eprintln!("Warning: {}", warning);
+                    }
+                }
+                stopped
+            }
+        }
+        Command::Continue { at, new } => {
+            continue_tracking(&settings, &mut data, new, at, now())?;
+            true
+        }
+        Command::List {
+            json: true,
+            only: Some(ListOnly::Sessions),
+            ..
+        } => anyhow::bail!("--only sessions can't be combined with --json"),
+        Command::List {
+            filter,
+            json: true,
+            epoch,
+            only,
+            reverse,
+            page,
+            per_page,
+            ..
+        } => {
+            let data = get_list_events(filter_events(&data, &filter, settings.zone)?, only);
+            let (entries, footer) = paginate(
+                get_list_entries(&data, epoch, settings.zone),
+                reverse,
+                page,
+                per_page,
+            );
+            println!("{}", serde_json::to_string_pretty(&entries)?);
+            // the footer would make the output invalid JSON
+            if let Some(footer) = footer {
+                eprintln!("{}", footer);
+            }
+            false
+        }
+        Command::List {
+            filter,
+            time_format,
+            only,
+            reverse,
+            page,
+            per_page,
+            ..
+        } => {
+            let data = filter_events(&data, &filter, settings.zone)?;
+            let time_format = time_format.unwrap_or(list_time_format);
+            let lines = match only {
+                Some(ListOnly::Sessions) => {
+                    get_session_lines(&data, &time_format, now(), settings.zone)
+                }
+                only => {
+                    get_human_readable(&get_list_events(data, only), &time_format, settings.zone)
+                }
+            };
+            let (mut lines, footer) = paginate(lines, reverse, page, per_page);
+            lines.extend(footer);
+            pager::print_lines(&lines, !no_pager);
+            false
+        }
+        Command::Stats {
+            gaps: true,
+            filter,
+            assume_stopped,
+            ..
+        } => {
+            let data = with_assumed_stop(&data, &assume_stopped, settings.zone)?;
+            let lines = match get_range_dates(&settings, &data, &filter)? {
+                Some((from, to)) => get_gaps(&settings, &data, from, to, now()),
+                None => Vec::new(),
+            };
+            if lines.is_empty() {
+                println!("No gaps found.");
+            }
+            for (start, end) in lines {
+                println!(
+                    "{} - {} ({})",
+                    start.format("%Y-%m-%d %H:%M:%S"),
+                    end.format("%H:%M:%S"),
+                    format_duration(end - start)
+                );
+            }
+            false
+        }
+        Command::Stats {
+            focus: true,
+            filter,
+            assume_stopped,
+            workdays_only,
+            ..
+        } => {
+            let data = filter_workdays(
+                &settings,
+                &with_assumed_stop(&data, &assume_stopped, settings.zone)?,
+                workdays_only,
+            );
+            let now = now();
+            let sessions = get_export_sessions(&data, &filter, true, now, settings.zone)?;
+            let focus = get_focus(&settings, &sessions, now);
+            println!("Tracked days: {}", focus.days);
+            println!("Sessions: {}", focus.sessions);
+            println!(
+                "Average session: {}",
+                format_duration(focus.average_session)
+            );
+            println!("Switches: {}", focus.switches);
+            println!(
+                "Descriptions per day: {}",
+                decimals.format(focus.descriptions_per_day)
+            );
+            false
+        }
+        Command::Stats {
+            by_hour_of_day: true,
+            chart,
+            filter,
+            assume_stopped,
+            workdays_only,
+            ..
+        } => {
+            let data = filter_workdays(
+                &settings,
+                &with_assumed_stop(&data, &assume_stopped, settings.zone)?,
+                workdays_only,
+            );
+            let now = now();
+            let sessions = get_export_sessions(&data, &filter, true, now, settings.zone)?;
+            for line in
+                get_hourly_lines(&get_hourly_work_time(&sessions, now, settings.zone), chart)
+            {
+                println!("{}", line);
+            }
+            false
+        }
+        Command::Stats {
+            streak: true,
+            filter,
+            assume_stopped,
+            ..
+        } => {
+            let data = get_export_events(
+                &with_assumed_stop(&data, &assume_stopped, settings.zone)?,
+                &filter,
+                settings.zone,
+            )?;
+            let (current, longest) =
+                get_streaks(&settings, &data, today(settings.zone).naive_local());
+            println!("Current streak: {}", pluralize(i64::from(current), "day"));
+            println!("Longest streak: {}", pluralize(i64::from(longest), "day"));
+            false
+        }
+        Command::Stats {
+            filter,
+            assume_stopped,
+            workdays_only,
+            ..
+        } => {
+            let data = filter_workdays(
+                &settings,
+                &with_assumed_stop(&data, &assume_stopped, settings.zone)?,
+                workdays_only,
+            );
+            let data = get_export_events(&data, &filter, settings.zone)?;
+            let minutes = get_daily_minutes(&settings, &data);
+            let total: i64 = minutes.values().sum();
+            let average = total / (minutes.len() as i64).max(1);
+            println!("Tracked days: {}", minutes.len());
+            println!("Total: {}", format_duration(Duration::minutes(total)));
+            println!(
+                "Average per day: {}",
+                format_duration(Duration::minutes(average))
+            );
+            false
+        }
+        Command::Backup { list: true, .. } => {
+            let lines = backup::describe_backups(
+                Path::new(&expanded_path),
+                &settings.list_time_format,
+                settings.zone,
+            )?;
+            if lines.is_empty() {
+                println!("No backups yet.");
+            }
+            for line in lines {
+                println!("{}", line);
+            }
+            false
+        }
+        Command::Backup {
+            restore: Some(name),
+            ..
+        } => {
+            if dry_run {
+                backup::find_backup(Path::new(&expanded_path), &name)?;
+                println!("Would restore the backup {}", name);
+            } else if backup::restore_backup(
+                Path::new(&expanded_path),
+                &name,
+                yes,
+                settings.backup_count,
+            )? {
+                println!("Restored the backup {}", name);
+            }
+            false
+        }
+        Command::Backup { .. } => {
+            if settings.backup_count == 0 {
+                anyhow::bail!("backups are disabled, backup_count is 0");
+            }
+            if dry_run {
+                println!("Would create a backup of {}", expanded_path);
+            } else {
+                match backup::create_backup(Path::new(&expanded_path), settings.backup_count)? {
+                    Some(backup) => println!("Created backup at {}", backup.display()),
+                    None => anyhow::bail!("there is no data file at {} yet", expanded_path),
+                }
+            }
+            false
+        }
+        Command::Config {
+            command: ConfigCommand::Migrate { path },
+        } => {
+            let path = path.unwrap_or_else(|| PathBuf::from(settings::global_config_path()));
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("could not read {}", path.display()))?;
+            match settings::migrate(&contents)? {
+                None => println!(
+                    "{} already uses version {} of the settings",
+                    path.display(),
+                    settings::CURRENT_VERSION
+                ),
+                Some(migrated) if dry_run => print!("{}", migrated),
+                Some(migrated) => {
+                    std::fs::write(&path, migrated)
+                        .with_context(|| format!("could not write {}", path.display()))?;
+                    println!(
+                        "Migrated {} to version {} of the settings",
+                        path.display(),
+                        settings::CURRENT_VERSION
+                    );
+                }
+            }
+            false
+        }
+        Command::InstallHooks { binary } => {
+            let binary = match binary {
+                Some(binary) => binary,
+                None => std::env::current_exe().context("could not find the path of tt")?,
+            };
+            // the unit uses the same data file, if it was given
+            let data_file = iif!(data_file.is_empty(), None, Some(expanded_path.as_str()));
+            let unit = hooks::systemd_unit(&binary, data_file);
+            if dry_run {
+                print!("{}", unit);
+            } else {
+                let path = hooks::install_unit(&unit)?;
+                println!("Wrote {}. Enable it with:", path.display());
+                println!("  systemctl --user daemon-reload");
+                println!("  systemctl --user enable --now {}", hooks::UNIT_NAME);
+            }
+            false
+        }
+        Command::Notify { stdout } => {
+            let notification =
+                get_notification(&settings, &data, today(settings.zone).naive_local());
+            if stdout {
+                println!("{}", serde_json::to_string_pretty(&notification)?);
+            } else {
+                send_notification(&settings.notify.url, &notification)?;
+            }
+            false
+        }
+        Command::Summary {
+            filter,
+            assume_stopped,
+            billable,
+            merge_adjacent,
+            matrix: true,
+            format,
+            csv_delimiter,
+            decimal_comma,
+            order,
+            group_by,
+            ..
+        } => {
+            let data = merge_adjacent_events(
+                &with_assumed_stop(&data, &assume_stopped, settings.zone)?,
+                merge_adjacent,
+            );
+            let data = filter_events(
+                &filter_billable(&settings, &data, &billable),
+                &filter,
+                settings.zone,
+            )?;
+            let sessions = group_sessions(get_sessions(&data, true), group_by);
+            let table = get_matrix(&settings, &sessions, &order, decimals, now());
+            let lines = render_table(&table, format, csv_delimiter, decimal_comma);
+            pager::print_lines(&lines, !no_pager);
+            false
+        }
+        Command::Summary {
+            filter,
+            assume_stopped,
+            billable,
+            merge_adjacent,
+            goals: true,
+            skip_empty,
+            format,
+            csv_delimiter,
+            decimal_comma,
+            ..
+        } => {
+            let data = merge_adjacent_events(
+                &with_assumed_stop(&data, &assume_stopped, settings.zone)?,
+                merge_adjacent,
+            );
+            let data = filter_events(
+                &filter_billable(&settings, &data, &billable),
+                &filter,
+                settings.zone,
+            )?;
+            let today = today(settings.zone).naive_local();
+            let (from, to) = get_range_dates(&settings, &data, &filter)?.unwrap_or((today, today));
+            let table = get_goal_table(&settings, &data, from, to, skip_empty, decimals);
+            let lines = render_table(&table, format, csv_delimiter, decimal_comma);
+            pager::print_lines(&lines, !no_pager);
+            false
+        }
+        Command::Summary {
+            filter,
+            assume_stopped,
+            billable,
+            merge_adjacent,
+            min_percent,
+            order,
+            format,
+            csv_delimiter,
+            decimal_comma,
+            group_by,
+            ..
+        } => {
+            let data = merge_adjacent_events(
+                &with_assumed_stop(&data, &assume_stopped, settings.zone)?,
+                merge_adjacent,
+            );
+            let data = filter_events(
+                &filter_billable(&settings, &data, &billable),
+                &filter,
+                settings.zone,
+            )?;
+            let rows = get_summary(
+                &group_sessions(get_sessions(&data, true), group_by),
+                &settings.unlabeled_description,
+                min_percent,
+                &order,
+                now(),
+            );
+            let lines: Vec<_> = match format {
+                TableFormat::Text => rows
+                    .into_iter()
+                    .map(|(description, time)| format!("{} {}", format_duration(time), description))
+                    .collect(),
+                _ => render_table(
+                    &get_summary_table(&rows, decimals),
+                    format,
+                    csv_delimiter,
+                    decimal_comma,
+                ),
+            };
+            pager::print_lines(&lines, !no_pager);
+            false
+        }
+        Command::Timeline { date, slice } => {
+            let day = match date {
+                Some(date) => match parse_date_or_date_time(&date, settings.zone)? {
+                    DateOrDateTime::Date(date) => date,
+                    DateOrDateTime::DateTime(time) => time.date(),
+                },
+                None => today(settings.zone).naive_local(),
+            };
+            let slice = match slice {
+                Some(slice) if slice == 0 || 60 % slice != 0 => anyhow::bail!(
+                    "a slice of {} minutes doesn't divide an hour evenly, e.g. use 15 or 20",
+                    slice
+                ),
+                Some(slice) => slice,
+                None => get_timeline_slice(terminal_width()),
+            };
+            let lines = get_timeline(&settings, &data, day, slice, use_color(no_color), now());
+            for line in lines {
+                println!("{}", line);
+            }
+            false
+        }
+        Command::Diff { other } => {
+            let other = read_data(&other)
+                .with_context(|| format!("could not read the data file {}", other.display()))?;
+            let lines = get_diff(&data, &other, &settings.list_time_format, settings.zone);
+            pager::print_lines(&lines, !no_pager);
+            false
+        }
+        Command::Path => {
+            for path in &data_files {
+                println!("{}", path);
+            }
+            false
+        }
+        Command::Show(options) => {
+            let data = with_assumed_stop(&data, &options.assume_stopped, settings.zone)?;
+            let data = filter_billable(&settings, &data, &options.billable);
+            let data = filter_workdays(&settings, &data, options.workdays_only);
+            show(&settings, &data, &options, decimals)?;
+            false
+        }
+        Command::Status {
+            wait_active,
+            wait_inactive,
+            timeout,
+            interval,
+            ..
+        } if wait_active || wait_inactive => {
+            let path = Path::new(&expanded_path);
+            if !wait_for_state(path, wait_active, interval, timeout, std::thread::sleep)? {
+                anyhow::bail!(
+                    "the time tracking did not become {} within {}",
+                    iif!(wait_active, "active", "inactive"),
+                    format_window(timeout.unwrap_or_else(Duration::zero))
+                );
+            }
+            false
+        }
+        Command::Status { short, .. } => {
+            if short {
+                let now = now();
+                let line = get_prompt_line(&settings.prompt_format, &data, now)
+                    .or_else(|| get_idle_line(&settings.idle_format, &data, now));
+                if let Some(line) = line {
+                    println!("{}", line);
+                }
+            } else {
+                let color = iif!(
+                    use_color(no_color),
+                    Some(get_goal_color(
+                        &settings,
+                        &data,
+                        today(settings.zone).naive_local()
+                    )),
+                    None
+                );
+                status(&data, color, clock_format, settings.zone);
+            }
+            false
+        }
+        Command::Edit {
+            filter: options,
+            shift,
+        } => {
+            let (filter, from, to) =
+                resolve_filter(&options.from, &options.to, &options.filter, settings.zone)?;
+            // the shifted entries have to stay in order with the ones around them, so whole
+            // ranges are shifted and no single sessions
+            if matches!(filter.as_deref(), Some(filter) if filter != "all")
+                || options.project.is_some()
+                || !options.tag.is_empty()
+                || !options.exclude.is_empty()
+            {
+                anyhow::bail!("edit shifts all entries in the range and can't filter by description, project or tag. use --from and --to");
+            }
+            let (from, to) = iif!(
+                filter.as_deref() == Some("all"),
+                (None, None),
+                (
+                    from.map(|from| from.range_start(settings.zone)),
+                    to.map(|to| to.range_end(settings.zone))
+                )
+            );
+            let (count, warnings) = shift_events(
+                &mut data,
+                from,
+                to,
+                shift,
+                &settings.list_time_format,
+                settings.zone,
+            );
+            for warning in warnings {
+                eprintln!("Warning: {}", warning);
+            }
+            count > 0
+                && (dry_run
+                    || backup::prepare_destructive_write(
+                        Path::new(&expanded_path),
+                        &format!("shift {} entries", count),
+                        yes,
+                        settings.backup_count,
+                    )?)
+        }
+        Command::Delete { from, to } => {
+            let (_, from, to) = resolve_filter(&Some(from), &to, &None, settings.zone)?;
+            let (from, to) = (
+                from.expect("from is given"),
+                to.expect("to defaults to from"),
+            );
+            let count = delete_events(
+                &mut data,
+                from,
+                to,
+                &settings.list_time_format,
+                settings.zone,
+            )?;
+            let confirmed = count > 0
+                && (dry_run
+                    || backup::prepare_destructive_write(
+                        Path::new(&expanded_path),
+                        &format!("delete {} entries", count),
+                        yes,
+                        settings.backup_count,
+                    )?);
+            if confirmed && !dry_run {
+                println!("Deleted {} entries.", count);
+            } else if count == 0 {
+                println!("No entries in the range.");
+            }
+            confirmed
+        }
+        Command::Reopen => {
+            let max_age = Duration::minutes(settings.reopen_max_minutes.into());
+            reopen_session(
+                &mut data,
+                max_age,
+                now(),
+                &settings.list_time_format,
+                settings.zone,
+            )?;
+            dry_run
+                || backup::prepare_destructive_write(
+                    Path::new(&expanded_path),
+                    "remove the last stop",
+                    yes,
+                    settings.backup_count,
+                )?
+        }
+        Command::Amend { minus } => {
+            amend_last_event(&mut data, minus, &settings.list_time_format, settings.zone)?;
+            dry_run
+                || backup::prepare_destructive_write(
+                    Path::new(&expanded_path),
+                    "change the time of the last entry",
+                    yes,
+                    settings.backup_count,
+                )?
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui => match tui::run(&settings, data.clone())? {
+            Some(changed) => {
+                data = changed;
+                dry_run
+                    || backup::prepare_destructive_write(
+                        Path::new(&expanded_path),
+                        "write the changes made in the browser",
+                        yes,
+                        settings.backup_count,
+                    )?
+            }
+            None => false,
+        },
+        Command::Cleanup => {
+            if dry_run
+                || backup::prepare_destructive_write(
+                    Path::new(&expanded_path),
+                    "start an interactive cleanup of repeated entries",
+                    yes,
+                    settings.backup_count,
+                )?
+            {
+                data = cleanup(&data, &settings.list_time_format, settings.zone);
+                true
+            } else {
+                false
+            }
+        }
+        #[cfg(not(feature = "binary"))]
+        Command::Export { options, path } => {
+            let path = path.to_string_lossy();
+            let expanded_path = shellexpand::full(&path)
+                .with_context(|| format!("could not expand the export path {}", path))?
+                .to_string();
+            let format = options.format.unwrap_or(ExportFormat::Readable);
+            export(
+                &settings,
+                &expanded_path,
+                &data,
+                format,
+                false,
+                &options,
+                decimals,
+            )
+            .with_context(|| format!("could not export to {}", expanded_path))?;
+            false
+        }
+
+        #[cfg(feature = "binary")]
+        Command::Export {
+            path,
+            readable,
+            pretty,
+            options,
+        } => {
+            let path = path.to_string_lossy();
+            let expanded_path = shellexpand::full(&path)
+                .with_context(|| format!("could not expand the export path {}", path))?
+                .to_string();
+            let format = options.format.unwrap_or(if readable {
+                ExportFormat::Readable
+            } else {
+                ExportFormat::Json
+            });
+            export(
+                &settings,
+                &expanded_path,
+                &data,
+                format,
+                pretty,
+                &options,
+                decimals,
+            )
+            .with_context(|| format!("could not export to {}", expanded_path))?;
+            false
+        }
+        #[cfg(feature = "binary")]
+        Command::Import {
+            path,
+            format,
+            mode,
+            continue_on_error,
+        } => {
+            let contents = std::fs::read_to_string(&path)
+                .with_context(|| format!("could not read {}", path.display()))?;
+            let format = match format {
+                Some(format) => format,
+                None => detect_import_format(&contents)?,
+            };
+            let (events, failures) =
+                parse_import_events(&contents, format, &settings.list_time_format, settings.zone)
+                    .with_context(|| format!("could not read {}", path.display()))?;
+            match failures.first() {
+                Some(failure) if !continue_on_error => anyhow::bail!(
+                    "{}. use --continue-on-error to import the valid entries",
+                    failure
+                ),
+                _ => import_failures = failures,
+            }
+            let mut imported = import_events(&data, events, mode);
+            for warning in
+                sort_and_validate(&mut imported, &settings.list_time_format, settings.zone)
+            {
+                eprintln!("Warning: {}", warning);
+            }
+            if mode == ImportMode::Merge
+                || dry_run
+                || backup::prepare_destructive_write(
+                    Path::new(&expanded_path),
+                    "replace all existing entries",
+                    yes,
+                    settings.backup_count,
+                )?
+            {
+                data = imported;
+                true
+            } else {
+                false
+            }
+        }
+        #[allow(unreachable_patterns)]
+        _ => unimplemented!(),
+    };
+
+    if let Some(inherited) = confirm.filter(|_| !quiet && !dry_run) {
+        for line in get_confirmations(
+            &data,
+            previous_len,
+            inherited,
+            &settings.list_time_format,
+            settings.zone,
+        ) {
+            println!("{}", line);
+        }
+    }
+
+    if data_changed || gaps_filled {
+        prepare_for_write(&mut data);
+        save_data(
+            &expanded_path,
+            &previous_data,
+            &data,
+            dry_run,
+            !no_chmod,
+            &settings.list_time_format,
+            settings.zone,
+        )
+        .with_context(|| format!("could not write the data file {}", expanded_path))?;
+    }
+
+    #[cfg(feature = "binary")]
+    if !import_failures.is_empty() {
+        for failure in &import_failures {
+            eprintln!("Error: {}", failure);
+        }
+        anyhow::bail!(
+            "skipped invalid entries: {}. The other entries were imported",
+            import_failures.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// parses a page number or size, which starts at 1
+fn parse_page(s: &str) -> Result<usize> {
+    match s.parse() {
+        Ok(page) if page > 0 => Ok(page),
+        _ => anyhow::bail!("invalid page: \"{}\". Use a number from 1 on", s),
+    }
+}
+
+/// parses the positive minutes of --round-remaining
+fn parse_round_remaining(s: &str) -> Result<i64> {
+    match s.parse() {
+        Ok(minutes) if minutes > 0 => Ok(minutes),
+        _ => anyhow::bail!("invalid minutes: \"{}\". Use a number from 1 on, e.g. 5", s),
+    }
+}
+
+/// parses a regular expression of --redact
+fn parse_regex(s: &str) -> Result<Regex> {
+    Regex::new(s).with_context(|| format!("invalid regular expression: \"{}\"", s))
+}
+
+/// parses the minutes of --round-boundaries, which have to divide a day evenly
+fn parse_boundaries(s: &str) -> Result<u32> {
+    match s.parse() {
+        Ok(minutes) if minutes > 0 && 24 * 60 % minutes == 0 => Ok(minutes),
+        _ => anyhow::bail!(
+            "invalid boundaries: \"{}\". Use minutes which divide a day evenly, e.g. 5 or 15",
+            s
+        ),
+    }
+}
+
+/// parses the positive length of a rolling window like "7d" or "12h"
+fn parse_window(s: &str) -> Result<Duration> {
+    let window = parse_shift(s)?;
+    if window < Duration::zero() {
+        anyhow::bail!("the window must not be negative: \"{}\"", s);
+    }
+    Ok(window)
+}
+
+/// parses a signed duration made of days, hours, minutes and seconds like "+1h", "-30m" or
+/// "1h30m"
+fn parse_shift(s: &str) -> Result<Duration> {
+    let (sign, rest) = match s.chars().next() {
+        Some('-') => (-1, &s[1..]),
+        Some('+') => (1, &s[1..]),
+        _ => (1, s),
+    };
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let value: i64 = number
+            .parse()
+            .with_context(|| format!("invalid duration: \"{}\"", s))?;
+        duration = duration
+            + match c {
+                'd' => Duration::days(value),
+                'h' => Duration::hours(value),
+                'm' => Duration::minutes(value),
+                's' => Duration::seconds(value),
+                _ => anyhow::bail!("invalid duration: \"{}\". Use d, h, m and s as units", s),
+            };
+        number.clear();
+    }
+    if !number.is_empty() || duration.is_zero() {
+        anyhow::bail!("invalid duration: \"{}\". Use e.g. \"+1h\" or \"-30m\"", s);
+    }
+    Ok(duration * sign)
+}
+
+/// parses a goal given as "HH:MM"
+fn parse_target(s: &str) -> Result<Duration> {
+    let invalid = || anyhow::anyhow!("invalid target: \"{}\". Use HH:MM, e.g. \"06:00\"", s);
+    let (hours, minutes) = match s.find(':') {
+        Some(i) => (&s[..i], &s[i + 1..]),
+        None => return Err(invalid()),
+    };
+    let hours: i64 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i64 = minutes.parse().map_err(|_| invalid())?;
+    if hours < 0 || !(0..60).contains(&minutes) {
+        return Err(invalid());
+    }
+    Ok(Duration::hours(hours) + Duration::minutes(minutes))
+}
+
+fn parse_timezone(s: &str) -> Result<Tz> {
+    s.parse().map_err(|_| {
+        anyhow::anyhow!(
+            "unknown timezone: \"{}\". Please use an IANA name like \"Europe/Vienna\"",
+            s
+        )
+    })
+}
+
+fn parse_time_format(s: &str) -> Result<String> {
+    if StrftimeItems::new(s).any(|item| item == Item::Error) {
+        anyhow::bail!("invalid time format: \"{}\"", s);
+    }
+    Ok(s.to_string())
+}
+
+/// the current time, or the RFC 3339 time in the TT_NOW environment variable if it is set. this
+/// makes everything which depends on the current time reproducible in scripts and tests
+fn now() -> DateTime<Utc> {
+    std::env::var("TT_NOW")
+        .ok()
+        .and_then(|now| DateTime::parse_from_rfc3339(&now).ok())
+        .map_or_else(Utc::now, |now| now.with_timezone(&Utc))
+}
+
+/// the current date in the timezone, see `now`
+fn today(zone: Zone) -> Date<Zone> {
+    now().with_timezone(&zone).date()
+}
+
+fn parse_date_time(s: &str, zone: Zone) -> Result<DateTime<Utc>> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(date_time.with_timezone(&Utc));
+    }
+    if let Some(timestamp) = s.strip_prefix('@') {
+        let seconds = timestamp
+            .parse()
+            .with_context(|| format!("invalid unix timestamp: \"{}\"", s))?;
+        return NaiveDateTime::from_timestamp_opt(seconds, 0)
+            .map(|time| DateTime::from_utc(time, Utc))
+            .with_context(|| format!("unix timestamp out of range: \"{}\"", s));
+    }
+
+    let from_time = |s: &str| NaiveTime::parse_from_str(s, "%H:%M:%S");
+    let from_date_time = |s: &str| zone.datetime_from_str(s, "%Y-%m-%d %H:%M:%S");
+
+    from_time(s)
+        .or_else(|_| from_time(&format!("{}:0", s)))
+        .or_else(|_| from_time(&format!("{}:0:0", s)))
+        .map_err(Into::into)
+        .and_then(|time| today(zone).and_time(time).context("invalid time"))
+        .or_else(|_| {
+            from_date_time(s)
+                .or_else(|_| from_date_time(&format!("{}:0", s)))
+                .or_else(|_| from_date_time(&format!("{}:0:0", s)))
+        })
+        .map(|date_time| date_time.with_timezone(&Utc))
+        .map_err(Into::into)
+}
+
+fn parse_date_or_date_time(s: &str, zone: Zone) -> Result<DateOrDateTime> {
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(date_time.with_timezone(&zone).naive_local().into());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.into());
+    }
+    if let Ok(date_time) = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S") {
+        return Ok(date_time.into());
+    }
+
+    parse_date_time(s, zone).map(|date_time| date_time.with_timezone(&zone).naive_local().into())
+}
+
+fn parse_show_range(s: &str) -> Result<ShowRange> {
+    let (from, to) = s
+        .split_once("..")
+        .with_context(|| format!("invalid range: \"{}\". Use \"<from>..<to>\"", s))?;
+    Ok(ShowRange {
+        label: s.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+
+    #[test]
+    fn test_parse_date_time() {
+        assert_eq!(
+            Local::now().date().and_hms(0, 0, 15).with_timezone(&Utc),
+            parse_date_time("00:00:15", Zone::System).unwrap()
+        );
+        assert_eq!(
+            Local::now().date().and_hms(0, 15, 0).with_timezone(&Utc),
+            parse_date_time("00:15", Zone::System).unwrap()
+        );
+        assert_eq!(
+            Local::now().date().and_hms(15, 0, 0).with_timezone(&Utc),
+            parse_date_time("15", Zone::System).unwrap()
+        );
+
+        assert_eq!(
+            Local.ymd(2021, 4, 1).and_hms(0, 0, 15).with_timezone(&Utc),
+            parse_date_time("2021-04-01 00:00:15", Zone::System).unwrap()
+        );
+        assert_eq!(
+            Local.ymd(2021, 4, 1).and_hms(0, 15, 0).with_timezone(&Utc),
+            parse_date_time("2021-04-01 00:15", Zone::System).unwrap()
+        );
+        assert_eq!(
+            Local.ymd(2021, 4, 1).and_hms(15, 0, 0).with_timezone(&Utc),
+            parse_date_time("2021-04-01 15", Zone::System).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_date_or_date_time() {
+        assert_eq!(
+            DateOrDateTime::Date(NaiveDate::from_ymd(2020, 4, 1)),
+            parse_date_or_date_time("2020-04-01", Zone::System).unwrap()
+        );
+        assert_eq!(
+            DateOrDateTime::DateTime(NaiveDate::from_ymd(2020, 4, 1).and_hms(12, 15, 20)),
+            parse_date_or_date_time("2020-04-01 12:15:20", Zone::System).unwrap()
+        );
+        assert_eq!(
+            DateOrDateTime::DateTime(NaiveDate::from_ymd(2020, 4, 1).and_hms(12, 0, 0)),
+            parse_date_or_date_time("2020-04-01 12", Zone::System).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc3339() {
+        let instant = Utc.ymd(2024, 3, 5).and_hms(8, 0, 0);
+        assert_eq!(
+            instant,
+            parse_date_time("2024-03-05T09:00:00+01:00", Zone::System).unwrap()
+        );
+        assert_eq!(
+            instant,
+            parse_date_time("2024-03-05T08:00:00Z", Zone::System).unwrap()
+        );
+        assert_eq!(
+            DateOrDateTime::DateTime(instant.with_timezone(&Local).naive_local()),
+            parse_date_or_date_time("2024-03-05T09:00:00+01:00", Zone::System).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_goal_percentage() {
+        let settings = Settings::default();
+        let day = NaiveDate::from_ymd(2021, 3, 29);
+        assert_eq!(50, get_goal_percentage(&settings, "", day, 4, 0));
+        assert_eq!(50, get_goal_percentage(&settings, "week", day, 20, 0));
+    }
+
+    #[test]
+    fn test_to_human_readable_with_time_format() {
+        let time = Local.ymd(2024, 3, 5).and_hms(9, 0, 0);
+        assert_eq!(
+            "Start at 2024-03-05T09:00:00 \"coding\"",
+            to_human_readable(
+                "Start",
+                &time,
+                Some("coding".to_string()),
+                "%Y-%m-%dT%H:%M:%S"
+            )
+        );
+        assert!(parse_time_format("%Y-%m-%d %Q").is_err());
+    }
+
+    fn start<Z: TimeZone>(time: DateTime<Z>, description: &str) -> TrackingEvent {
+        TrackingEvent::Start(TrackingData::new(
+            Some(description.to_string()).filter(|d| !d.is_empty()),
+            time.with_timezone(&Utc),
+        ))
+    }
+
+    fn stop<Z: TimeZone>(time: DateTime<Z>) -> TrackingEvent {
+        TrackingEvent::Stop(TrackingData::new(None, time.with_timezone(&Utc)))
+    }
+
+    #[test]
+    fn test_get_busiest() {
+        let day1 = Local.ymd(2021, 3, 29);
+        let day2 = Local.ymd(2021, 3, 30);
+        let data = vec![
+            start(day1.and_hms(9, 30, 0), ""),
+            stop(day1.and_hms(10, 45, 0)),
+            start(day2.and_hms(10, 0, 0), ""),
+            stop(day2.and_hms(10, 30, 0)),
+            start(day2.and_hms(13, 0, 0), ""),
+            stop(day2.and_hms(15, 0, 0)),
+        ];
+        let sessions = get_sessions(&data, true);
+        let now = Utc::now();
+        let mut settings = Settings::default();
+
+        assert_eq!(
+            Some((Bucket::Hour(10), Duration::minutes(75))),
+            get_busiest(&settings, &sessions, Busiest::Hour, now)
+        );
+        assert_eq!(
+            Some((Bucket::Weekday(1), Duration::minutes(150))),
+            get_busiest(&settings, &sessions, Busiest::Weekday, now)
+        );
+        assert_eq!(
+            Some((Bucket::Day(day2.naive_local()), Duration::minutes(150))),
+            get_busiest(&settings, &sessions, Busiest::Day, now)
+        );
+
+        // a work day which begins at 10:00 counts the first half hour of the second day to the
+        // first one
+        settings.day_start = NaiveTime::from_hms(10, 0, 0);
+        let data = vec![
+            start(day1.and_hms(13, 0, 0), ""),
+            stop(day1.and_hms(14, 0, 0)),
+            start(day2.and_hms(9, 30, 0), ""),
+            stop(day2.and_hms(11, 0, 0)),
+        ];
+        let sessions = get_sessions(&data, true);
+        assert_eq!(
+            Some((Bucket::Day(day1.naive_local()), Duration::minutes(90))),
+            get_busiest(&settings, &sessions, Busiest::Day, now)
+        );
+        assert_eq!(
+            Some((Bucket::Weekday(0), Duration::minutes(90))),
+            get_busiest(&settings, &sessions, Busiest::Weekday, now)
+        );
+    }
+
+    #[test]
+    fn test_get_org_clocks() {
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(10, 30, 0)),
+            start(day.and_hms(11, 0, 0), "meeting"),
+            stop(day.and_hms(11, 15, 0)),
+            start(day.and_hms(13, 0, 0), "coding"),
+        ];
+
+        assert_eq!(
+            vec![
+                "CLOCK: [2021-03-29 Mon 09:00]--[2021-03-29 Mon 10:30] =>  1:30",
+                "CLOCK: [2021-03-29 Mon 11:00]--[2021-03-29 Mon 11:15] =>  0:15",
+                "CLOCK: [2021-03-29 Mon 13:00]",
+            ],
+            get_org_clocks(
+                &get_sessions(&data, false),
+                false,
+                "(no description)",
+                Zone::System
+            )
+        );
+        assert_eq!(
+            vec![
+                "* coding",
+                ":LOGBOOK:",
+                "CLOCK: [2021-03-29 Mon 09:00]--[2021-03-29 Mon 10:30] =>  1:30",
+                "CLOCK: [2021-03-29 Mon 13:00]",
+                ":END:",
+                "* meeting",
+                ":LOGBOOK:",
+                "CLOCK: [2021-03-29 Mon 11:00]--[2021-03-29 Mon 11:15] =>  0:15",
+                ":END:",
+            ],
+            get_org_clocks(
+                &get_sessions(&data, false),
+                true,
+                "(no description)",
+                Zone::System
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_weekly_totals() {
+        let settings = Settings::default();
+        let data = vec![
+            start(Local.ymd(2021, 3, 17).and_hms(9, 0, 0), "meeting"),
+            stop(Local.ymd(2021, 3, 17).and_hms(17, 0, 0)),
+            start(Local.ymd(2021, 3, 29).and_hms(8, 0, 0), ""),
+            stop(Local.ymd(2021, 3, 29).and_hms(12, 0, 0)),
+            start(Local.ymd(2021, 4, 1).and_hms(13, 0, 0), ""),
+            stop(Local.ymd(2021, 4, 1).and_hms(14, 30, 0)),
+        ];
+
+        let today = NaiveDate::from_ymd(2021, 4, 1);
+        let totals = get_weekly_totals(&settings, &data, &FilterData::default(), today, 3, true);
+
+        assert_eq!(
+            vec![
+                ("2021-W11".to_string(), Duration::hours(8)),
+                ("2021-W12".to_string(), Duration::zero()),
+                ("2021-W13".to_string(), Duration::minutes(330)),
+            ],
+            totals
+                .into_iter()
+                .map(|(week, time)| (iso_week_label(week), time))
+                .collect::<Vec<_>>()
+        );
+        let filter = FilterData {
+            exclude: vec!["meeting".to_string()],
+            ..FilterData::default()
+        };
+        let totals = get_weekly_totals(&settings, &data, &filter, today, 3, true);
+        assert_eq!(Duration::zero(), totals[0].1);
+        assert_eq!(Duration::minutes(330), totals[2].1);
+    }
+
+    #[test]
+    fn test_get_range_totals() {
+        let settings = Settings::default();
+        let data = vec![
+            start(Local.ymd(2021, 3, 2).and_hms(9, 0, 0), "coding"),
+            stop(Local.ymd(2021, 3, 2).and_hms(17, 0, 0)),
+            start(Local.ymd(2021, 3, 10).and_hms(9, 0, 0), "coding"),
+            stop(Local.ymd(2021, 3, 10).and_hms(11, 0, 0)),
+            start(Local.ymd(2021, 3, 11).and_hms(9, 0, 0), "meeting"),
+            stop(Local.ymd(2021, 3, 11).and_hms(10, 0, 0)),
+        ];
+        let ranges = vec![
+            parse_show_range("2021-03-01..2021-03-07").unwrap(),
+            parse_show_range("2021-03-08..2021-03-14").unwrap(),
+        ];
+
+        assert_eq!(
+            vec![
+                ("2021-03-01..2021-03-07".to_string(), Duration::hours(8)),
+                ("2021-03-08..2021-03-14".to_string(), Duration::hours(3)),
+            ],
+            get_range_totals(&settings, &data, &FilterData::default(), &ranges, true).unwrap()
+        );
+        let filter = FilterData {
+            exclude: vec!["meeting".to_string()],
+            ..FilterData::default()
+        };
+        assert_eq!(
+            Duration::hours(2),
+            get_range_totals(&settings, &data, &filter, &ranges, true).unwrap()[1].1
+        );
+        assert!(parse_show_range("2021-03-01").is_err());
+    }
+
+    #[test]
+    fn test_parse_editor_description() {
+        assert_eq!(
+            Some("fix bug #42".to_string()),
+            parse_editor_description(&format!(
+                "\n  fix bug #42  \nmore notes\n{}",
+                EDITOR_TEMPLATE
+            ))
+        );
+        assert_eq!(None, parse_editor_description(EDITOR_TEMPLATE));
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_sort_and_validate() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let mut data = vec![
+            start(day.and_hms(13, 0, 0), ""),
+            stop(day.and_hms(12, 0, 0)),
+            stop(day.and_hms(17, 0, 0)),
+            start(day.and_hms(8, 0, 0), ""),
+        ];
+
+        let warnings = sort_and_validate(&mut data, "%H:%M", Zone::System);
+
+        assert!(warnings.is_empty());
+        assert_eq!(
+            vec![
+                start(day.and_hms(8, 0, 0), ""),
+                stop(day.and_hms(12, 0, 0)),
+                start(day.and_hms(13, 0, 0), ""),
+                stop(day.and_hms(17, 0, 0)),
+            ],
+            data
+        );
+        assert_eq!(
+            Duration::hours(8),
+            get_time_from_events(&settings, &data, true)
+        );
+
+        data.push(stop(day.and_hms(18, 0, 0)));
+        assert_eq!(
+            vec!["Repeated stop at 18:00 (run cleanup to resolve)"],
+            sort_and_validate(&mut data, "%H:%M", Zone::System)
+        );
+    }
+
+    #[test]
+    fn test_get_data_as_days_with_day_start() {
+        let data = vec![
+            start(Local.ymd(2021, 3, 29).and_hms(22, 0, 0), ""),
+            stop(Local.ymd(2021, 3, 30).and_hms(2, 0, 0)),
+            start(Local.ymd(2021, 3, 30).and_hms(8, 0, 0), ""),
+            stop(Local.ymd(2021, 3, 30).and_hms(12, 0, 0)),
+        ];
+
+        let settings = Settings::default();
+        assert_eq!(
+            vec![data[..1].to_vec(), data[1..].to_vec()],
+            get_data_as_days(&settings, &data)
+        );
+
+        let settings = Settings {
+            day_start: NaiveTime::from_hms(5, 0, 0),
+            ..Settings::default()
+        };
+        assert_eq!(
+            NaiveDate::from_ymd(2021, 3, 29),
+            work_day(&settings, data[1].time(true))
+        );
+        assert_eq!(
+            vec![data[..2].to_vec(), data[2..].to_vec()],
+            get_data_as_days(&settings, &data)
+        );
+    }
+
+    #[test]
+    fn test_fill_dangling_sessions() {
+        let settings = Settings::default();
+        let auto_stop = |time: DateTime<Local>| {
+            TrackingEvent::Stop(TrackingData::new(
+                Some(AUTO_INSERTED_DESCRIPTION.to_string()),
+                time.with_timezone(&Utc),
+            ))
+        };
+        let mut data = vec![
+            start(Local.ymd(2021, 3, 26).and_hms(9, 0, 0), ""),
+            start(Local.ymd(2021, 3, 29).and_hms(9, 0, 0), ""),
+            stop(Local.ymd(2021, 3, 29).and_hms(12, 0, 0)),
+            start(Local.ymd(2021, 3, 29).and_hms(13, 0, 0), ""),
+        ];
+        let now = Local.ymd(2021, 4, 1).and_hms(10, 0, 0).with_timezone(&Utc);
+
+        assert_eq!(2, fill_dangling_sessions(&settings, &mut data, now));
+        assert_eq!(
+            vec![
+                start(Local.ymd(2021, 3, 26).and_hms(9, 0, 0), ""),
+                auto_stop(Local.ymd(2021, 3, 26).and_hms(17, 0, 0)),
+                start(Local.ymd(2021, 3, 29).and_hms(9, 0, 0), ""),
+                stop(Local.ymd(2021, 3, 29).and_hms(12, 0, 0)),
+                start(Local.ymd(2021, 3, 29).and_hms(13, 0, 0), ""),
+                auto_stop(Local.ymd(2021, 3, 29).and_hms(21, 0, 0)),
+            ],
+            data
+        );
+
+        let now = Local.ymd(2021, 3, 29).and_hms(18, 0, 0).with_timezone(&Utc);
+        let mut running = data[2..5].to_vec();
+        assert_eq!(0, fill_dangling_sessions(&settings, &mut running, now));
+    }
+
+    #[test]
+    fn test_get_export_sessions() {
+        let monday = Local.ymd(2021, 3, 29);
+        let now = Local.ymd(2021, 4, 12).and_hms(12, 0, 0).with_timezone(&Utc);
+        let data = vec![
+            start(monday.pred().and_hms(22, 0, 0), "late"),
+            stop(monday.and_hms(2, 0, 0)),
+            start(monday.and_hms(9, 0, 0), "coding"),
+            stop(monday.and_hms(17, 0, 0)),
+            start((monday + Duration::days(6)).and_hms(23, 0, 0), "release"),
+            stop((monday + Duration::days(7)).and_hms(1, 0, 0)),
+            start((monday + Duration::days(8)).and_hms(9, 0, 0), "coding"),
+        ];
+        let week = FilterData {
+            from: Some("2021-03-29".to_string()),
+            to: Some("2021-04-04".to_string()),
+            filter: None,
+            ..FilterData::default()
+        };
+
+        let sessions = get_export_sessions(&data, &week, true, now, Zone::System).unwrap();
+        let ranges: Vec<_> = sessions
+            .iter()
+            .map(|session| (session.start, session.stop))
+            .collect();
+        assert_eq!(
+            vec![
+                (
+                    monday.and_hms(0, 0, 0).with_timezone(&Utc),
+                    Some(monday.and_hms(2, 0, 0).with_timezone(&Utc))
+                ),
+                (
+                    monday.and_hms(9, 0, 0).with_timezone(&Utc),
+                    Some(monday.and_hms(17, 0, 0).with_timezone(&Utc))
+                ),
+                (
+                    (monday + Duration::days(6))
+                        .and_hms(23, 0, 0)
+                        .with_timezone(&Utc),
+                    Some(
+                        (monday + Duration::days(7))
+                            .and_hms(0, 0, 0)
+                            .with_timezone(&Utc)
+                    )
+                ),
+            ],
+            ranges
+        );
+
+        let coding = FilterData {
+            filter: Some("coding".to_string()),
+            ..week
+        };
+        assert_eq!(
+            1,
+            get_export_sessions(&data, &coding, true, now, Zone::System)
+                .unwrap()
+                .len()
+        );
+        assert_eq!(
+            4,
+            get_export_sessions(&data, &FilterData::default(), true, now, Zone::System)
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_get_csv_lines() {
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding; reviews"),
+            stop(day.and_hms(10, 30, 0)),
+            start(day.and_hms(11, 0, 0), "say \"hi\""),
+        ];
+        let sessions = get_sessions(&data, true);
+
+        assert_eq!(
+            vec![
+                "start,stop,hours,description",
+                "2021-03-29 09:00:00,2021-03-29 10:30:00,1.50,coding; reviews",
+                "2021-03-29 11:00:00,,,\"say \"\"hi\"\"\"",
+            ],
+            get_csv_lines(&sessions, ',', Decimals::default(), false, Zone::System)
+        );
+        assert_eq!(
+            vec![
+                "start;stop;hours;description",
+                "2021-03-29 09:00:00;2021-03-29 10:30:00;1,50;\"coding; reviews\"",
+                "2021-03-29 11:00:00;;;\"say \"\"hi\"\"\"",
+            ],
+            get_csv_lines(&sessions, ';', Decimals::default(), true, Zone::System)
+        );
+        assert_eq!(
+            "2021-03-29 09:00:00,2021-03-29 10:30:00,\"1,50\",coding; reviews",
+            get_csv_lines(&sessions, ',', Decimals::default(), true, Zone::System)[1]
+        );
+    }
+
+    #[test]
+    fn test_get_prompt_line() {
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(10, 23, 30).with_timezone(&Utc);
+        let format = "⏱ {elapsed} {description}";
+
+        let data = vec![start(day.and_hms(9, 0, 0), "coding")];
+        assert_eq!(
+            Some("⏱ 1:23 coding".to_string()),
+            get_prompt_line(format, &data, now)
+        );
+
+        let data = vec![start(day.and_hms(9, 0, 0), "")];
+        assert_eq!(
+            Some("⏱ 1:23".to_string()),
+            get_prompt_line(format, &data, now)
+        );
+
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(10, 0, 0)),
+        ];
+        assert_eq!(None, get_prompt_line(format, &data, now));
+        assert_eq!(None, get_prompt_line(format, &[], now));
+    }
+
+    #[test]
+    fn test_get_idle() {
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(13, 42, 30).with_timezone(&Utc);
+        let mut data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(13, 0, 0)),
+        ];
+
+        assert_eq!(Some(Duration::seconds(42 * 60 + 30)), get_idle(&data, now));
+        assert_eq!(
+            Some("idle 0:42".to_string()),
+            get_idle_line("idle {idle}", &data, now)
+        );
+        assert_eq!(None, get_idle_line("", &data, now));
+        let later = day.and_hms(23, 5, 0).with_timezone(&Utc);
+        assert_eq!(
+            Some("idle 10:05".to_string()),
+            get_idle_line("idle {idle}", &data, later)
+        );
+
+        data.push(start(day.and_hms(13, 30, 0), "coding"));
+        assert_eq!(None, get_idle(&data, now));
+        assert_eq!(None, get_idle(&[], now));
+    }
+
+    #[test]
+    fn test_resolve_same_second() {
+        let mut settings = Settings::default();
+        let time = Local.ymd(2021, 3, 29).and_hms(9, 0, 0).with_timezone(&Utc);
+        let data = vec![start(time.with_timezone(&Local), "coding")];
+        let same_second = time + Duration::milliseconds(300);
+
+        assert_eq!(
+            time + Duration::seconds(1),
+            resolve_same_second(&settings, &data, same_second).unwrap()
+        );
+        assert_eq!(
+            time + Duration::seconds(2),
+            resolve_same_second(&settings, &data, time + Duration::seconds(2)).unwrap()
+        );
+
+        settings.same_second_events = SameSecondEvents::Error;
+        assert!(resolve_same_second(&settings, &data, same_second).is_err());
+        assert!(resolve_same_second(&settings, &[], same_second).is_ok());
+    }
+
+    #[test]
+    fn test_get_summary() {
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(18, 0, 0).with_timezone(&Utc);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(12, 0, 0), "mails"),
+            stop(day.and_hms(12, 5, 0)),
+            start(day.and_hms(12, 5, 0), "reviews"),
+            stop(day.and_hms(14, 0, 0)),
+            start(day.and_hms(14, 0, 0), "standup"),
+            stop(day.and_hms(14, 10, 0)),
+            start(day.and_hms(14, 10, 0), ""),
+            stop(day.and_hms(14, 15, 0)),
+        ];
+        let sessions = get_sessions(&data, true);
+
+        assert_eq!(
+            vec![
+                ("coding".to_string(), Duration::hours(4)),
+                ("reviews".to_string(), Duration::minutes(115)),
+                ("standup".to_string(), Duration::minutes(10)),
+                ("(no description)".to_string(), Duration::minutes(5)),
+                ("mails".to_string(), Duration::minutes(5)),
+            ],
+            get_summary(
+                &sessions,
+                "(no description)",
+                None,
+                &SortOptions::default(),
+                now
+            )
+        );
+        assert_eq!(
+            vec![
+                ("coding".to_string(), Duration::hours(4)),
+                ("reviews".to_string(), Duration::minutes(115)),
+                ("Other (3 tasks)".to_string(), Duration::minutes(20)),
+            ],
+            get_summary(
+                &sessions,
+                "(no description)",
+                Some(5.0),
+                &SortOptions::default(),
+                now
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_summary_table() {
+        let rows = vec![
+            ("coding, tests".to_string(), Duration::minutes(90)),
+            ("mails".to_string(), Duration::minutes(30)),
+        ];
+        let table = get_summary_table(&rows, Decimals::default());
+
+        assert_eq!(
+            vec![
+                "description,seconds,hours,percent",
+                "\"coding, tests\",5400,1.50,75.00",
+                "mails,1800,0.50,25.00",
+            ],
+            render_table(&table, TableFormat::Csv, ',', false)
+        );
+        assert_eq!(
+            vec![
+                "description;seconds;hours;percent",
+                "coding, tests;5400;1,50;75,00",
+                "mails;1800;0,50;25,00",
+            ],
+            render_table(&table, TableFormat::Csv, ';', true)
+        );
+        assert_eq!(
+            vec!["description,seconds,hours,percent"],
+            render_table(
+                &get_summary_table(&[], Decimals::default()),
+                TableFormat::Csv,
+                ',',
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_summary_sort() {
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(18, 0, 0).with_timezone(&Utc);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "reviews"),
+            stop(day.and_hms(9, 0, 0)),
+            start(day.and_hms(9, 0, 0), "mails"),
+            stop(day.and_hms(9, 30, 0)),
+            start(day.and_hms(9, 30, 0), "coding"),
+            stop(day.and_hms(10, 30, 0)),
+            start(day.and_hms(10, 30, 0), "mails"),
+            stop(day.and_hms(11, 0, 0)),
+            start(day.and_hms(11, 0, 0), "admin"),
+            stop(day.and_hms(11, 15, 0)),
+        ];
+        let sessions = get_sessions(&data, true);
+        let summary = |sort, reverse| {
+            let order = SortOptions { sort, reverse };
+            get_summary(&sessions, "(no description)", None, &order, now)
+                .into_iter()
+                .map(|(description, _)| description)
+                .collect::<Vec<_>>()
+        };
+
+        // coding, mails and reviews all took an hour, so they are ordered by description
+        assert_eq!(
+            vec!["coding", "mails", "reviews", "admin"],
+            summary(SummarySort::Duration, false)
+        );
+        assert_eq!(
+            vec!["admin", "reviews", "mails", "coding"],
+            summary(SummarySort::Duration, true)
+        );
+        assert_eq!(
+            vec!["reviews", "mails", "coding", "admin"],
+            summary(SummarySort::Time, false)
+        );
+        assert_eq!(
+            vec!["admin", "coding", "mails", "reviews"],
+            summary(SummarySort::Description, false)
+        );
+        assert_eq!(
+            vec!["reviews", "mails", "coding", "admin"],
+            summary(SummarySort::Description, true)
+        );
+
+        let order = SortOptions {
+            sort: SummarySort::Description,
+            reverse: false,
+        };
+        let table = get_matrix(
+            &Settings::default(),
+            &sessions,
+            &order,
+            Decimals::default(),
+            now,
+        );
+        assert_eq!("admin", table[1][0]);
+        assert_eq!("Total", table[5][0]);
+    }
+
+    #[test]
+    fn test_filter_events_include_unlabeled() {
+        let day = Local::today();
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+            start(day.and_hms(9, 0, 0), ""),
+            stop(day.and_hms(10, 0, 0)),
+            start(day.and_hms(10, 0, 0), "mails"),
+            stop(day.and_hms(11, 0, 0)),
+        ];
+        let mut filter = FilterData {
+            filter: Some("coding".to_string()),
+            ..FilterData::default()
+        };
+
+        assert_eq!(
+            vec![data[0].clone()],
+            filter_events(&data, &filter, Zone::System).unwrap()
+        );
+
+        filter.include_unlabeled = true;
+        assert_eq!(
+            vec![
+                data[0].clone(),
+                data[1].clone(),
+                data[2].clone(),
+                data[3].clone(),
+                data[5].clone(),
+            ],
+            filter_events(&data, &filter, Zone::System).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_exclude_sessions() {
+        let day = Local.ymd(2021, 3, 29);
+        // the meeting ends the running session by starting
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            start(day.and_hms(10, 0, 0), "meeting"),
+            stop(day.and_hms(11, 0, 0)),
+            start(day.and_hms(12, 0, 0), "meeting"),
+            stop(day.and_hms(13, 0, 0)),
+        ];
+
+        assert_eq!(
+            vec![data[0].clone(), stop(day.and_hms(10, 0, 0))],
+            exclude_sessions(data.clone(), &["meeting".to_string()])
+        );
+        assert_eq!(data.clone(), exclude_sessions(data, &[]));
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let settings = Settings::default();
+        let dir = TempDir::new("dry-run");
+        let path = dir.join("timetracking.bin");
+        let previous = vec![
+            start(Local.ymd(2021, 3, 29).and_hms(8, 0, 0), "coding"),
+            stop(Local.ymd(2021, 3, 29).and_hms(9, 0, 0)),
+        ];
+        write_data(&path, &previous, true).unwrap();
+        let stored = std::fs::read(&path).unwrap();
+
+        let mut data = previous.clone();
+        start_tracking(
+            &settings,
+            &mut data,
+            Some("reviews".to_string()),
+            Labels::default(),
+            None,
+            false,
+            false,
+        )
+        .unwrap();
+        save_data(
+            &path,
+            &previous,
+            &data,
+            true,
+            true,
+            &settings.list_time_format,
+            Zone::System,
+        )
+        .unwrap();
+        assert_eq!(stored, std::fs::read(&path).unwrap());
+
+        save_data(
+            &path,
+            &previous,
+            &data,
+            false,
+            true,
+            &settings.list_time_format,
+            Zone::System,
+        )
+        .unwrap();
+        assert_eq!(3, read_data(&path).unwrap().len());
+    }
+
+    #[test]
+    fn test_get_dry_run_report() {
+        let day = Local.ymd(2021, 3, 29);
+        let previous = vec![start(day.and_hms(8, 0, 0), "coding")];
+        let mut data = previous.clone();
+        stop_tracking(
+            &Settings::default(),
+            &mut data,
+            None,
+            Some("2021-03-29 09:00:00".to_string()),
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                "Dry run: 2 entries would be written (1 before). Nothing was changed.",
+                "+ Stop  at 2021-03-29 09:00:00",
+            ],
+            get_dry_run_report(&previous, &data, "%Y-%m-%d %H:%M:%S", Zone::System)
+        );
+    }
+
+    #[test]
+    fn test_iso_week_at_year_boundary() {
+        let monday = NaiveDate::from_ymd(2024, 12, 30);
+        let (from, to) = week_bounds(monday + Duration::days(3));
+        assert_eq!((monday, NaiveDate::from_ymd(2025, 1, 5)), (from, to));
+        assert_eq!("2025-W01", iso_week_label(monday.iso_week()));
+        assert_eq!(
+            iso_week_label(from.iso_week()),
+            iso_week_label(to.iso_week())
+        );
+
+        let sunday = monday.pred();
+        assert_eq!(NaiveDate::from_ymd(2024, 12, 23), week_bounds(sunday).0);
+        assert_eq!("2024-W52", iso_week_label(sunday.iso_week()));
+    }
+
+    #[test]
+    fn test_get_carryover_minutes() {
+        let settings = Settings::default();
+        let monday = NaiveDate::from_ymd(2021, 3, 29);
+        let at = |days: i64, hour: u32| {
+            Local
+                .from_local_datetime(&(monday + Duration::days(days)).and_hms(hour, 0, 0))
+                .unwrap()
+        };
+        let data = vec![
+            start(at(0, 8), "coding"),
+            stop(at(0, 18)),
+            start(at(1, 8), "coding"),
+            stop(at(1, 17)),
+        ];
+
+        assert_eq!(0, get_carryover_minutes(&settings, &data, monday, false));
+        assert_eq!(
+            120,
+            get_carryover_minutes(&settings, &data, monday.succ(), false)
+        );
+        let wednesday = monday + Duration::days(2);
+        assert_eq!(
+            180,
+            get_carryover_minutes(&settings, &data, wednesday, false)
+        );
+        assert_eq!(
+            300,
+            get_remaining_minutes(&settings, "", wednesday, 0, 0)
+                - get_carryover_minutes(&settings, &data, wednesday, false)
+        );
+        // wednesday to friday without any work leave a deficit, saturday has no goal
+        let saturday = monday + Duration::days(5);
+        assert_eq!(0, get_daily_goal_minutes(&settings, saturday));
+        assert_eq!(
+            -1260,
+            get_carryover_minutes(&settings, &data, saturday, false)
+        );
+    }
+
+    #[test]
+    fn test_get_heatmap_lines() {
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(18, 0, 0).with_timezone(&Utc);
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(10, 30, 0)),
+            start((day + Duration::days(3)).and_hms(9, 0, 0), "coding"),
+            stop((day + Duration::days(3)).and_hms(9, 45, 0)),
+        ];
+        let sessions = get_sessions(&data, true);
+        let settings = Settings::default();
+
+        assert_eq!(
+            vec![
+                "date,minutes",
+                "2021-03-29,90",
+                "2021-03-30,0",
+                "2021-03-31,0",
+                "2021-04-01,45",
+            ],
+            get_heatmap_lines(&settings, &sessions, None, None, now)
+        );
+        assert_eq!(
+            vec!["date,minutes", "2021-03-28,0", "2021-03-29,90"],
+            get_heatmap_lines(
+                &settings,
+                &sessions,
+                Some(NaiveDate::from_ymd(2021, 3, 28)),
+                Some(NaiveDate::from_ymd(2021, 3, 29)),
+                now
+            )
+        );
+    }
+
+    #[test]
+    fn test_stop_all() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(17, 0, 0).with_timezone(&Utc);
+        let mut data = vec![start(day.and_hms(9, 0, 0), "coding")];
+
+        assert_eq!(1, stop_all(&settings, &mut data, None, now).unwrap());
+        assert_eq!(
+            vec![
+                start(day.and_hms(9, 0, 0), "coding"),
+                stop(day.and_hms(17, 0, 0)),
+            ],
+            data
+        );
+        assert_eq!(0, stop_all(&settings, &mut data, None, now).unwrap());
+        assert_eq!(2, data.len());
+        assert_eq!(0, stop_all(&settings, &mut Vec::new(), None, now).unwrap());
+    }
+
+    #[test]
+    fn test_empty_description() {
+        let settings = Settings::default();
+        let mut data = Vec::new();
+        let at = Some("2021-03-29 09:00:00".to_string());
+
+        start_tracking(
+            &settings,
+            &mut data,
+            Some("  ".to_string()),
+            Labels::default(),
+            at.clone(),
+            false,
+            false,
+        )
+        .unwrap();
+        assert_eq!(None, data[0].description());
+        stop_tracking(&settings, &mut data, Some("".to_string()), None, false).unwrap();
+        assert_eq!(None, data[1].description());
+
+        start_tracking(
+            &settings,
+            &mut data,
+            Some("  ".to_string()),
+            Labels::default(),
+            at,
+            true,
+            false,
+        )
+        .unwrap();
+        assert_eq!(Some("  ".to_string()), data[2].description());
+        assert_eq!(
+            Some(" coding ".to_string()),
+            normalize_description(Some(" coding ".to_string()), false)
+        );
+    }
+
+    #[test]
+    fn test_negate_format() {
+        assert_eq!("-{hh}:{mm}:{ss}", negate_format("{hh}:{mm}:{ss}"));
+        assert_eq!("Worked -{hh}h {mm}m", negate_format("Worked {hh}h {mm}m"));
+        assert_eq!("{pct}% -{m}m", negate_format("{pct}% {m}m"));
+        assert_eq!("{pct}%", negate_format("{pct}%"));
+    }
+
+    #[test]
+    fn test_format_preset() {
+        assert_eq!(
+            "07:05:09",
+            format_preset(Preset::Clock, Decimals::default(), 7, 5, 9)
+        );
+        assert_eq!(
+            "7h05",
+            format_preset(Preset::Compact, Decimals::default(), 7, 5, 9)
+        );
+        assert_eq!(
+            "7.50",
+            format_preset(Preset::Decimal, Decimals::default(), 7, 30, 0)
+        );
+        assert_eq!(
+            "7 hours 5 minutes",
+            format_preset(Preset::Words, Decimals::default(), 7, 5, 9)
+        );
+        assert_eq!(
+            "1 hour 1 minute",
+            format_preset(Preset::Words, Decimals::default(), 1, 1, 0)
+        );
+        assert_eq!(
+            "0 hours 0 minutes",
+            format_preset(Preset::Words, Decimals::default(), 0, 0, 0)
+        );
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_import_events() {
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+        ];
+        let imported = vec![
+            start(day.and_hms(10, 0, 0), "reviews"),
+            stop(day.and_hms(11, 0, 0)),
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+        ];
+
+        assert_eq!(
+            imported,
+            import_events(&data, imported.clone(), ImportMode::Replace)
+        );
+        assert_eq!(
+            vec![
+                start(day.and_hms(8, 0, 0), "coding"),
+                stop(day.and_hms(9, 0, 0)),
+                start(day.and_hms(10, 0, 0), "reviews"),
+                stop(day.and_hms(11, 0, 0)),
+            ],
+            import_events(&data, imported, ImportMode::Merge)
+        );
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_detect_import_format() {
+        let json = r#"[{"Start":{"description":"coding","time":1617001200}}]"#;
+        let with_meta = r#"{
+            "meta": {},
+            "events": [{"Start":{"description":"coding","time":1617001200}}]
+        }"#;
+        let ndjson = r#"{"Start":{"description":"coding","time":1617001200}}
+            {"Stop":{"description":null,"time":1617004800}}"#;
+        let csv = "start,stop,hours,description\n2021-03-29 09:00:00,,,coding\n";
+        let readable = "Start at 2021-03-29 09:00:00 \"coding, tests\"\n";
+
+        assert_eq!(ImportFormat::Json, detect_import_format(json).unwrap());
+        assert_eq!(ImportFormat::Json, detect_import_format(with_meta).unwrap());
+        assert_eq!(ImportFormat::Ndjson, detect_import_format(ndjson).unwrap());
+        assert_eq!(ImportFormat::Csv, detect_import_format(csv).unwrap());
+        assert_eq!(
+            ImportFormat::Readable,
+            detect_import_format(readable).unwrap()
+        );
+        assert!(detect_import_format("hello").is_err());
+        assert_eq!(
+            "the file could be csv or readable. choose one with --format",
+            detect_import_format("Start at 09:00, start")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_parse_import_events() {
+        let time_format = "%Y-%m-%d %H:%M:%S";
+        let data = vec![
+            start(Local.ymd(2021, 3, 29).and_hms(9, 0, 0), "coding, \"tests\""),
+            stop(Local.ymd(2021, 3, 29).and_hms(10, 20, 0)),
+            start(Local.ymd(2021, 3, 29).and_hms(11, 0, 0), "meeting"),
+        ];
+        let readable = get_human_readable(&data, time_format, Zone::System).join("\n");
+        let csv = get_csv_lines(
+            &get_sessions(&data, true),
+            ',',
+            Decimals::default(),
+            false,
+            Zone::System,
+        )
+        .join("\n");
+        let ndjson: Vec<_> = data
+            .iter()
+            .map(|event| serde_json::to_string(event).unwrap())
+            .collect();
+
+        for (contents, format) in [
+            (readable, ImportFormat::Readable),
+            (csv, ImportFormat::Csv),
+            (ndjson.join("\n"), ImportFormat::Ndjson),
+        ] {
+            let (events, failures) =
+                parse_import_events(&contents, format, time_format, Zone::System).unwrap();
+            assert_eq!((data.clone(), Vec::<String>::new()), (events, failures));
+        }
+
+        let (events, failures) = parse_import_events(
+            "start,description\n2021-03-29 09:00:00,coding\nyesterday,coding\n",
+            ImportFormat::Csv,
+            time_format,
+            Zone::System,
+        )
+        .unwrap();
+        assert_eq!(1, events.len());
+        assert_eq!(1, failures.len());
+        assert!(failures[0].starts_with("line 3: "));
+        assert!(
+            parse_import_events("stop\n", ImportFormat::Csv, time_format, Zone::System).is_err()
+        );
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_parse_json_events_leniently() {
+        let contents = r#"[
+            { "Start": { "description": "coding", "time": 1617001200 } },
+            { "Stop": { "description": null, "time": "yesterday" } },
+            { "Stop": { "description": null, "time": 1617004800 } },
+            { "Pause": { "description": null, "time": 1617006600 } },
+            { "Start": { "description": null, "time": 1617008400 } }
+        ]"#;
+        let (events, failures) = parse_json_events_leniently(contents).unwrap();
+
+        assert_eq!(3, events.len());
+        assert_eq!(Some("coding".to_string()), events[0].description());
+        assert_eq!(2, failures.len());
+        assert_eq!(
+            "entry 2: field \"Stop.time\" should be an integer of seconds since the unix epoch, \
+             found the string \"yesterday\"",
+            failures[0]
+        );
+        assert_eq!(
+            "entry 4: unknown kind \"Pause\", expected \"Start\" or \"Stop\"",
+            failures[1]
+        );
+        assert!(serde_json::from_str::<Vec<TrackingEvent>>(contents).is_err());
+
+        let with_meta = format!(r#"{{ "meta": {{}}, "events": {} }}"#, contents);
+        assert_eq!(events, parse_json_events_leniently(&with_meta).unwrap().0);
+        assert!(parse_json_events_leniently("[").is_err());
+    }
+
+    #[test]
+    fn test_get_notification() {
+        let settings = Settings::default();
+        let monday = Local.ymd(2021, 3, 29);
+        let tuesday = monday.succ();
+        let data = vec![
+            start(monday.and_hms(8, 0, 0), "coding"),
+            stop(monday.and_hms(18, 0, 0)),
+            start(tuesday.and_hms(8, 0, 0), "coding"),
+            stop(tuesday.and_hms(12, 0, 0)),
+        ];
+
+        let notification = get_notification(&settings, &data, tuesday.naive_local());
+        assert_eq!(
+            serde_json::json!({
+                "text": "Today: 4:00 of 8:00 (50%), week: 14:00 of 40:00 (35%)",
+                "content": "Today: 4:00 of 8:00 (50%), week: 14:00 of 40:00 (35%)",
+                "today": { "worked": 240, "goal": 480, "remaining": 240, "percentage": 50 },
+                "week": { "worked": 840, "goal": 2400, "remaining": 1560, "percentage": 35 },
+            }),
+            serde_json::to_value(&notification).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(Tz::Europe__Vienna, parse_timezone("Europe/Vienna").unwrap());
+        assert!(parse_timezone("Europe/Nowhere").is_err());
+    }
+
+    #[test]
+    fn test_redact_descriptions() {
+        let day = Local.ymd(2021, 3, 1);
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "call with ACME about invoice"),
+            stop(day.and_hms(10, 0, 0)),
+            start(day.and_hms(10, 0, 0), "coding"),
+            TrackingEvent::Stop(TrackingData::new(
+                Some("reviewed acme-2 and Initech".to_string()),
+                day.and_hms(11, 0, 0).with_timezone(&Utc),
+            )),
+        ];
+        let patterns = vec![
+            parse_regex("(?i)acme(-\\d+)?").unwrap(),
+            parse_regex("Initech").unwrap(),
+        ];
+
+        let redacted = redact_descriptions(&data, &patterns);
+        assert_eq!(
+            Some("call with *** about invoice".to_string()),
+            redacted[0].description()
+        );
+        assert_eq!(data[1..3], redacted[1..3]);
+        assert_eq!(
+            Some("reviewed *** and ***".to_string()),
+            redacted[3].description()
+        );
+        assert_eq!(data[0].time(true), redacted[0].time(true));
+        assert!(parse_regex("(").is_err());
+    }
+
+    #[test]
+    fn test_round_session_boundaries() {
+        let time = |h, m, s| Local.ymd(2021, 3, 1).and_hms(h, m, s).with_timezone(&Utc);
+        let session = |start, stop| Session {
+            start,
+            stop,
+            description: Some("coding".to_string()),
+            labels: Labels::default(),
+        };
+
+        let rounded = round_session_boundaries(
+            vec![session(time(9, 2, 0), Some(time(9, 58, 0)))],
+            5,
+            Zone::System,
+        );
+        assert_eq!(vec![session(time(9, 0, 0), Some(time(10, 0, 0)))], rounded);
+        assert_eq!(
+            Duration::minutes(60),
+            rounded[0].end(now()) - rounded[0].start
+        );
+
+        // boundaries stay, the start of the second session isn't rounded into the first one
+        assert_eq!(
+            vec![
+                session(time(9, 0, 0), Some(time(9, 15, 0))),
+                session(time(9, 15, 0), Some(time(9, 30, 0))),
+                session(time(9, 30, 0), None),
+            ],
+            round_session_boundaries(
+                vec![
+                    session(time(9, 0, 0), Some(time(9, 14, 59))),
+                    session(time(9, 14, 59), Some(time(9, 30, 0))),
+                    session(time(9, 31, 0), None),
+                ],
+                15,
+                Zone::System
+            )
+        );
+        assert!(parse_boundaries("7").is_err());
+        assert!(parse_boundaries("0").is_err());
+        assert_eq!(5, parse_boundaries("5").unwrap());
+    }
+
+    #[test]
+    fn test_merge_adjacent_sessions() {
+        let time = |h, m, s| Local.ymd(2021, 3, 1).and_hms(h, m, s).with_timezone(&Utc);
+        let session = |start, stop, description: &str| Session {
+            start,
+            stop,
+            description: Some(description.to_string()),
+            labels: Labels::default(),
+        };
+        let sessions = vec![
+            session(time(9, 0, 0), Some(time(9, 30, 0)), "coding"),
+            session(time(9, 30, 30), Some(time(10, 0, 0)), "coding"),
+            session(time(10, 0, 30), Some(time(10, 30, 0)), "meeting"),
+            session(time(10, 35, 0), Some(time(11, 0, 0)), "meeting"),
+            session(time(11, 0, 10), None, "meeting"),
+        ];
+
+        assert_eq!(
+            vec![
+                session(time(9, 0, 0), Some(time(10, 0, 0)), "coding"),
+                session(time(10, 0, 30), Some(time(10, 30, 0)), "meeting"),
+                session(time(10, 35, 0), None, "meeting"),
+            ],
+            merge_adjacent_sessions(sessions.clone(), Duration::minutes(1))
+        );
+        assert_eq!(
+            sessions,
+            merge_adjacent_sessions(sessions.clone(), Duration::seconds(10))
+        );
+    }
+
+    #[test]
+    fn test_get_hourly_work_time() {
+        let time = |h, m| Local.ymd(2021, 3, 1).and_hms(h, m, 0).with_timezone(&Utc);
+        let sessions = vec![
+            Session {
+                start: time(9, 30),
+                stop: Some(time(11, 15)),
+                description: Some("coding".to_string()),
+                labels: Labels::default(),
+            },
+            Session {
+                start: time(23, 50),
+                stop: None,
+                description: None,
+                labels: Labels::default(),
+            },
+        ];
+        let hours = get_hourly_work_time(
+            &sessions,
+            time(23, 50) + Duration::minutes(20),
+            Zone::System,
+        );
+
+        let mut expected = [Duration::zero(); 24];
+        expected[9] = Duration::minutes(30);
+        expected[10] = Duration::minutes(60);
+        expected[11] = Duration::minutes(15);
+        // a running session counts until now, across midnight
+        expected[23] = Duration::minutes(10);
+        expected[0] = Duration::minutes(10);
+        assert_eq!(expected, hours);
+
+        let lines = get_hourly_lines(&hours, true);
+        assert_eq!(24, lines.len());
+        assert_eq!(format!("09:00 {:<40} 00:30:00", "#".repeat(20)), lines[9]);
+        assert_eq!(format!("10:00 {} 01:00:00", "#".repeat(40)), lines[10]);
+        assert_eq!(format!("12:00 {:<40} 00:00:00", ""), lines[12]);
+        assert_eq!("11:00 00:15:00", get_hourly_lines(&hours, false)[11]);
+    }
+
+    #[test]
+    fn test_get_focus() {
+        let settings = Settings::default();
+        let session = |day: u32, hour: u32, minutes: i64, description: Option<&str>| Session {
+            start: Local
+                .ymd(2021, 3, day)
+                .and_hms(hour, 0, 0)
+                .with_timezone(&Utc),
+            stop: Some(
+                Local
+                    .ymd(2021, 3, day)
+                    .and_hms(hour, 0, 0)
+                    .with_timezone(&Utc)
+                    + Duration::minutes(minutes),
+            ),
+            description: description.map(str::to_string),
+            labels: Labels::default(),
+        };
+        let sessions = vec![
+            session(1, 9, 60, Some("coding")),
+            session(1, 10, 30, Some("meeting")),
+            session(1, 11, 60, Some("coding")),
+            session(1, 13, 30, Some("coding")),
+            session(2, 9, 60, Some("review")),
+            session(2, 10, 60, None),
+        ];
+        let now = Utc.ymd(2021, 3, 3).and_hms(0, 0, 0);
+
+        assert_eq!(
+            Focus {
+                days: 2,
+                sessions: 6,
+                average_session: Duration::minutes(50),
+                // the switch to "review" is on the next day and doesn't count
+                switches: 3,
+                descriptions_per_day: 2.0,
+            },
+            get_focus(&settings, &sessions, now)
+        );
+        assert_eq!(0, get_focus(&settings, &[], now).sessions);
+    }
+
+    #[test]
+    fn test_get_streaks() {
+        let mut settings = Settings::default();
+        let monday = NaiveDate::from_ymd(2021, 3, 29);
+        let work = |days: i64, hours: u32| {
+            let date = monday + Duration::days(days);
+            vec![
+                start(
+                    Local.from_local_date(&date).unwrap().and_hms(8, 0, 0),
+                    "coding",
+                ),
+                stop(
+                    Local
+                        .from_local_date(&date)
+                        .unwrap()
+                        .and_hms(8 + hours, 0, 0),
+                ),
+            ]
+        };
+        let data: Vec<_> = vec![
+            work(0, 8),
+            work(1, 8),
+            work(2, 4),
+            work(3, 8),
+            work(4, 9),
+            work(7, 8),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let today = monday + Duration::days(8);
+
+        assert_eq!((3, 3), get_streaks(&settings, &data, today));
+        // an unfinished day doesn't break the streak yet, but a finished one does
+        let wednesday = monday + Duration::days(2);
+        assert_eq!((2, 2), get_streaks(&settings, &data, wednesday));
+        assert_eq!((1, 2), get_streaks(&settings, &data, wednesday.succ()));
+
+        settings.streak_free_days = StreakFreeDays::Met;
+        assert_eq!((5, 5), get_streaks(&settings, &data, today));
+        assert_eq!((0, 0), get_streaks(&settings, &[], today));
+    }
+
+    #[test]
+    fn test_json_data_round_trip() {
+        let dir = TempDir::new("json");
+        let path = dir.join("timetracking.json");
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+        ];
+
+        write_json_data(&path, &data, None, false).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with('['));
+        assert_eq!((None, data.clone()), read_json_file(&path).unwrap());
+
+        let meta = serde_json::json!({ "version": 1, "notes": "client A" });
+        write_json_data(&path, &data, Some(&meta), true).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().starts_with('{'));
+        assert_eq!((Some(meta), data.clone()), read_json_file(&path).unwrap());
+        assert_eq!(data, read_json_data(&path).unwrap());
+    }
+
+    #[test]
+    fn test_parse_shift() {
+        assert_eq!(Duration::hours(1), parse_shift("+1h").unwrap());
+        assert_eq!(Duration::minutes(-30), parse_shift("-30m").unwrap());
+        assert_eq!(Duration::minutes(90), parse_shift("1h30m").unwrap());
+        assert!(parse_shift("1").is_err());
+        assert_eq!(Duration::hours(36), parse_shift("1d12h").unwrap());
+        assert!(parse_shift("1w").is_err());
+        assert!(parse_shift("+").is_err());
+    }
+
+    #[test]
+    fn test_shift_events() {
+        let day = Local.ymd(2021, 3, 29);
+        let mut data = vec![
+            start(day.pred().and_hms(22, 0, 0), "coding"),
+            stop(day.pred().and_hms(23, 0, 0)),
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(13, 0, 0), "reviews"),
+            stop(day.and_hms(17, 0, 0)),
+            start(day.succ().and_hms(9, 0, 0), "coding"),
+        ];
+        let from = Some(DateOrDateTime::Date(day.naive_local()).range_start(Zone::System));
+        let to = Some(DateOrDateTime::Date(day.naive_local()).range_end(Zone::System));
+
+        let (count, warnings) = shift_events(
+            &mut data,
+            from,
+            to,
+            Duration::hours(1),
+            "%Y-%m-%d %H:%M:%S",
+            Zone::System,
+        );
+        assert_eq!(4, count);
+        assert!(warnings.is_empty());
+        assert_eq!(
+            vec![
+                start(day.pred().and_hms(22, 0, 0), "coding"),
+                stop(day.pred().and_hms(23, 0, 0)),
+                start(day.and_hms(10, 0, 0), "coding"),
+                stop(day.and_hms(13, 0, 0)),
+                start(day.and_hms(14, 0, 0), "reviews"),
+                stop(day.and_hms(18, 0, 0)),
+                start(day.succ().and_hms(9, 0, 0), "coding"),
+            ],
+            data
+        );
+
+        let (_, warnings) = shift_events(
+            &mut data,
+            from,
+            to,
+            Duration::hours(-11),
+            "%Y-%m-%d %H:%M:%S",
+            Zone::System,
+        );
+        assert_eq!(
+            vec!["The shifted entries move before the entry at 2021-03-28 23:00:00"],
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_delete_events() {
+        let day = Local.ymd(2021, 3, 29);
+        let before = vec![
+            start(day.pred().and_hms(22, 0, 0), "coding"),
+            stop(day.and_hms(1, 0, 0)),
+        ];
+        let after = vec![
+            start(day.succ().and_hms(9, 0, 0), "coding"),
+            stop(day.succ().and_hms(12, 0, 0)),
+        ];
+        let mut data = before.clone();
+        data.extend(vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            start(day.and_hms(12, 0, 0), "reviews"),
+            stop(day.and_hms(17, 0, 0)),
+        ]);
+        data.extend(after.clone());
+        let range = DateOrDateTime::Date(day.naive_local());
+
+        // the stop after midnight belongs to the session of the day before
+        assert_eq!(
+            3,
+            delete_events(&mut data, range, range, "%H:%M", Zone::System).unwrap()
+        );
+        assert_eq!(before.into_iter().chain(after).collect::<Vec<_>>(), data);
+        assert_eq!(
+            0,
+            delete_events(&mut data, range, range, "%H:%M", Zone::System).unwrap()
+        );
+
+        let mut data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            start(day.and_hms(12, 0, 0), "reviews"),
+            stop(day.and_hms(17, 0, 0)),
+        ];
+        let at = |hour| DateOrDateTime::DateTime(day.and_hms(hour, 0, 0).naive_local());
+        assert_eq!(
+            "the stop at 17:00 would lose its start. move --to after it",
+            delete_events(&mut data, at(8), at(13), "%H:%M", Zone::System)
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "the session of the start at 09:00 \"coding\" would lose its end. move --from before it",
+            delete_events(&mut data, at(10), at(13), "%H:%M", Zone::System)
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(3, data.len());
+
+        // equal entries are deleted by their place in the data
+        let reviews = vec![
+            start(day.and_hms(11, 0, 0), "reviews"),
+            stop(day.and_hms(12, 0, 0)),
+        ];
+        let mut data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(10, 0, 0)),
+        ];
+        data.extend(reviews.clone());
+        assert_eq!(
+            3,
+            delete_events(&mut data, at(9), at(10), "%H:%M", Zone::System).unwrap()
+        );
+        assert_eq!(reviews, data);
+    }
+
+    #[test]
+    fn test_filter_events_exclude() {
+        let settings = Settings::default();
+        let day = Local::today();
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(10, 0, 0)),
+            start(day.and_hms(10, 0, 0), "team meeting"),
+            stop(day.and_hms(11, 0, 0)),
+            start(day.and_hms(11, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(13, 0, 0), "meeting with client"),
+            stop(day.and_hms(13, 30, 0)),
+        ];
+        let mut filter = FilterData::default();
+        let total = |filter: &FilterData| {
+            get_time_from_events(
+                &settings,
+                &filter_events(&data, filter, Zone::System).unwrap(),
+                false,
+            )
+        };
+
+        assert_eq!(Duration::minutes(270), total(&filter));
+        filter.exclude = vec!["meeting".to_string()];
+        assert_eq!(Duration::hours(3), total(&filter));
+        filter.exclude = vec!["client".to_string(), "team".to_string()];
+        assert_eq!(Duration::hours(3), total(&filter));
+        filter.filter = Some("meeting".to_string());
+        filter.include_unlabeled = true;
+        filter.exclude = vec!["client".to_string()];
+        assert_eq!(Duration::hours(1), total(&filter));
+    }
+
+    #[test]
+    fn test_split_negative_duration() {
+        let duration = Duration::hours(1) + Duration::minutes(30) + Duration::seconds(5);
+
+        assert_eq!((1, 30, 5, false), split_duration(duration));
+        assert_eq!((1, 30, 5, true), split_duration(-duration));
+        assert_eq!((0, 1, 30, true), split_duration(Duration::seconds(-90)));
+        assert_eq!((0, 45, 0, true), split_duration(Duration::minutes(-45)));
+        assert_eq!((0, 0, 0, false), split_duration(Duration::zero()));
+        assert_eq!("-01:30:05", format_duration(-duration));
+        assert_eq!("-00:45:00", format_duration(Duration::minutes(-45)));
+        assert_eq!("00:00:00", format_duration(Duration::zero()));
+    }
+
+    #[test]
+    fn test_truncate_to_minutes() {
+        let split = |minutes, seconds| {
+            split_duration(truncate_to_minutes(
+                Duration::minutes(minutes) + Duration::seconds(seconds),
+            ))
+        };
+
+        assert_eq!((0, 59, 0, false), split(59, 30));
+        assert_eq!((0, 59, 0, false), split(59, 45));
+        assert_eq!((1, 59, 0, false), split(119, 45));
+        assert_eq!((2, 0, 0, false), split(120, 0));
+        assert_eq!((0, 59, 0, true), split(-59, -45));
+        assert_eq!(
+            "1.98",
+            format_preset(Preset::Decimal, Decimals::default(), 1, 59, 0)
+        );
+    }
+
+    #[test]
+    fn test_saturating_durations() {
+        let near_max = Duration::max_value() - Duration::hours(1);
+
+        assert_eq!(
+            Duration::max_value(),
+            saturating_add(near_max, Duration::hours(2))
+        );
+        assert_eq!(
+            Duration::max_value() - Duration::minutes(30),
+            saturating_add(near_max, Duration::minutes(30))
+        );
+        assert_eq!(
+            Duration::min_value(),
+            saturating_add(-near_max, Duration::hours(-2))
+        );
+        assert_eq!(
+            Duration::min_value(),
+            saturating_sub(-near_max, Duration::hours(2))
+        );
+        assert_eq!(
+            Duration::max_value(),
+            saturating_sub(near_max, Duration::hours(-2))
+        );
+
+        let settings = Settings::default();
+        let day = Local.ymd(262_000, 1, 1);
+        let data = vec![start(day.and_hms(8, 0, 0), ""), stop(day.and_hms(16, 0, 0))];
+        assert_eq!(
+            Duration::hours(8),
+            get_time_from_events(&settings, &data, true)
+        );
+    }
+
+    #[test]
+    fn test_get_list_entries() {
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(12, 30, 0), "meeting"),
+            stop(day.and_hms(13, 0, 0)),
+            start(day.and_hms(13, 0, 0), "coding"),
+        ];
+        let filter = FilterData {
+            from: Some("2021-03-29".to_string()),
+            filter: Some("coding".to_string()),
+            include_unlabeled: true,
+            ..FilterData::default()
+        };
+        let entries = get_list_entries(
+            &filter_events(&data, &filter, Zone::System).unwrap(),
+            false,
+            Zone::System,
+        );
+        let time = |hour, minute| day.and_hms(hour, minute, 0).to_rfc3339();
+
+        assert_eq!(
+            serde_json::json!([
+                { "kind": "start", "time": time(8, 0), "description": "coding" },
+                { "kind": "stop", "time": time(12, 0), "description": null },
+                { "kind": "stop", "time": time(13, 0), "description": null },
+                { "kind": "start", "time": time(13, 0), "description": "coding" },
+            ]),
+            serde_json::to_value(&entries).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_continue_tracking_at() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(10, 0, 0).with_timezone(&Utc);
+        let mut data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+        ];
+
+        assert!(continue_tracking(
+            &settings,
+            &mut data,
+            None,
+            Some("2021-03-29 08:59:00".to_string()),
+            now
+        )
+        .is_err());
+        assert!(continue_tracking(
+            &settings,
+            &mut data,
+            None,
+            Some("2021-03-29 10:00:01".to_string()),
+            now
+        )
+        .is_err());
+        assert_eq!(2, data.len());
+
+        continue_tracking(
+            &settings,
+            &mut data,
+            None,
+            Some("2021-03-29 09:15:00".to_string()),
+            now,
+        )
+        .unwrap();
+        assert_eq!(start(day.and_hms(9, 15, 0), "coding"), data[2]);
+    }
+
+    #[test]
+    fn test_continue_tracking() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(10, 0, 0).with_timezone(&Utc);
+        let error = |data: &mut Vec<TrackingEvent>| {
+            continue_tracking(&settings, data, None, None, now)
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error(&mut Vec::new()).contains("there are no entries"));
+        let mut stops = vec![stop(day.and_hms(8, 0, 0)), stop(day.and_hms(9, 0, 0))];
+        assert!(error(&mut stops).contains("there is no start before the last stop"));
+        assert_eq!(2, stops.len());
+        let mut running = vec![start(day.and_hms(8, 0, 0), "coding")];
+        assert_eq!("the time tracking is already running", error(&mut running));
+
+        let mut data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+        ];
+        continue_tracking(&settings, &mut data, None, None, now).unwrap();
+        assert_eq!(start(day.and_hms(10, 0, 0), "coding"), data[2]);
+
+        // --new records its own description, but only if the time tracking is stopped
+        let new = |data: &mut Vec<TrackingEvent>| {
+            continue_tracking(&settings, data, Some("review".to_string()), None, now)
+        };
+        assert_eq!(
+            "the time tracking is already running",
+            new(&mut data).unwrap_err().to_string()
+        );
+        let mut data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+        ];
+        new(&mut data).unwrap();
+        assert_eq!(start(day.and_hms(10, 0, 0), "review"), data[2]);
+        assert!(new(&mut Vec::new()).is_err());
+
+        // like start, a blank description is dropped
+        data.push(stop(day.and_hms(10, 0, 0)));
+        continue_tracking(&settings, &mut data, Some("  ".to_string()), None, now).unwrap();
+        assert_eq!(None, data[4].description());
+    }
+
+    #[test]
+    fn test_get_binding_remaining_minutes() {
+        let settings = Settings::default();
+        let remaining = |today: i64, week: i64| {
+            let monday = NaiveDate::from_ymd(2021, 3, 29);
+            let daily = get_remaining_minutes(&settings, "", monday, 0, today);
+            let weekly = get_remaining_minutes(&settings, "week", monday, 0, week);
+            (daily, weekly, get_binding_remaining_minutes(daily, weekly))
+        };
+
+        // monday morning, nothing worked yet: the daily goal binds
+        assert_eq!((480, 2400, 480), remaining(0, 0));
+        // wednesday after two long days: the daily goal still binds
+        assert_eq!((300, 1380, 300), remaining(180, 1020));
+        // friday after four long days: the weekly goal binds
+        assert_eq!((360, 120, 120), remaining(120, 2280));
+        // behind on the week, the remaining never exceeds the daily goal
+        assert_eq!((420, 1800, 420), remaining(60, 600));
+        // a goal is exceeded: nothing remains
+        assert_eq!((-60, 1860, 0), remaining(540, 540));
+        assert_eq!((240, -30, 0), remaining(240, 2430));
+    }
+
+    #[test]
+    fn test_render_template() {
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 30, 0)),
+            start(day.and_hms(10, 0, 0), ""),
+        ];
+        let sessions = get_sessions(&data, true);
+        let template = "\\begin{tabular}{llrl}\n\
+                        {{#each sessions}}\n\
+                        {{start}} & {{stop}} & {{minutes}} & {{description}} \\\\\n\
+                        {{/each}}\n\
+                        \\end{tabular} % {{count}} sessions, {{total}}\n";
+
+        assert_eq!(
+            "\\begin{tabular}{llrl}\n\
+             08:00 & 09:30 & 90 & coding \\\\\n\
+             10:00 &  & 30 &  \\\\\n\
+             \\end{tabular} % 2 sessions, 02:00:00\n",
+            render_template(
+                template,
+                &sessions,
+                "%H:%M",
+                day.and_hms(10, 30, 0).into(),
+                Zone::System
+            )
+            .unwrap()
+        );
+        let render = |template| {
+            render_template(
+                template,
+                &sessions,
+                "%H:%M",
+                day.and_hms(10, 0, 0).into(),
+                Zone::System,
+            )
+        };
+        assert_eq!(
+            "coding 01:30:00; 00:00:00;",
+            render("{{description}} {{duration}};").unwrap()
+        );
+        assert_eq!(
+            "\\textbf{coding}\\textbf{}",
+            render("\\textbf{{{description}}}").unwrap()
+        );
+
+        let error = |template| render(template).unwrap_err().to_string();
+        assert_eq!(
+            "unknown placeholder {{descripton}} in the template",
+            error("{{descripton}}")
+        );
+        assert_eq!(
+            "unknown placeholder {{start}} outside of the {{#each sessions}} block",
+            error("{{start}}{{#each sessions}}{{stop}}{{/each}}")
+        );
+        assert_eq!(
+            "a {{ in the template isn't closed with }}",
+            error("{{description")
+        );
+        assert_eq!(
+            "the template can only have one {{#each sessions}} block",
+            error("{{#each sessions}}{{#each sessions}}{{start}}{{/each}}{{/each}}")
+        );
+        assert_eq!(
+            "the template can only have one {{#each sessions}} block",
+            error("{{#each sessions}}{{start}}{{/each}}{{#each sessions}}{{stop}}{{/each}}")
+        );
+        assert_eq!(
+            "the {{#each sessions}} block of the template isn't closed with {{/each}}",
+            error("{{#each sessions}}{{start}}")
+        );
+    }
+
+    #[test]
+    fn test_get_rolling_events() {
+        let settings = Settings::default();
+        let now = Local.ymd(2021, 3, 29).and_hms(12, 0, 0);
+        let mut data = Vec::new();
+        for days_ago in (0..10).rev() {
+            let day = now.date() - Duration::days(days_ago);
+            data.push(start(day.and_hms(9, 0, 0), "coding"));
+            data.push(stop(day.and_hms(11, 0, 0)));
+        }
+        let total = |window: &str| {
+            let events = get_rolling_events(
+                &data,
+                &FilterData::default(),
+                parse_window(window).unwrap(),
+                now.naive_local(),
+                Zone::System,
+            );
+            get_time_from_events(&settings, &events, false)
+        };
+
+        // today and the six days before, the session 7 days ago started before the window
+        assert_eq!(Duration::hours(14), total("7d"));
+        assert_eq!(Duration::hours(4), total("2d"));
+        assert_eq!(Duration::hours(2), total("3h"));
+        assert!(parse_window("-7d").is_err());
+        assert_eq!("7d", format_window(Duration::days(7)));
+        assert_eq!("1d12h30m", format_window(Duration::minutes(2190)));
+    }
+
+    #[test]
+    fn test_amend_last_event() {
+        let day = Local.ymd(2021, 3, 29);
+        let mut data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(12, 30, 0), "coding"),
+        ];
+
+        amend_last_event(&mut data, Duration::minutes(5), "%H:%M", Zone::System).unwrap();
+        assert_eq!(start(day.and_hms(12, 25, 0), "coding"), data[2]);
+        assert_eq!(stop(day.and_hms(12, 0, 0)), data[1]);
+
+        let error =
+            amend_last_event(&mut data, Duration::minutes(25), "%H:%M", Zone::System).unwrap_err();
+        assert_eq!(
+            "the entry would move before the entry at 12:00",
+            error.to_string()
+        );
+        assert_eq!(start(day.and_hms(12, 25, 0), "coding"), data[2]);
+        assert!(amend_last_event(&mut [], Duration::minutes(5), "%H:%M", Zone::System).is_err());
+    }
+
+    #[test]
+    fn test_reopen_session() {
+        let day = Local.ymd(2021, 3, 29);
+        let now = day.and_hms(12, 20, 0).with_timezone(&Utc);
+        let max_age = Duration::minutes(30);
+        let mut data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+        ];
+
+        reopen_session(&mut data, max_age, now, "%H:%M", Zone::System).unwrap();
+        assert_eq!(vec![start(day.and_hms(9, 0, 0), "coding")], data);
+        assert!(reopen_session(&mut data, max_age, now, "%H:%M", Zone::System).is_err());
+        assert!(reopen_session(&mut Vec::new(), max_age, now, "%H:%M", Zone::System).is_err());
+
+        data.push(stop(day.and_hms(11, 0, 0)));
+        let error = reopen_session(&mut data, max_age, now, "%H:%M", Zone::System).unwrap_err();
+        assert_eq!(
+            "the last stop at 11:00 is older than 30 minutes (reopen_max_minutes). use continue instead",
+            error.to_string()
+        );
+        assert_eq!(2, data.len());
+    }
+
+    #[test]
+    fn test_get_goal_color() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let mut data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+        ];
+        assert_eq!(RED, get_goal_color(&settings, &data, day.naive_local()));
+
+        data.push(start(day.and_hms(13, 0, 0), "coding"));
+        data.push(stop(day.and_hms(17, 0, 0)));
+        assert_eq!(GREEN, get_goal_color(&settings, &data, day.naive_local()));
+        // no goal on the weekend
+        assert_eq!(
+            GREEN,
+            get_goal_color(&settings, &[], day.naive_local() + Duration::days(5))
+        );
+
+        // a running session counts until now
+        let settings = Settings {
+            last_day_of_work_week: Weekday::Sun,
+            ..Settings::default()
+        };
+        let running = [start(Local::now() - Duration::minutes(1), "coding")];
+        assert_eq!(
+            YELLOW,
+            get_goal_color(&settings, &running, Local::today().naive_local())
+        );
+    }
+
+    #[test]
+    fn test_epoch_timestamps() {
+        let time = Utc.ymd(2024, 3, 5).and_hms(8, 40, 0);
+        assert_eq!(time, parse_date_time("@1709628000", Zone::System).unwrap());
+        assert_eq!(
+            DateOrDateTime::DateTime(time.with_timezone(&Local).naive_local()),
+            parse_date_or_date_time("@1709628000", Zone::System).unwrap()
+        );
+        assert!(parse_date_time("@soon", Zone::System).is_err());
+
+        let data = [start(time.with_timezone(&Local), "coding")];
+        assert_eq!(
+            serde_json::json!([{ "kind": "start", "time": 1_709_628_000, "description": "coding" }]),
+            serde_json::to_value(get_list_entries(&data, true, Zone::System)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_session_limit_warning() {
+        let mut settings = Settings::default();
+        settings.session_limits.insert("meeting".to_string(), 60);
+        settings
+            .session_limits
+            .insert("Team Meeting".to_string(), 30);
+        let day = Local.ymd(2021, 3, 29);
+        let mut data = vec![start(day.and_hms(9, 0, 0), "Team Meeting")];
+
+        stop_tracking(
+            &settings,
+            &mut data,
+            None,
+            Some("2021-03-29 09:20:00".to_string()),
+            false,
+        )
+        .unwrap();
+        assert_eq!(None, get_session_limit_warning(&settings, &data));
+
+        data.push(start(day.and_hms(10, 0, 0), "client meeting"));
+        stop_tracking(
+            &settings,
+            &mut data,
+            None,
+            Some("2021-03-29 12:10:00".to_string()),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            Some("the \"client meeting\" session ran 2h10m, over the 1h soft limit".to_string()),
+            get_session_limit_warning(&settings, &data)
+        );
+
+        data.push(start(day.and_hms(13, 0, 0), "coding"));
+        stop_tracking(
+            &settings,
+            &mut data,
+            None,
+            Some("2021-03-29 18:00:00".to_string()),
+            false,
+        )
+        .unwrap();
+        assert_eq!(None, get_session_limit_warning(&settings, &data));
+    }
+
+    #[test]
+    fn test_get_matrix() {
+        let settings = Settings::default();
+        let monday = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(monday.and_hms(9, 0, 0), "client a"),
+            stop(monday.and_hms(12, 0, 0)),
+            start(monday.and_hms(13, 0, 0), "client b"),
+            stop(monday.and_hms(14, 30, 0)),
+            start(monday.and_hms(14, 30, 0), "client a"),
+            stop(monday.and_hms(16, 0, 0)),
+            start((monday + Duration::days(7)).and_hms(9, 0, 0), "client b"),
+            stop((monday + Duration::days(7)).and_hms(17, 0, 0)),
+        ];
+        let sessions = get_sessions(&data, true);
+        let table = get_matrix(
+            &settings,
+            &sessions,
+            &SortOptions::default(),
+            Decimals::default(),
+            Utc::now(),
+        );
+
+        assert_eq!(
+            vec![
+                "Description  2021-W13  2021-W14  Total",
+                "client b         1.50      8.00   9.50",
+                "client a         4.50             4.50",
+                "Total            6.00      8.00  14.00",
+            ],
+            render_table(&table, TableFormat::Text, ',', false)
+        );
+        assert_eq!(
+            "client a,4.50,,4.50",
+            render_table(&table, TableFormat::Csv, ',', false)[2]
+        );
+        assert_eq!(
+            vec![
+                "| Description | 2021-W13 | 2021-W14 | Total |",
+                "| --- | ---: | ---: | ---: |",
+            ],
+            render_table(&table, TableFormat::Markdown, ',', false)[..2].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_with_assumed_stop() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(13, 0, 0), "coding"),
+        ];
+        let total = |assume_stopped: Option<Option<AssumedStop>>| {
+            let option = AssumeStopped { assume_stopped };
+            get_time_from_events(
+                &settings,
+                &with_assumed_stop(&data, &option, Zone::System).unwrap(),
+                true,
+            )
+        };
+
+        // without the flag the dangling session runs until now
+        assert!(total(None) > Duration::days(365));
+        assert_eq!(Duration::hours(4), total(Some(None)));
+        assert_eq!(Duration::hours(4), total(Some(Some(AssumedStop::Start))));
+        assert_eq!(
+            Duration::hours(4) + Duration::seconds(39599),
+            total(Some(Some(AssumedStop::EndOfDay)))
+        );
+        let at = "2021-03-29 15:30:00".parse().unwrap();
+        assert_eq!(Duration::minutes(390), total(Some(Some(at))));
+        let before = AssumeStopped {
+            assume_stopped: Some(Some("2021-03-29 12:30:00".parse().unwrap())),
+        };
+        assert!(with_assumed_stop(&data, &before, Zone::System).is_err());
+    }
+
+    #[test]
+    fn test_get_page() {
+        let entries: Vec<usize> = (1..=7).collect();
+        let page = |page, per_page| get_page(entries.clone(), page, per_page);
+        assert_eq!(
+            (vec![1, 2, 3], "page 1/3 (7 entries)".to_string()),
+            page(1, 3)
+        );
+        assert_eq!(
+            (vec![4, 5, 6], "page 2/3 (7 entries)".to_string()),
+            page(2, 3)
+        );
+        assert_eq!((vec![7], "page 3/3 (7 entries)".to_string()), page(3, 3));
+        assert_eq!((vec![], "page 4/3 (7 entries)".to_string()), page(4, 3));
+        assert_eq!(
+            (entries.clone(), "page 1/1 (7 entries)".to_string()),
+            page(1, 7)
+        );
+        assert_eq!(
+            (vec![], "page 3/1 (7 entries)".to_string()),
+            page(3, usize::MAX)
+        );
+        assert_eq!(
+            (vec![], "page 1/1 (0 entries)".to_string()),
+            get_page(Vec::<usize>::new(), 1, 50)
+        );
+
+        assert_eq!(
+            (entries.clone(), None),
+            paginate(entries.clone(), false, None, None)
+        );
+        assert_eq!(
+            (vec![5, 4], Some("page 2/4 (7 entries)".to_string())),
+            paginate(entries.clone(), true, Some(2), Some(2))
+        );
+        assert_eq!(
+            (entries.clone(), Some("page 1/1 (7 entries)".to_string())),
+            paginate(entries.clone(), false, Some(1), None)
+        );
+        assert!(parse_page("0").is_err());
+        assert_eq!(2, parse_page("2").unwrap());
+    }
+
+    #[test]
+    fn test_get_average() {
+        let settings = Settings::default();
+        let monday = Local.ymd(2021, 3, 29);
+        let day = |offset| monday + Duration::days(offset);
+        let data = vec![
+            start(day(0).and_hms(8, 0, 0), "coding"),
+            stop(day(0).and_hms(12, 0, 0)),
+            start(day(1).and_hms(9, 0, 0), "coding"),
+            stop(day(1).and_hms(13, 0, 0)),
+            start(day(3).and_hms(9, 0, 0), "coding"),
+            stop(day(3).and_hms(11, 0, 0)),
+        ];
+        let work_time = get_time_from_events(&settings, &data, true);
+        assert_eq!(Duration::hours(10), work_time);
+
+        let average = |over, range| get_average(&settings, &data, work_time, over, range);
+        assert_eq!(
+            Duration::hours(3) + Duration::minutes(20),
+            average(AverageOver::Active, None)
+        );
+        // without a range, the calendar days are those from the first to the last tracked day
+        assert_eq!(
+            Duration::hours(2) + Duration::minutes(30),
+            average(AverageOver::Calendar, None)
+        );
+        let week = Some((day(0).naive_local(), day(6).naive_local()));
+        assert_eq!(
+            Duration::seconds(36000 / 7),
+            average(AverageOver::Calendar, week)
+        );
+        assert_eq!(
+            Duration::zero(),
+            get_average(&settings, &[], Duration::zero(), AverageOver::Active, None)
+        );
+        assert_eq!(
+            Duration::zero(),
+            get_average(
+                &settings,
+                &[],
+                Duration::zero(),
+                AverageOver::Calendar,
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn test_get_fte() {
+        let settings = Settings::default();
+        let monday = NaiveDate::from_ymd(2021, 3, 29);
+        let fte = |work_time, to| {
+            get_fte(&settings, work_time, monday, to).map(|fte| format!("{:.2}", fte))
+        };
+
+        assert_eq!(Some("0.75".to_string()), fte(Duration::hours(6), monday));
+        // the weekend has no daily goal, so the week has five days of 8h
+        assert_eq!(
+            Some("0.75".to_string()),
+            fte(Duration::hours(30), monday + Duration::days(6))
+        );
+        let saturday = NaiveDate::from_ymd(2021, 4, 3);
+        assert_eq!(
+            None,
+            get_fte(&settings, Duration::hours(2), saturday, saturday)
+        );
+    }
+
+    #[test]
+    fn test_list_only() {
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(13, 0, 0), "meeting"),
+            stop(day.and_hms(13, 45, 0)),
+            start(day.and_hms(14, 0, 0), "coding"),
+        ];
+        let time_format = "%H:%M";
+        let list = |only| {
+            get_human_readable(
+                &get_list_events(data.clone(), only),
+                time_format,
+                Zone::System,
+            )
+        };
+
+        assert_eq!(
+            vec![
+                "Start at 08:00 \"coding\"",
+                "Start at 13:00 \"meeting\"",
+                "Start at 14:00 \"coding\"",
+            ],
+            list(Some(ListOnly::Starts))
+        );
+        assert_eq!(
+            vec!["Stop  at 12:00", "Stop  at 13:45"],
+            list(Some(ListOnly::Stops))
+        );
+        assert_eq!(5, list(None).len());
+        assert_eq!(
+            data,
+            get_list_events(data.clone(), Some(ListOnly::Sessions))
+        );
+
+        let now = day.and_hms(15, 30, 0).with_timezone(&Utc);
+        assert_eq!(
+            vec![
+                "08:00 - 12:00 (04:00:00) \"coding\"",
+                "13:00 - 13:45 (00:45:00) \"meeting\"",
+                "14:00 - ongoing (01:30:00) \"coding\"",
+            ],
+            get_session_lines(&data, time_format, now, Zone::System)
+        );
+    }
+
+    #[test]
+    fn test_auto_continue() {
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+        ];
+        let start_at = |settings: &Settings, description: Option<&str>| {
+            let mut data = data.clone();
+            let at = Some("2021-03-29 13:00:00".to_string());
+            let description = description.map(ToString::to_string);
+            start_tracking(
+                settings,
+                &mut data,
+                description,
+                Labels::default(),
+                at,
+                false,
+                false,
+            )
+            .unwrap();
+            data[2].description()
+        };
+
+        let settings = Settings::default();
+        assert_eq!(None, start_at(&settings, None));
+
+        let settings = Settings {
+            auto_continue: true,
+            ..Settings::default()
+        };
+        assert_eq!(Some("coding".to_string()), start_at(&settings, None));
+        assert_eq!(
+            Some("review".to_string()),
+            start_at(&settings, Some("review"))
+        );
+    }
+
+    #[test]
+    fn test_get_gaps() {
+        let settings = Settings::default();
+        let day = Zone::System.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "early"),
+            stop(day.and_hms(9, 10, 0)),
+            start(day.and_hms(11, 0, 0), "coding"),
+            stop(day.and_hms(14, 0, 0)),
+        ];
+        let date = day.naive_local();
+        let now = day.and_hms(20, 0, 0).with_timezone(&Utc);
+
+        assert_eq!(
+            vec![
+                (day.and_hms(9, 10, 0), day.and_hms(11, 0, 0)),
+                (day.and_hms(14, 0, 0), day.and_hms(17, 0, 0)),
+            ],
+            get_gaps(&settings, &data, date, date, now)
+        );
+        // the afternoon hasn't happened yet and saturday has no daily goal
+        let now = day.and_hms(14, 10, 0).with_timezone(&Utc);
+        assert_eq!(
+            vec![(day.and_hms(9, 10, 0), day.and_hms(11, 0, 0))],
+            get_gaps(&settings, &data, date, date + Duration::days(5), now)
+        );
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_data_format_version() {
+        let dir = TempDir::new("version");
+        let path = dir.join("timetracking.bin");
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(8, 0, 0), "coding"),
+            stop(day.and_hms(9, 0, 0)),
+        ];
+
+        let mut labeled = data.clone();
+        labeled.push(TrackingEvent::Start(TrackingData {
+            labels: Labels {
+                project: Some("acme".to_string()),
+                tags: vec!["urgent".to_string()],
+            },
+            ..TrackingData::new(None, day.and_hms(10, 0, 0).with_timezone(&Utc))
+        }));
+        write_data(&path, &labeled, true).unwrap();
+        let stored = std::fs::read(&path).unwrap();
+        assert!(stored.starts_with(b"TTDF\x02"));
+        assert_eq!(labeled, read_data(&path).unwrap());
+
+        // version 1 and files without a header, of tt 1.5, have no labels
+        let v1: Vec<_> = data
+            .iter()
+            .map(|event| {
+                let data = BinaryDataV1 {
+                    description: event.description(),
+                    time: event.time(true),
+                };
+                iif!(
+                    event.is_start(),
+                    BinaryEvent::Start(data),
+                    BinaryEvent::Stop(data)
+                )
+            })
+            .collect();
+        let v1 = bincode::serialize(&v1).unwrap();
+        std::fs::write(&path, [b"TTDF\x01".as_ref(), &v1].concat()).unwrap();
+        assert_eq!(data, read_data(&path).unwrap());
+        std::fs::write(&path, v1).unwrap();
+        assert_eq!(data, read_data(&path).unwrap());
+
+        let mut future = stored;
+        future[DATA_MAGIC.len()] = DATA_VERSION + 1;
+        std::fs::write(&path, future).unwrap();
+        let error = read_data(&path).unwrap_err().to_string();
+        assert!(error.contains("version 3 of the data format"));
     }
 
-    for mut conflicting in all_conflicting {
-        let event_type = iif!(
-            conflicting
-                .first()
-                .expect("Nothing first tracking event founded")
-                .is_start(),
-            "start",
-            "stop"
+    #[test]
+    fn test_auto_insert_stop() {
+        let settings = Settings {
+            auto_insert_stop: true,
+            ..Settings::default()
+        };
+        let mut data = vec![start(Local.ymd(2021, 3, 29).and_hms(8, 0, 0), "coding")];
+        let before = Utc::now();
+        let reviews = Some("reviews".to_string());
+        assert!(start_tracking(
+            &settings,
+            &mut data,
+            reviews,
+            Labels::default(),
+            None,
+            false,
+            false
+        )
+        .unwrap());
+        let after = Utc::now();
+
+        assert_eq!(3, data.len());
+        assert!(data[1].is_stop());
+        assert!(before <= data[2].time(true) && data[2].time(true) <= after);
+        assert_eq!(
+            Duration::seconds(1),
+            data[2].time(true) - data[1].time(true)
         );
-        println!("Repeated {} events found:", event_type);
-        for (i, event) in conflicting.iter().enumerate() {
-            println!(
-                "({}) {}",
-                i,
-                to_human_readable(
-                    &format!("S{}", &event_type[1..]),
-                    &event.time(true).with_timezone(&Local),
-                    event.description()
-                )
-            );
-        }
-        loop {
-            println!();
-            println!("Please enter the number of the entry to keep (<num>|skip) [default: skip]: ");
-            let mut input = String::new();
-            match io::stdin().read_line(&mut input) {
-                Ok(_) => {
-                    let text = input.trim();
-                    if text == "skip" || text.is_empty() {
-                        cleaned.append(&mut conflicting);
-                        break;
-                    } else {
-                        let parsed: Result<usize, _> = text.parse();
-                        match parsed {
-                            Ok(n) => match conflicting.get(n) {
-                                Some(value) => {
-                                    cleaned.push(value);
-                                    break;
-                                }
-                                None => println!("Please use one of the numbers given above!"),
-                            },
-                            Err(_) => println!("Could not parse number!"),
-                        }
-                    }
-                }
-                Err(_) => println!("Could not read from stdin!"),
-            }
-        }
     }
 
-    cleaned.iter().map(Clone::clone).cloned().collect()
-}
+    #[test]
+    fn test_resume_if_same() {
+        let settings = Settings {
+            auto_insert_stop: true,
+            ..Settings::default()
+        };
+        let running = vec![start(Local.ymd(2021, 3, 29).and_hms(8, 0, 0), "coding")];
+        let start_again = |description: &str, resume_if_same| {
+            let mut data = running.clone();
+            let description = Some(description.to_string());
+            let added = start_tracking(
+                &settings,
+                &mut data,
+                description,
+                Labels::default(),
+                None,
+                false,
+                resume_if_same,
+            )
+            .unwrap();
+            (added, data.len())
+        };
 
-fn status(data: &[TrackingEvent]) {
-    if let Some(event) = data.last() {
-        let time = event.time(true).with_timezone(&Local);
-        let active = event.is_start();
-        let text = iif!(active, "Start", "End");
-        if let Some(description) = event.description() {
-            println!("Active: {}", active);
-            println!("Description: {}", description,);
-            println!(
-                "{} Time: {:02}:{:02}:{:02}",
-                text,
-                time.hour(),
-                time.minute(),
-                time.second()
-            );
-        } else {
-            println!("Active: {}", active);
-            println!(
-                "{} Time: {:02}:{:02}:{:02}",
-                text,
-                time.hour(),
-                time.minute(),
-                time.second()
-            );
-        }
-        std::process::exit(iif!(active, 0, -1));
-    } else {
-        println!("No Events found!");
-        std::process::exit(-1);
+        assert_eq!((false, 1), start_again("coding", true));
+        assert_eq!((true, 3), start_again("reviews", true));
+        assert_eq!((true, 3), start_again("reviews", false));
+        // auto_insert_stop doesn't split a session with the same description
+        assert_eq!((false, 1), start_again("coding", false));
+
+        // also with --at, which otherwise adds a start to the running session
+        let mut data = running.clone();
+        let at = Some("2021-03-29 09:00:00".to_string());
+        assert!(!start_tracking(
+            &settings,
+            &mut data,
+            Some("coding".to_string()),
+            Labels::default(),
+            at,
+            false,
+            true,
+        )
+        .unwrap());
+        assert_eq!(running, data);
+
+        let mut data = vec![stop(Local.ymd(2021, 3, 29).and_hms(9, 0, 0))];
+        assert!(!stop_tracking(&settings, &mut data, None, None, false).unwrap());
+        assert_eq!(1, data.len());
     }
-}
 
-fn to_human_readable<Tz: TimeZone>(
-    prefix: &str,
-    time: &DateTime<Tz>,
-    description: Option<String>,
-) -> String {
-    let description = description
-        .map(|d| format!(" \"{}\"", d))
-        .unwrap_or_default();
-    format!(
-        "{} at {:04}-{:02}-{:02} {:02}:{:02}:{:02}{}",
-        prefix,
-        time.year(),
-        time.month(),
-        time.day(),
-        time.hour(),
-        time.minute(),
-        time.second(),
-        description,
-    )
-}
+    #[test]
+    fn test_get_goal_table() {
+        let settings = Settings::default();
+        let monday = Local.ymd(2021, 3, 29);
+        let day = |offset| monday + Duration::days(offset);
+        let data = vec![
+            start(day(0).and_hms(8, 0, 0), "coding"),
+            stop(day(0).and_hms(17, 0, 0)),
+            start(day(1).and_hms(9, 0, 0), "coding"),
+            stop(day(1).and_hms(15, 0, 0)),
+            start(day(2).and_hms(9, 0, 0), "coding"),
+            stop(day(2).and_hms(17, 0, 0)),
+            start(day(5).and_hms(10, 0, 0), "coding"),
+            stop(day(5).and_hms(11, 30, 0)),
+        ];
+        let monday = monday.naive_local();
+        let sunday = monday + Duration::days(6);
 
-fn get_human_readable(data: &[TrackingEvent]) -> Vec<String> {
-    data.iter()
-        .map(|event| match event {
-            TrackingEvent::Start(TrackingData { time, description }) => {
-                to_human_readable("Start", &time.with_timezone(&Local), description.clone())
-            }
-            TrackingEvent::Stop(TrackingData { time, description }) => {
-                to_human_readable("Stop ", &time.with_timezone(&Local), description.clone())
-            }
-        })
-        .collect::<Vec<_>>()
-}
+        assert_eq!(
+            vec![
+                "Date             Goal  Actual    Diff",
+                "2021-03-29 Mon   8.00    9.00   +1.00",
+                "2021-03-30 Tue   8.00    6.00   -2.00",
+                "2021-03-31 Wed   8.00    8.00    0.00",
+                "2021-04-01 Thu   8.00    0.00   -8.00",
+                "2021-04-02 Fri   8.00    0.00   -8.00",
+                "2021-04-03 Sat   0.00    1.50   +1.50",
+                "2021-04-04 Sun   0.00    0.00    0.00",
+                "Total           40.00   24.50  -15.50",
+            ],
+            render_table(
+                &get_goal_table(&settings, &data, monday, sunday, false, Decimals::default()),
+                TableFormat::Text,
+                ',',
+                false
+            )
+        );
+        let table = get_goal_table(&settings, &data, monday, sunday, true, Decimals::default());
+        assert_eq!(8, table.len());
+        assert_eq!("2021-04-03 Sat", table[6][0]);
+    }
 
-fn export_human_readable(path: String, data: &[TrackingEvent]) {
-    let lines = get_human_readable(data);
-    std::fs::write(path, lines.join("\n")).expect("could not export file");
-}
+    #[cfg(unix)]
+    #[test]
+    fn test_private_data_file() {
+        use std::os::unix::fs::PermissionsExt;
 
-fn main() -> Result<()> {
-    let Options { command, data_file, config_file } = Options::from_args();
+        let dir = TempDir::new("private");
+        let path = dir.join("timetracking.bin");
+        let data = vec![start(Local.ymd(2021, 3, 29).and_hms(8, 0, 0), "coding")];
+        let mode = || std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
 
-    let settings = Settings::new(&config_file)?;
+        write_data(&path, &data, true).unwrap();
+        assert_eq!(0o600, mode());
 
-    let path = match data_file {
-        Some(path) => path,
-        None => shellexpand::full(&settings.data_file)?.parse()?,
-    };
-    let expanded_path = shellexpand::full(&path.to_string_lossy())
-        .expect("could not expand path")
-        .to_string();
-    let mut data = read_data(&expanded_path).unwrap_or_default();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        write_data(&path, &data, true).unwrap();
+        assert_eq!(0o600, mode());
+        assert_eq!(data, read_data(&path).unwrap());
 
-    let data_changed = match command.unwrap_or_default() {
-        Command::Start { description, at } => {
-            start_tracking(&settings, &mut data, description, at)?;
-            true
-        }
-        Command::Stop { description, at } => {
-            stop_tracking(&mut data, description, at)?;
-            true
-        }
-        Command::Continue => {
-            continue_tracking(&mut data);
-            true
-        }
-        Command::List { filter } => {
-            let data = filter_events(&data, &filter.from, &filter.to, &filter.filter)?;
-            for s in get_human_readable(&data) {
-                println!("{}", s);
-            }
-            false
-        }
-        Command::Path => {
-            println!("{}", expanded_path);
-            false
-        }
-        Command::Show {
-            format,
-            filter,
-            include_seconds,
-            plain,
-            remaining,
-        } => {
-            show(
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+        write_data(&path, &data, false).unwrap();
+        assert_eq!(0o640, mode());
+    }
+
+    #[test]
+    fn test_get_goal_period_remaining_minutes() {
+        let settings = Settings::default();
+        let work = |day: u32, hours: u32| {
+            vec![
+                start(Local.ymd(2021, 3, day).and_hms(8, 0, 0), "coding"),
+                stop(Local.ymd(2021, 3, day).and_hms(8 + hours, 0, 0)),
+            ]
+        };
+        // 8 hours in the first week of march and 8 + 8 + 3 hours in the week of wednesday 3/31
+        let data = [work(1, 8), work(29, 8), work(30, 8), work(31, 3)].concat();
+        let remaining = |period| {
+            get_goal_period_remaining_minutes(
                 &settings,
                 &data,
-                &filter,
-                format,
-                include_seconds,
-                plain,
-                remaining,
-            )?;
-            false
-        }
-        Command::Status => {
-            status(&data);
-            false
-        }
-        Command::Cleanup => {
-            data = cleanup(&data);
-            true
-        }
-        #[cfg(not(feature = "binary"))]
-        Command::Export { path } => {
-            let expanded_path = shellexpand::full(&path.to_string_lossy())
-                .expect("could not expand path")
-                .to_string();
-            export_human_readable(expanded_path, &data);
-            false
-        }
+                period,
+                NaiveDate::from_ymd(2021, 3, 31),
+                false,
+            )
+        };
 
-        #[cfg(feature = "binary")]
-        Command::Export {
-            path,
-            readable,
-            pretty,
-        } => {
-            let expanded_path = shellexpand::full(&path.to_string_lossy())
-                .expect("could not expand path")
-                .to_string();
-            if readable {
-                export_human_readable(expanded_path, &data);
-            } else {
-                write_json_data(expanded_path, &data, pretty).expect("Could not write file");
-            }
-            false
-        }
-        #[cfg(feature = "binary")]
-        Command::Import { path } => {
-            data = read_json_data(path)?;
-            true
-        }
-        #[allow(unreachable_patterns)]
-        _ => unimplemented!(),
-    };
+        assert_eq!(5 * 60, remaining(GoalPeriod::Daily));
+        assert_eq!((40 - 19) * 60, remaining(GoalPeriod::Weekly));
+        // march 2021 has 23 work days of 8 hours
+        assert_eq!((23 * 8 - 27) * 60, remaining(GoalPeriod::Monthly));
+    }
 
-    if data_changed {
-        data.sort_by_key(|e| e.time(true));
-        data.dedup();
-        write_data(expanded_path, &data).expect("Could not write file!");
+    #[test]
+    fn test_round_remaining_minutes() {
+        assert_eq!(95, round_remaining_minutes(97, 5));
+        assert_eq!(100, round_remaining_minutes(98, 5));
+        assert_eq!(90, round_remaining_minutes(97, 15));
+        // near zero the remaining time doesn't change its sign
+        assert_eq!(0, round_remaining_minutes(0, 5));
+        assert_eq!(0, round_remaining_minutes(2, 5));
+        assert_eq!(5, round_remaining_minutes(3, 5));
+        assert_eq!(0, round_remaining_minutes(-2, 5));
+        assert_eq!(-5, round_remaining_minutes(-3, 5));
+        assert_eq!(7, round_remaining_minutes(7, 1));
+        assert!(parse_round_remaining("0").is_err());
+        assert_eq!(5, parse_round_remaining("5").unwrap());
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_show_remaining_target() {
+        let settings = Settings::default();
+        let target = parse_target("06:00").unwrap();
 
-fn parse_date_time(s: &str) -> Result<DateTime<Utc>> {
-    let from_time = |s: &str| NaiveTime::parse_from_str(s, "%H:%M:%S");
-    let from_date_time = |s: &str| Local.datetime_from_str(s, "%Y-%m-%d %H:%M:%S");
+        assert_eq!(
+            120,
+            get_show_remaining_minutes(&settings, &[], "", 240, false, Some(target)).unwrap()
+        );
+        assert_eq!(
+            240,
+            get_show_remaining_minutes(&settings, &[], "", 240, false, None).unwrap()
+        );
+        assert_eq!(
+            0,
+            get_show_remaining_minutes(&settings, &[], "", 400, false, Some(target)).unwrap()
+        );
+        assert_eq!(Duration::minutes(1950), parse_target("32:30").unwrap());
+        assert!(parse_target("6h").is_err());
+        assert!(parse_target("06:60").is_err());
+    }
 
-    from_time(s)
-        .or_else(|_| from_time(&format!("{}:0", s)))
-        .or_else(|_| from_time(&format!("{}:0:0", s)))
-        .map_err(Into::into)
-        .and_then(|time| Local::today().and_time(time).context("invalid time"))
-        .or_else(|_| {
-            from_date_time(s)
-                .or_else(|_| from_date_time(&format!("{}:0", s)))
-                .or_else(|_| from_date_time(&format!("{}:0:0", s)))
-        })
-        .map(|date_time| date_time.with_timezone(&Utc))
-        .map_err(Into::into)
-}
+    #[test]
+    fn test_get_timeline() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 1);
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(10, 20, 0)),
+        ];
+        let now = Utc.ymd(2021, 3, 2).and_hms(0, 0, 0);
 
-fn parse_date_or_date_time(s: &str) -> Result<DateOrDateTime> {
-    if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
-        return Ok(date.into());
+        assert_eq!(
+            vec![
+                " 00    06    12    18   24",
+                "|         #              |",
+                "# 01:20:00 coding",
+            ],
+            get_timeline(&settings, &data, day.naive_local(), 60, false, now)
+        );
+        assert_eq!(
+            // 10:00 to 10:30 is tracked for 20 minutes, more than half of the slice
+            format!(
+                "|{}{}{}|",
+                " ".repeat(18),
+                format!("{}#{}", GREEN, RESET).repeat(3),
+                " ".repeat(27)
+            ),
+            get_timeline(&settings, &data, day.naive_local(), 30, true, now)[1]
+        );
+        assert_eq!(20, get_timeline_slice(80));
+        assert_eq!(60, get_timeline_slice(20));
     }
-    if let Ok(date_time) = NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S") {
-        return Ok(date_time.into());
+
+    #[test]
+    fn test_filter_workdays() {
+        let settings = Settings {
+            holidays: vec![NaiveDate::from_ymd(2021, 4, 1)],
+            ..Settings::default()
+        };
+        let friday = Local.ymd(2021, 4, 2);
+        let saturday = friday.succ();
+        let data = vec![
+            start(friday.pred().and_hms(9, 0, 0), "holiday"),
+            start(friday.and_hms(9, 0, 0), "coding"),
+            stop(friday.and_hms(17, 0, 0)),
+            start(saturday.and_hms(10, 0, 0), "coding"),
+            stop(saturday.and_hms(12, 0, 0)),
+        ];
+
+        assert_eq!(data, filter_workdays(&settings, &data, false));
+        assert_eq!(
+            vec![
+                start(friday.and_hms(9, 0, 0), "coding"),
+                stop(friday.and_hms(17, 0, 0)),
+            ],
+            filter_workdays(&settings, &data, true)
+        );
     }
 
-    parse_date_time(s).map(|date_time| date_time.with_timezone(&Local).naive_local().into())
-}
+    #[test]
+    fn test_filter_billable() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            start(day.and_hms(10, 0, 0), "support #nb"),
+            stop(day.and_hms(11, 0, 0)),
+            start(day.and_hms(13, 0, 0), "#nb call"),
+            start(day.and_hms(13, 30, 0), "reviews"),
+            stop(day.and_hms(14, 0, 0)),
+        ];
+        let billable = |billable_only, nonbillable_only| {
+            let filter = BillableFilter {
+                billable_only,
+                nonbillable_only,
+            };
+            filter_billable(&settings, &data, &filter)
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        assert_eq!(data, billable(false, false));
+        assert_eq!(
+            vec![
+                start(day.and_hms(9, 0, 0), "coding"),
+                stop(day.and_hms(10, 0, 0)),
+                start(day.and_hms(13, 30, 0), "reviews"),
+                stop(day.and_hms(14, 0, 0)),
+            ],
+            billable(true, false)
+        );
+        assert_eq!(
+            Duration::hours(1) + Duration::minutes(30),
+            get_time_from_events(&settings, &billable(true, false), true)
+        );
+        assert_eq!(
+            Duration::hours(1) + Duration::minutes(30),
+            get_time_from_events(&settings, &billable(false, true), true)
+        );
+
+        let settings = Settings {
+            billable_tag: "#client".to_string(),
+            ..Settings::default()
+        };
+        assert!(is_billable(
+            &settings,
+            &Some("#client meeting".to_string()),
+            &Labels::default()
+        ));
+        assert!(!is_billable(
+            &settings,
+            &Some("coding".to_string()),
+            &Labels::default()
+        ));
+        assert!(!is_billable(
+            &settings,
+            &Some("#client #nb".to_string()),
+            &Labels::default()
+        ));
+        assert!(!is_billable(&settings, &None, &Labels::default()));
+    }
 
     #[test]
-    fn test_parse_date_time() {
+    fn test_structured_labels() {
+        let day = Local.ymd(2021, 3, 29);
+        let labeled = |hour, description: &str, project: Option<&str>, tags: &[&str]| {
+            TrackingEvent::Start(TrackingData {
+                labels: Labels {
+                    project: project.map(str::to_string),
+                    tags: tags.iter().map(|tag| tag.to_string()).collect(),
+                },
+                ..TrackingData::new(
+                    Some(description.to_string()),
+                    day.and_hms(hour, 0, 0).with_timezone(&Utc),
+                )
+            })
+        };
+        let data = vec![
+            labeled(8, "fix bug", Some("acme"), &["urgent", "backend"]),
+            stop(day.and_hms(10, 0, 0)),
+            labeled(10, "review #backend", Some("initech"), &[]),
+            stop(day.and_hms(11, 0, 0)),
+            // the tags given with --tag replace the ones of the description
+            labeled(11, "mails #backend", Some("acme"), &["urgent"]),
+            stop(day.and_hms(11, 30, 0)),
+        ];
+        let filter = |project: Option<&str>, tags: &[&str]| FilterData {
+            filter: Some("all".to_string()),
+            project: project.map(str::to_string),
+            tag: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..FilterData::default()
+        };
+        let time = |filter| {
+            get_time_from_events(
+                &Settings::default(),
+                &filter_events(&data, &filter, Zone::System).unwrap(),
+                true,
+            )
+        };
+
+        assert_eq!(Duration::minutes(150), time(filter(Some("acme"), &[])));
+        assert_eq!(Duration::hours(3), time(filter(None, &["#backend"])));
+        assert_eq!(Duration::minutes(150), time(filter(None, &["urgent"])));
         assert_eq!(
-            Local::now().date().and_hms(0, 0, 15).with_timezone(&Utc),
-            parse_date_time("00:00:15").unwrap()
+            Duration::hours(2),
+            time(filter(Some("acme"), &["urgent", "backend"]))
         );
+        assert_eq!(Duration::zero(), time(filter(Some("initech"), &["urgent"])));
+
+        let summary = |group_by| {
+            get_summary(
+                &group_sessions(get_sessions(&data, true), group_by),
+                "(none)",
+                None,
+                &SortOptions::default(),
+                Utc::now(),
+            )
+        };
         assert_eq!(
-            Local::now().date().and_hms(0, 15, 0).with_timezone(&Utc),
-            parse_date_time("00:15").unwrap()
+            vec![
+                ("acme".to_string(), Duration::minutes(150)),
+                ("initech".to_string(), Duration::hours(1)),
+            ],
+            summary(GroupBy::Project)
         );
         assert_eq!(
-            Local::now().date().and_hms(15, 0, 0).with_timezone(&Utc),
-            parse_date_time("15").unwrap()
+            vec![
+                ("backend".to_string(), Duration::hours(3)),
+                ("urgent".to_string(), Duration::minutes(150)),
+            ],
+            summary(GroupBy::Tag)
         );
 
+        let settings = Settings {
+            billable_tag: "#client".to_string(),
+            ..Settings::default()
+        };
+        let client = Labels {
+            tags: vec!["client".to_string()],
+            ..Labels::default()
+        };
+        assert!(is_billable(
+            &settings,
+            &Some("meeting".to_string()),
+            &client
+        ));
+        assert!(!is_billable(
+            &settings,
+            &Some("#client".to_string()),
+            &Labels {
+                tags: vec!["x".to_string()],
+                ..Labels::default()
+            }
+        ));
+
+        // the json of events without labels doesn't change
         assert_eq!(
-            Local.ymd(2021, 4, 1).and_hms(0, 0, 15).with_timezone(&Utc),
-            parse_date_time("2021-04-01 00:00:15").unwrap()
+            r#"{"Stop":{"description":null,"time":1617012000}}"#,
+            serde_json::to_string(&data[1]).unwrap()
+        );
+        let json = serde_json::to_string(&data[0]).unwrap();
+        assert!(json.ends_with(r#""project":"acme","tags":["urgent","backend"]}}"#));
+        assert_eq!(data[0], serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_export_split_by_day() {
+        let settings = Settings::default();
+        let day = Local.ymd(2021, 3, 29);
+        let data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(22, 0, 0), "release"),
+            stop(day.succ().and_hms(1, 0, 0)),
+        ];
+        // the export creates the directory
+        let temp_dir = TempDir::new("split");
+        let dir = temp_dir.join("days");
+        let options = ExportOptions::from_iter(&["export", "--split-by", "day", "all"]);
+
+        let path = dir.to_string_lossy();
+        let decimals = Decimals::default();
+        export(
+            &settings,
+            &path,
+            &data,
+            ExportFormat::Json,
+            false,
+            &options,
+            decimals,
+        )
+        .unwrap();
+
+        let mut files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(vec!["2021-03-29.json", "2021-03-30.json"], files);
+        let read = |name: &str| {
+            let json = std::fs::read_to_string(dir.join(name)).unwrap();
+            serde_json::from_str::<Vec<TrackingEvent>>(&json).unwrap()
+        };
+        assert_eq!(
+            vec![
+                start(day.and_hms(9, 0, 0), "coding"),
+                stop(day.and_hms(12, 0, 0)),
+                start(day.and_hms(22, 0, 0), "release"),
+                stop(day.succ().and_hms(0, 0, 0)),
+            ],
+            read("2021-03-29.json")
         );
         assert_eq!(
-            Local.ymd(2021, 4, 1).and_hms(0, 15, 0).with_timezone(&Utc),
-            parse_date_time("2021-04-01 00:15").unwrap()
+            vec![
+                start(day.succ().and_hms(0, 0, 0), "release"),
+                stop(day.succ().and_hms(1, 0, 0)),
+            ],
+            read("2021-03-30.json")
+        );
+    }
+
+    #[test]
+    fn test_decimals_rounding() {
+        let decimals = |rounding| Decimals {
+            precision: 2,
+            rounding,
+        };
+        let format = |rounding, value| decimals(rounding).format(value);
+
+        assert_eq!("0.13", format(RoundingMode::HalfUp, 0.125));
+        assert_eq!("0.12", format(RoundingMode::HalfEven, 0.125));
+        assert_eq!("0.12", format(RoundingMode::Floor, 0.125));
+        assert_eq!("0.13", format(RoundingMode::Ceil, 0.125));
+        assert_eq!("0.38", format(RoundingMode::HalfEven, 0.375));
+        assert_eq!("-0.13", format(RoundingMode::HalfUp, -0.125));
+        assert_eq!("-0.12", format(RoundingMode::HalfEven, -0.125));
+        assert_eq!("0.15", format(RoundingMode::HalfUp, 0.145));
+
+        // 7.5 minutes are 0.125 hours
+        let duration = Duration::seconds(450);
+        assert_eq!(
+            "0.13",
+            to_decimal_hours(duration, decimals(RoundingMode::HalfUp), false)
         );
         assert_eq!(
-            Local.ymd(2021, 4, 1).and_hms(15, 0, 0).with_timezone(&Utc),
-            parse_date_time("2021-04-01 15").unwrap()
+            "0,12",
+            to_decimal_hours(duration, decimals(RoundingMode::Floor), true)
         );
+        let decimals = Decimals {
+            precision: 1,
+            rounding: RoundingMode::HalfUp,
+        };
+        assert_eq!("7.5", format_preset(Preset::Decimal, decimals, 7, 30, 0));
     }
 
     #[test]
-    fn test_parse_date_or_date_time() {
+    fn test_wait_for_state() {
+        let day = Local.ymd(2021, 3, 29);
+        let dir = TempDir::new("wait");
+        let path = dir.join("timetracking.data");
+        let stopped = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+        ];
+        write_data(&path, &stopped, false).unwrap();
+        let second = Duration::seconds(1);
+
+        // another process starts the time tracking during the third wait
+        let mut waits = Vec::new();
+        let active = wait_for_state(&path, true, second, Some(Duration::minutes(1)), |wait| {
+            waits.push(wait);
+            if waits.len() == 3 {
+                let mut data = stopped.clone();
+                data.push(start(day.and_hms(13, 0, 0), "reviews"));
+                write_data(&path, &data, false).unwrap();
+            }
+        });
+        assert!(active.unwrap());
+        assert_eq!(vec![std::time::Duration::from_secs(1); 3], waits);
+
+        let mut waits = Vec::new();
+        assert!(wait_for_state(&path, true, second, None, |wait| waits.push(wait)).unwrap());
+        assert!(waits.is_empty());
+
+        let timeout = Some(Duration::seconds(3));
+        let inactive = wait_for_state(&path, false, Duration::seconds(2), timeout, |wait| {
+            waits.push(wait)
+        });
+        assert!(!inactive.unwrap());
         assert_eq!(
-            DateOrDateTime::Date(NaiveDate::from_ymd(2020, 4, 1)),
-            parse_date_or_date_time("2020-04-01").unwrap()
+            vec![
+                std::time::Duration::from_secs(2),
+                std::time::Duration::from_secs(1)
+            ],
+            waits
         );
+    }
+
+    #[test]
+    fn test_prepare_for_write() {
+        let day = Local.ymd(2021, 3, 29);
+        let mut data = vec![
+            start(day.and_hms(9, 0, 0), "coding"),
+            start(day.and_hms(9, 0, 0), "coding"),
+            stop(day.and_hms(12, 0, 0)),
+            start(day.and_hms(13, 0, 0), "coding"),
+            start(day.and_hms(13, 0, 0), "reviews"),
+            start(day.and_hms(13, 0, 1), "reviews"),
+        ];
+        prepare_for_write(&mut data);
         assert_eq!(
-            DateOrDateTime::DateTime(NaiveDate::from_ymd(2020, 4, 1).and_hms(12, 15, 20)),
-            parse_date_or_date_time("2020-04-01 12:15:20").unwrap()
+            vec![
+                start(day.and_hms(9, 0, 0), "coding"),
+                stop(day.and_hms(12, 0, 0)),
+                start(day.and_hms(13, 0, 0), "coding"),
+                start(day.and_hms(13, 0, 0), "reviews"),
+                start(day.and_hms(13, 0, 1), "reviews"),
+            ],
+            data
+        );
+    }
+
+    #[test]
+    fn test_holidays() {
+        let monday = NaiveDate::from_ymd(2021, 3, 29);
+        let wednesday = monday + Duration::days(2);
+        let settings = Settings {
+            holidays: vec![wednesday, monday + Duration::days(5)],
+            ..Settings::default()
+        };
+
+        // the saturday has no goal anyway, so only the wednesday lowers the weekly goal
+        assert_eq!(2400, get_goal_minutes(&Settings::default(), "week", monday));
+        assert_eq!(1920, get_goal_minutes(&settings, "week", monday));
+        assert_eq!(1920, get_goal_minutes(&settings, "week", wednesday));
+        assert_eq!(
+            2400,
+            get_goal_minutes(&settings, "week", monday + Duration::days(7))
         );
+        assert_eq!(0, get_goal_minutes(&settings, "", wednesday));
+        assert_eq!(480, get_goal_minutes(&settings, "", monday));
+        assert_eq!(0, get_daily_goal_minutes(&settings, wednesday));
         assert_eq!(
-            DateOrDateTime::DateTime(NaiveDate::from_ymd(2020, 4, 1).and_hms(12, 0, 0)),
-            parse_date_or_date_time("2020-04-01 12").unwrap()
+            1920 - 600,
+            get_remaining_minutes(&settings, "week", monday, 10, 0)
+        );
+
+        let table = get_goal_table(
+            &settings,
+            &[],
+            monday,
+            wednesday,
+            false,
+            Decimals::default(),
         );
+        assert_eq!("2021-03-31 Wed Holiday", table[3][0]);
+        assert_eq!("0.00", table[3][1]);
+        assert_eq!("16.00", table[4][1]);
     }
 }
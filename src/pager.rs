@@ -0,0 +1,43 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const DEFAULT_PAGER: &str = "less -FRX";
+
+/// prints the lines through $PAGER if enabled and stdout is a terminal. falls back to printing
+/// directly if the pager cannot be started.
+pub fn print_lines(lines: &[String], enabled: bool) {
+    if !enabled || !atty::is(atty::Stream::Stdout) || !page(lines) {
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+}
+
+/// returns false if the pager could not be started
+fn page(lines: &[String]) -> bool {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    let mut args = pager.split_whitespace();
+    let program = match args.next() {
+        Some(program) => program,
+        None => return false,
+    };
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(_) => return false,
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager closes its input when the user quits early, which is not an error
+        for line in lines {
+            if writeln!(stdin, "{}", line).is_err() {
+                break;
+            }
+        }
+    }
+    let _ = child.wait();
+    true
+}
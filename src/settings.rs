@@ -1,9 +1,13 @@
+use chrono::{NaiveDate, NaiveTime, Weekday};
 use config::{Config, ConfigError, Environment, File, FileFormat};
-use chrono::Weekday;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
+use toml::value::{Table, Value};
 
+use std::collections::HashMap;
 use std::path::Path;
 
+use crate::zone::Zone;
+
 #[derive(Debug, Deserialize)]
 pub struct Time {
     pub hours: u8,
@@ -16,24 +20,253 @@ pub struct TimeGoal {
     pub weekly: Time,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Notify {
+    pub url: String,
+    pub template: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Gaps {
+    #[serde(deserialize_with = "deserialize_time")]
+    pub start: NaiveTime,
+    #[serde(deserialize_with = "deserialize_time")]
+    pub end: NaiveTime,
+    pub min_minutes: u32,
+}
+
+/// what happens when a new event falls into the same second as the last one
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SameSecondEvents {
+    Nudge,
+    Error,
+}
+
+/// how decimal hours are rounded to the configured precision
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingMode {
+    HalfUp,
+    HalfEven,
+    Floor,
+    Ceil,
+}
+
+/// the line ending of the exported text files
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+/// how days without a daily goal count towards a streak
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StreakFreeDays {
+    Met,
+    Skip,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Settings {
+    pub version: u32,
     pub data_file: String,
     pub auto_insert_stop: bool,
+    pub auto_continue: bool,
+    pub resume_if_same: bool,
+    pub reopen_max_minutes: u32,
+    // read from the config before it is deserialized, see add_discovered_files
+    #[allow(dead_code)]
     pub enable_project_settings: bool,
     pub time_goal: TimeGoal,
     pub min_daily_break: u8,
+    pub carryover: bool,
+    pub subtract_suspend: bool,
+    pub streak_free_days: StreakFreeDays,
     pub last_day_of_work_week: Weekday,
+    pub list_time_format: String,
+    pub prompt_format: String,
+    pub idle_format: String,
+    pub same_second_events: SameSecondEvents,
+    pub rounding_mode: RoundingMode,
+    pub export_line_ending: LineEnding,
+    pub unlabeled_description: String,
+    pub billable_tag: String,
+    pub nonbillable_tag: String,
+    pub notify: Notify,
+    pub gaps: Gaps,
+    pub backup_count: usize,
+    #[serde(deserialize_with = "deserialize_dates")]
+    pub holidays: Vec<NaiveDate>,
+    pub holidays_file: String,
+    #[serde(deserialize_with = "deserialize_time")]
+    pub day_start: NaiveTime,
+    pub fill_gaps_after: u32,
+    pub typical_day_length: Time,
+    pub fte_day_length: Time,
+    #[serde(default)]
+    pub session_limits: HashMap<String, u32>,
+    /// given with --tz, it can't be configured
+    #[serde(skip)]
+    pub zone: Zone,
+}
+
+/// accepts times as "HH:MM:SS" or "HH:MM"
+fn deserialize_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveTime, D::Error> {
+    let time = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(&time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(&time, "%H:%M"))
+        .map_err(serde::de::Error::custom)
+}
+
+/// accepts dates as "YYYY-MM-DD"
+fn deserialize_dates<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<NaiveDate>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+        .collect::<Result<_, _>>()
+        .map_err(serde::de::Error::custom)
+}
+
+/// the dates of a holidays file: the first word of every line which is not empty or a comment
+pub fn parse_holidays(contents: &str) -> Result<Vec<NaiveDate>, String> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|date| !date.starts_with('#'))
+        .map(|date| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| format!("invalid date in holidays_file: \"{}\"", date))
+        })
+        .collect()
+}
+
+/// the current version of the settings format. config files without a version are version 1
+pub const CURRENT_VERSION: i64 = 2;
+
+/// keys which were renamed, with the version which renamed them
+const RENAMED_KEYS: &[(i64, &str, &str)] = &[
+    (2, "notify_url", "notify.url"),
+    (2, "notify_template", "notify.template"),
+];
+
+/// splits "a.b.c" into "a" and "b.c"
+fn split_key(key: &str) -> Option<(&str, &str)> {
+    key.find('.').map(|i| (&key[..i], &key[i + 1..]))
+}
+
+fn take_key(table: &mut Table, key: &str) -> Option<Value> {
+    match split_key(key) {
+        Some((parent, rest)) => match table.get_mut(parent) {
+            Some(Value::Table(parent)) => take_key(parent, rest),
+            _ => None,
+        },
+        None => table.remove(key),
+    }
+}
+
+fn insert_key(table: &mut Table, key: &str, value: Value) {
+    match split_key(key) {
+        Some((parent, rest)) => {
+            let parent = table
+                .entry(parent)
+                .or_insert_with(|| Value::Table(Table::new()));
+            if let Value::Table(parent) = parent {
+                insert_key(parent, rest, value);
+            }
+        }
+        None => {
+            table.insert(key.to_string(), value);
+        }
+    }
 }
 
+/// upgrades the contents of a config file to the current version.
+/// returns None if the file already uses the current version.
+pub fn migrate(contents: &str) -> Result<Option<String>, ConfigError> {
+    let mut table: Table =
+        toml::from_str(contents).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+    let version = match table.get("version") {
+        Some(Value::Integer(version)) => *version,
+        Some(_) => return Err(ConfigError::Message("version must be a number".to_string())),
+        None => 1,
+    };
+    if version >= CURRENT_VERSION {
+        return Ok(None);
+    }
+    for (since, old, new) in RENAMED_KEYS {
+        if version < *since {
+            if let Some(value) = take_key(&mut table, old) {
+                insert_key(&mut table, new, value);
+            }
+        }
+    }
+    table.insert("version".to_string(), Value::Integer(CURRENT_VERSION));
+    // serializing a Value writes plain values before tables, as toml requires
+    toml::to_string(&Value::Table(table))
+        .map(Some)
+        .map_err(|e| ConfigError::Foreign(Box::new(e)))
+}
+
+/// merges the config file, migrated to the current version, if it exists
 fn add_file_if_exists(s: &mut Config, file: &str) -> Result<bool, ConfigError> {
-    let result = if Path::new(file).exists() {
-        s.merge(File::new(file, FileFormat::Toml).required(false))?;
-        true
-    } else {
-        false
+    if !Path::new(file).exists() {
+        return Ok(false);
+    }
+    let contents = std::fs::read_to_string(file).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+    match migrate(&contents)? {
+        Some(migrated) => s.merge(File::from_str(&migrated, FileFormat::Toml))?,
+        None => s.merge(File::from_str(&contents, FileFormat::Toml))?,
     };
-    Ok(result)
+    Ok(true)
+}
+
+/// merges the global config file, the project config file of the current directory or its
+/// parents and the local config file
+fn add_discovered_files(s: &mut Config) -> Result<(), ConfigError> {
+    add_file_if_exists(s, &global_config_path())?;
+
+    if s.get_bool("enable_project_settings")? {
+        let current_dir = std::env::current_dir().expect("Could not get current directory");
+        let mut path = current_dir.as_path();
+        if !add_file_if_exists(
+            s,
+            &format!("{}/timetracking.project.toml", path_to_string_lossy(path)),
+        )? {
+            while let Some(parent) = path.parent() {
+                if add_file_if_exists(
+                    s,
+                    &format!("{}/timetracking.project.toml", path_to_string_lossy(path)),
+                )? {
+                    break;
+                }
+                path = parent;
+            }
+        }
+    }
+
+    add_file_if_exists(s, ".timetracking.toml")?;
+    Ok(())
+}
+
+/// the path of the global config file
+pub fn global_config_path() -> String {
+    shellexpand::full("~/.config/timetracking/config.toml")
+        .expect("could not expand path")
+        .to_string()
 }
 
 fn path_to_string_lossy<P: AsRef<Path>>(path: P) -> String {
@@ -41,7 +274,9 @@ fn path_to_string_lossy<P: AsRef<Path>>(path: P) -> String {
 }
 
 impl Settings {
-    pub fn new(config_file: &Option<String>) -> Result<Self, ConfigError> {
+    /// the settings from the defaults, the discovered config files, the environment and
+    /// `config_file`. `config` replaces the discovered config files
+    pub fn new(config_file: &Option<String>, config: &Option<String>) -> Result<Self, ConfigError> {
         let mut s = Config::new();
 
         // Start off by merging in the "default" configuration file
@@ -56,32 +291,26 @@ impl Settings {
             config::FileFormat::Toml,
         ))?;
 
-        let config_path = shellexpand::full("~/.config/timetracking/config.toml")
-            .expect("could not expand path")
-            .to_string();
-        s.merge(File::with_name(config_path.as_str()).required(false))?;
-
-        if s.get_bool("enable_project_settings")? {
-            let current_dir = std::env::current_dir().expect("Could not get current directory");
-            let mut path = current_dir.as_path();
-            if !add_file_if_exists(
-                &mut s,
-                &format!("{}/timetracking.project.toml", path_to_string_lossy(&path)),
-            )? {
-                while let Some(parent) = path.parent() {
-                    if add_file_if_exists(
-                        &mut s,
-                        &format!("{}/timetracking.project.toml", path_to_string_lossy(&path)),
-                    )? {
-                        break;
-                    }
-                    path = parent;
+        match config {
+            Some(config) => {
+                let path = shellexpand::full(config)
+                    .map_err(|error| ConfigError::Message(error.to_string()))?;
+                let added = add_file_if_exists(&mut s, &path).map_err(|error| {
+                    ConfigError::Message(format!(
+                        "could not load the config file {}: {}",
+                        path, error
+                    ))
+                })?;
+                if !added {
+                    return Err(ConfigError::Message(format!(
+                        "the config file {} doesn't exist",
+                        path
+                    )));
                 }
             }
+            None => add_discovered_files(&mut s)?,
         }
 
-        s.merge(File::with_name(".timetracking.config").required(false))?;
-
         s.merge(Environment::with_prefix("tt"))?;
 
         if let Some(config_file) = config_file {
@@ -101,6 +330,87 @@ impl Settings {
         s.set("time_goal.weekly.minutes", weekly_minutes.min(59))?;
 
         // You can deserialize (and thus freeze) the entire configuration as
-        s.try_into()
+        let mut settings: Self = s.try_into()?;
+        if !settings.holidays_file.is_empty() {
+            let path = shellexpand::full(&settings.holidays_file)
+                .map_err(|error| ConfigError::Message(error.to_string()))?;
+            let contents = std::fs::read_to_string(path.as_ref()).map_err(|error| {
+                ConfigError::Message(format!("could not read holidays_file {}: {}", path, error))
+            })?;
+            settings
+                .holidays
+                .extend(parse_holidays(&contents).map_err(ConfigError::Message)?);
+        }
+        if i64::from(settings.version) > CURRENT_VERSION {
+            eprintln!(
+                "Warning: the config uses version {} of the settings, but this version of tt only knows version {}",
+                settings.version, CURRENT_VERSION
+            );
+        }
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+impl Default for Settings {
+    fn default() -> Self {
+        let mut s = Config::new();
+        s.merge(File::from_str(
+            include_str!("../default_config.toml"),
+            FileFormat::Toml,
+        ))
+        .expect("could not load default config");
+        s.try_into().expect("could not deserialize default config")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_version_1() {
+        let v1 = r#"
+min_daily_break = 30
+notify_url = "https://example.com/hook"
+notify_template = "{today_worked}"
+
+[time_goal.daily]
+hours = 6
+"#;
+        let migrated = migrate(v1).unwrap().unwrap();
+
+        let mut s = Config::new();
+        s.merge(File::from_str(
+            include_str!("../default_config.toml"),
+            FileFormat::Toml,
+        ))
+        .unwrap();
+        s.merge(File::from_str(&migrated, FileFormat::Toml))
+            .unwrap();
+        let settings: Settings = s.try_into().unwrap();
+
+        assert_eq!(2, settings.version);
+        assert_eq!("https://example.com/hook", settings.notify.url);
+        assert_eq!("{today_worked}", settings.notify.template);
+        assert_eq!(30, settings.min_daily_break);
+        assert_eq!(6, settings.time_goal.daily.hours);
+        assert_eq!(40, settings.time_goal.weekly.hours);
+        assert!(!settings.carryover);
+        assert!(!migrated.contains("notify_url"));
+        assert_eq!(None, migrate(&migrated).unwrap());
+    }
+
+    #[test]
+    fn test_parse_holidays() {
+        let contents = "# austria\n2021-01-01 new year\n\n2021-01-06\n";
+        assert_eq!(
+            vec![
+                NaiveDate::from_ymd(2021, 1, 1),
+                NaiveDate::from_ymd(2021, 1, 6)
+            ],
+            parse_holidays(contents).unwrap()
+        );
+        assert!(parse_holidays("01.01.2021").is_err());
     }
 }
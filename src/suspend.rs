@@ -0,0 +1,117 @@
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+
+/// a period in which the machine was suspended
+pub type Period = (DateTime<Utc>, DateTime<Utc>);
+
+/// the periods since `since` in which the machine was suspended or hibernated.
+/// reads the suspend and resume messages of the kernel from the journal. returns no periods
+/// if the journal cannot be read.
+#[cfg(target_os = "linux")]
+pub fn suspended_periods(since: DateTime<Utc>) -> Vec<Period> {
+    let output = std::process::Command::new("journalctl")
+        .arg("_TRANSPORT=kernel")
+        .arg(format!("--since=@{}", since.timestamp()))
+        .arg("--output=short-unix")
+        .arg("--no-pager")
+        .arg("--quiet")
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_journal(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// detecting suspends is only implemented on linux
+#[cfg(not(target_os = "linux"))]
+pub fn suspended_periods(_since: DateTime<Utc>) -> Vec<Period> {
+    Vec::new()
+}
+
+/// pairs the "PM: suspend entry" and "PM: suspend exit" messages, and the same for hibernation,
+/// of `journalctl --output=short-unix`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_journal(output: &str) -> Vec<Period> {
+    let mut periods = Vec::new();
+    let mut entry = None;
+    for line in output.lines() {
+        let time = match line.split_whitespace().next().and_then(parse_unix_time) {
+            Some(time) => time,
+            None => continue,
+        };
+        if line.contains("PM: suspend entry") || line.contains("PM: hibernation entry") {
+            entry = Some(time);
+        } else if line.contains("PM: suspend exit") || line.contains("PM: hibernation exit") {
+            if let Some(entry) = entry.take() {
+                periods.push((entry, time));
+            }
+        }
+    }
+    periods
+}
+
+/// parses seconds since the epoch with an optional fraction, e.g. "1617000000.123456"
+fn parse_unix_time(s: &str) -> Option<DateTime<Utc>> {
+    let seconds = s.split('.').next()?.parse().ok()?;
+    NaiveDateTime::from_timestamp_opt(seconds, 0).map(|time| DateTime::from_utc(time, Utc))
+}
+
+/// how much of the time between `start` and `end` the machine was suspended
+pub fn suspended_duration(
+    periods: &[Period],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Duration {
+    periods
+        .iter()
+        .map(|(entry, exit)| (*entry.max(&start), *exit.min(&end)))
+        .filter(|(entry, exit)| entry < exit)
+        .fold(Duration::zero(), |total, (entry, exit)| {
+            total + (exit - entry)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_journal() {
+        let output = "\
+1617000000.000001 laptop kernel: PM: suspend entry (deep)
+1617000001.500000 laptop kernel: usb 1-1: new device
+1617003600.250000 laptop kernel: PM: suspend exit
+1617010000.000000 laptop kernel: PM: hibernation entry
+1617020000.000000 laptop kernel: PM: hibernation exit
+1617030000.000000 laptop kernel: PM: suspend exit
+1617040000.000000 laptop kernel: PM: suspend entry (s2idle)
+";
+        assert_eq!(
+            vec![
+                (
+                    Utc.timestamp(1_617_000_000, 0),
+                    Utc.timestamp(1_617_003_600, 0)
+                ),
+                (
+                    Utc.timestamp(1_617_010_000, 0),
+                    Utc.timestamp(1_617_020_000, 0)
+                ),
+            ],
+            parse_journal(output)
+        );
+    }
+
+    #[test]
+    fn test_suspended_duration() {
+        let at = |hour| Utc.ymd(2021, 3, 29).and_hms(hour, 0, 0);
+        let periods = vec![(at(6), at(7)), (at(9), at(11)), (at(12), at(13))];
+
+        assert_eq!(
+            Duration::hours(2),
+            suspended_duration(&periods, at(10), at(13))
+        );
+        assert_eq!(Duration::zero(), suspended_duration(&periods, at(7), at(9)));
+    }
+}
@@ -0,0 +1,29 @@
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// a directory for the files of a test. it is removed when the test ends, also when it fails
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// starts empty, even if a previous run of the test left the directory behind
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("tt-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,506 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::settings::Settings;
+use crate::{normalize_description, parse_date_time, work_day, TrackingData, TrackingEvent};
+
+/// a line in the list of the browser
+#[derive(Debug, PartialEq)]
+enum Row {
+    /// the heading of a day with the time worked on it
+    Day { date: NaiveDate, total: Duration },
+    /// a session, by the index of its start and stop event in the data
+    Session { start: usize, stop: Option<usize> },
+}
+
+/// the part of a session which is being edited
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Start,
+    Stop,
+    Description,
+}
+
+/// the index of every start event with the index of the stop event ending it
+fn get_session_indices(data: &[TrackingEvent]) -> Vec<(usize, Option<usize>)> {
+    let mut events = data.iter().enumerate();
+    let mut sessions = Vec::new();
+    while let Some((start, _)) = events.find(|(_, event)| event.is_start()) {
+        let stop = events
+            .find(|(_, event)| event.is_stop())
+            .map(|(stop, _)| stop);
+        sessions.push((start, stop));
+        if stop.is_none() {
+            break;
+        }
+    }
+    sessions
+}
+
+/// the sessions grouped by the day they started on. running sessions count until `now`
+fn get_rows(settings: &Settings, data: &[TrackingEvent], now: DateTime<Utc>) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let mut day: Option<(NaiveDate, usize)> = None;
+    for (start, stop) in get_session_indices(data) {
+        let start_time = data[start].time(true);
+        let date = work_day(settings, start_time);
+        let heading = match day {
+            Some((current, heading)) if current == date => heading,
+            _ => {
+                rows.push(Row::Day {
+                    date,
+                    total: Duration::zero(),
+                });
+                rows.len() - 1
+            }
+        };
+        day = Some((date, heading));
+        if let Row::Day { total, .. } = &mut rows[heading] {
+            let end = stop.map_or(now, |stop| data[stop].time(true));
+            *total = *total + (end - start_time);
+        }
+        rows.push(Row::Session { start, stop });
+    }
+    rows
+}
+
+fn set_time(event: &mut TrackingEvent, new_time: DateTime<Utc>) {
+    match event {
+        TrackingEvent::Start(TrackingData { time, .. })
+        | TrackingEvent::Stop(TrackingData { time, .. }) => *time = new_time,
+    }
+}
+
+/// the sessions of the data grouped by day, with one selected session which can be changed.
+/// changes only apply to the copy of the data held by the browser
+struct Browser<'a> {
+    settings: &'a Settings,
+    data: Vec<TrackingEvent>,
+    rows: Vec<Row>,
+    selected: Option<usize>,
+    changed: bool,
+}
+
+impl<'a> Browser<'a> {
+    /// selects the latest session
+    fn new(settings: &'a Settings, data: Vec<TrackingEvent>, now: DateTime<Utc>) -> Self {
+        let rows = get_rows(settings, &data, now);
+        let selected = rows
+            .iter()
+            .rposition(|row| matches!(row, Row::Session { .. }));
+        Self {
+            settings,
+            data,
+            rows,
+            selected,
+            changed: false,
+        }
+    }
+
+    /// the start and stop index of the selected session
+    fn selected_session(&self) -> Option<(usize, Option<usize>)> {
+        match self.selected.map(|selected| &self.rows[selected]) {
+            Some(Row::Session { start, stop }) => Some((*start, *stop)),
+            _ => None,
+        }
+    }
+
+    /// moves the selection by `offset` sessions, stopping at the first and the last one
+    fn select(&mut self, offset: isize) {
+        let sessions: Vec<_> = (0..self.rows.len())
+            .filter(|row| matches!(self.rows[*row], Row::Session { .. }))
+            .collect();
+        if let Some(current) = sessions.iter().position(|row| Some(*row) == self.selected) {
+            let target = (current as isize + offset).max(0) as usize;
+            self.selected = Some(sessions[target.min(sessions.len() - 1)]);
+        }
+    }
+
+    /// the current text of a field of the selected session, as it is shown in the edit line
+    fn field_text(&self, field: Field) -> String {
+        let (start, stop) = match self.selected_session() {
+            Some(session) => session,
+            None => return String::new(),
+        };
+        let format_time = |index: usize| {
+            self.data[index]
+                .time(true)
+                .with_timezone(&self.settings.zone)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        match field {
+            Field::Start => format_time(start),
+            Field::Stop => stop.map(format_time).unwrap_or_default(),
+            Field::Description => self.data[start].description().unwrap_or_default(),
+        }
+    }
+
+    /// changes a field of the selected session. times have to stay between the events around them
+    fn edit(&mut self, field: Field, input: &str, now: DateTime<Utc>) -> Result<()> {
+        let (start, stop) = match self.selected_session() {
+            Some(session) => session,
+            None => anyhow::bail!("no session is selected"),
+        };
+        let index = match field {
+            Field::Start => start,
+            Field::Stop => match stop {
+                Some(stop) => stop,
+                None => anyhow::bail!("the session is still running"),
+            },
+            Field::Description => {
+                let description = normalize_description(Some(input.to_string()), false);
+                if let TrackingEvent::Start(data) = &mut self.data[start] {
+                    data.description = description;
+                }
+                self.refresh(start, now);
+                return Ok(());
+            }
+        };
+        let time = parse_date_time(input.trim(), self.settings.zone)?;
+        if let Some(previous) = index.checked_sub(1).map(|index| &self.data[index]) {
+            if time <= previous.time(true) {
+                anyhow::bail!("the entry would move before the entry before it");
+            }
+        }
+        if let Some(next) = self.data.get(index + 1) {
+            if time >= next.time(true) {
+                anyhow::bail!("the entry would move after the entry after it");
+            }
+        }
+        set_time(&mut self.data[index], time);
+        self.refresh(start, now);
+        Ok(())
+    }
+
+    /// removes the selected session and selects the one after it
+    fn delete(&mut self, now: DateTime<Utc>) {
+        if let Some((start, stop)) = self.selected_session() {
+            if let Some(stop) = stop {
+                self.data.remove(stop);
+            }
+            self.data.remove(start);
+            self.refresh(start, now);
+        }
+    }
+
+    /// regroups the sessions after a change and keeps the session starting at `start` selected
+    fn refresh(&mut self, start: usize, now: DateTime<Utc>) {
+        self.changed = true;
+        self.rows = get_rows(self.settings, &self.data, now);
+        let sessions = || {
+            self.rows
+                .iter()
+                .enumerate()
+                .filter_map(|(row, session)| match session {
+                    Row::Session { start, .. } => Some((row, *start)),
+                    Row::Day { .. } => None,
+                })
+        };
+        self.selected = sessions()
+            .find(|(_, session)| *session >= start)
+            .or_else(|| sessions().next_back())
+            .map(|(row, _)| row);
+    }
+}
+
+#[cfg(feature = "tui")]
+mod terminal {
+    use super::{Browser, Field, Row};
+    use crate::settings::Settings;
+    use crate::{format_duration, now, TrackingEvent};
+
+    use anyhow::Result;
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+    use crossterm::execute;
+    use crossterm::terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    };
+    use ratatui::backend::{Backend, CrosstermBackend};
+    use ratatui::layout::{Constraint, Direction, Layout};
+    use ratatui::style::{Modifier, Style};
+    use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+    use ratatui::{Frame, Terminal};
+
+    const HELP: &str =
+        "↑/↓ select  s start  e stop  d description  x delete  w write and quit  q quit";
+
+    enum Mode {
+        Browse,
+        Edit(Field, String),
+        Quit,
+    }
+
+    /// browses the sessions in the terminal. returns the changed data if the user chose to
+    /// write the changes
+    pub fn run(
+        settings: &Settings,
+        data: Vec<TrackingEvent>,
+    ) -> Result<Option<Vec<TrackingEvent>>> {
+        enable_raw_mode()?;
+        let mut stdout = std::io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+        let result = browse(&mut terminal, Browser::new(settings, data, now()));
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+        result
+    }
+
+    fn browse<B: Backend>(
+        terminal: &mut Terminal<B>,
+        mut browser: Browser,
+    ) -> Result<Option<Vec<TrackingEvent>>> {
+        let mut mode = Mode::Browse;
+        let mut message = String::new();
+        let mut state = ListState::default();
+        loop {
+            state.select(browser.selected);
+            terminal.draw(|frame| draw(frame, &browser, &mut state, &mode, &message))?;
+            let key = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key,
+                _ => continue,
+            };
+            message.clear();
+            let now = now();
+            mode = match (mode, key.code) {
+                (Mode::Browse, KeyCode::Up) | (Mode::Browse, KeyCode::Char('k')) => {
+                    browser.select(-1);
+                    Mode::Browse
+                }
+                (Mode::Browse, KeyCode::Down) | (Mode::Browse, KeyCode::Char('j')) => {
+                    browser.select(1);
+                    Mode::Browse
+                }
+                (Mode::Browse, KeyCode::PageUp) => {
+                    browser.select(-10);
+                    Mode::Browse
+                }
+                (Mode::Browse, KeyCode::PageDown) => {
+                    browser.select(10);
+                    Mode::Browse
+                }
+                (Mode::Browse, KeyCode::Char('s')) => {
+                    Mode::Edit(Field::Start, browser.field_text(Field::Start))
+                }
+                (Mode::Browse, KeyCode::Char('e')) => match browser.selected_session() {
+                    Some((_, Some(_))) => Mode::Edit(Field::Stop, browser.field_text(Field::Stop)),
+                    _ => {
+                        message = "the session is still running".to_string();
+                        Mode::Browse
+                    }
+                },
+                (Mode::Browse, KeyCode::Char('d')) => {
+                    Mode::Edit(Field::Description, browser.field_text(Field::Description))
+                }
+                (Mode::Browse, KeyCode::Char('x')) | (Mode::Browse, KeyCode::Delete) => {
+                    browser.delete(now);
+                    Mode::Browse
+                }
+                (Mode::Browse, KeyCode::Char('w')) => {
+                    let changed = browser.changed;
+                    return Ok(Some(browser.data).filter(|_| changed));
+                }
+                (Mode::Browse, KeyCode::Char('q')) | (Mode::Browse, KeyCode::Esc) => {
+                    if !browser.changed {
+                        return Ok(None);
+                    }
+                    Mode::Quit
+                }
+                (Mode::Edit(field, mut input), code) => match code {
+                    KeyCode::Enter => match browser.edit(field, &input, now) {
+                        Ok(()) => Mode::Browse,
+                        Err(e) => {
+                            message = e.to_string();
+                            Mode::Edit(field, input)
+                        }
+                    },
+                    KeyCode::Esc => Mode::Browse,
+                    KeyCode::Backspace => {
+                        input.pop();
+                        Mode::Edit(field, input)
+                    }
+                    KeyCode::Char(c) => {
+                        input.push(c);
+                        Mode::Edit(field, input)
+                    }
+                    _ => Mode::Edit(field, input),
+                },
+                (Mode::Quit, KeyCode::Char('y')) => return Ok(Some(browser.data)),
+                (Mode::Quit, KeyCode::Char('n')) => return Ok(None),
+                (Mode::Quit, KeyCode::Esc) => Mode::Browse,
+                (mode, _) => mode,
+            };
+        }
+    }
+
+    fn draw(
+        frame: &mut Frame,
+        browser: &Browser,
+        state: &mut ListState,
+        mode: &Mode,
+        message: &str,
+    ) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(1)])
+            .split(frame.size());
+
+        let now = now();
+        let format_time = |index: usize| {
+            browser.data[index]
+                .time(true)
+                .with_timezone(&browser.settings.zone)
+                .format("%H:%M:%S")
+                .to_string()
+        };
+        let items: Vec<_> = browser
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Day { date, total } => ListItem::new(format!(
+                    "{}  {}",
+                    date.format("%a %Y-%m-%d"),
+                    format_duration(*total)
+                ))
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+                Row::Session { start, stop } => {
+                    let end = stop.map_or(now, |stop| browser.data[stop].time(true));
+                    ListItem::new(format!(
+                        "  {} - {}  {}  {}",
+                        format_time(*start),
+                        stop.map_or_else(|| "running ".to_string(), format_time),
+                        format_duration(end - browser.data[*start].time(true)),
+                        browser.data[*start].description().unwrap_or_default()
+                    ))
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" tt "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, chunks[0], state);
+
+        let line = match mode {
+            Mode::Edit(field, input) => {
+                let name = match field {
+                    Field::Start => "start",
+                    Field::Stop => "stop",
+                    Field::Description => "description",
+                };
+                format!("{}: {}  {}", name, input, message)
+            }
+            Mode::Quit => "write the changes? (y)es, (n)o, (esc) back".to_string(),
+            Mode::Browse if message.is_empty() => HELP.to_string(),
+            Mode::Browse => message.to_string(),
+        };
+        frame.render_widget(Paragraph::new(line), chunks[1]);
+    }
+}
+
+#[cfg(feature = "tui")]
+pub use terminal::run;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn event(start: bool, hour: u32, description: Option<&str>) -> TrackingEvent {
+        let data = TrackingData::new(
+            description.map(ToString::to_string),
+            Local
+                .ymd(2021, 3, 29 + hour / 24)
+                .and_hms(hour % 24, 0, 0)
+                .with_timezone(&Utc),
+        );
+        if start {
+            TrackingEvent::Start(data)
+        } else {
+            TrackingEvent::Stop(data)
+        }
+    }
+
+    fn test_data() -> Vec<TrackingEvent> {
+        vec![
+            event(true, 8, Some("a")),
+            event(false, 10, None),
+            event(true, 11, Some("b")),
+            event(false, 12, None),
+            event(true, 33, Some("c")),
+            event(false, 36, None),
+        ]
+    }
+
+    #[test]
+    fn test_get_rows() {
+        let settings = Settings::default();
+        let now = Utc::now();
+        assert_eq!(
+            vec![
+                Row::Day {
+                    date: NaiveDate::from_ymd(2021, 3, 29),
+                    total: Duration::hours(3)
+                },
+                Row::Session {
+                    start: 0,
+                    stop: Some(1)
+                },
+                Row::Session {
+                    start: 2,
+                    stop: Some(3)
+                },
+                Row::Day {
+                    date: NaiveDate::from_ymd(2021, 3, 30),
+                    total: Duration::hours(3)
+                },
+                Row::Session {
+                    start: 4,
+                    stop: Some(5)
+                },
+            ],
+            get_rows(&settings, &test_data(), now)
+        );
+    }
+
+    #[test]
+    fn test_browser_edit() {
+        let settings = Settings::default();
+        let now = Utc::now();
+        let mut browser = Browser::new(&settings, test_data(), now);
+        assert_eq!(Some((4, Some(5))), browser.selected_session());
+
+        browser.select(-1);
+        assert_eq!(Some((2, Some(3))), browser.selected_session());
+        assert_eq!("2021-03-29 12:00:00", browser.field_text(Field::Stop));
+        assert!(!browser.changed);
+
+        assert!(browser
+            .edit(Field::Start, "2021-03-29 09:30:00", now)
+            .is_err());
+        browser
+            .edit(Field::Stop, "2021-03-29 13:30:00", now)
+            .unwrap();
+        browser.edit(Field::Description, "review", now).unwrap();
+        assert!(browser.changed);
+        assert_eq!(
+            Row::Day {
+                date: NaiveDate::from_ymd(2021, 3, 29),
+                total: Duration::minutes(270)
+            },
+            browser.rows[0]
+        );
+        assert_eq!(Some("review".to_string()), browser.data[2].description());
+
+        browser.delete(now);
+        assert_eq!(4, browser.data.len());
+        assert_eq!(Some((2, Some(3))), browser.selected_session());
+        assert_eq!(Some("c".to_string()), browser.data[2].description());
+
+        browser.select(-5);
+        assert_eq!(Some((0, Some(1))), browser.selected_session());
+    }
+}
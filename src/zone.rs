@@ -0,0 +1,114 @@
+use chrono::{FixedOffset, Local, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+
+use std::fmt;
+
+/// the timezone in which times are shown and parsed and entries are grouped by day: the one
+/// given with --tz or else the one of the system
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Zone {
+    #[default]
+    System,
+    Named(Tz),
+}
+
+impl From<Option<Tz>> for Zone {
+    fn from(tz: Option<Tz>) -> Self {
+        tz.map_or(Self::System, Self::Named)
+    }
+}
+
+/// the offset of a zone at some point in time. it is shown like the offset of the system
+/// timezone, e.g. "+01:00"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoneOffset {
+    zone: Zone,
+    fixed: FixedOffset,
+}
+
+impl Offset for ZoneOffset {
+    fn fix(&self) -> FixedOffset {
+        self.fixed
+    }
+}
+
+impl fmt::Display for ZoneOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fixed.fmt(f)
+    }
+}
+
+impl Zone {
+    fn offset<O: Offset>(self, offset: O) -> ZoneOffset {
+        ZoneOffset {
+            zone: self,
+            fixed: offset.fix(),
+        }
+    }
+}
+
+impl TimeZone for Zone {
+    type Offset = ZoneOffset;
+
+    fn from_offset(offset: &ZoneOffset) -> Self {
+        offset.zone
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<ZoneOffset> {
+        match self {
+            Self::System => Local
+                .offset_from_local_date(local)
+                .map(|offset| self.offset(offset)),
+            Self::Named(tz) => tz
+                .offset_from_local_date(local)
+                .map(|offset| self.offset(offset)),
+        }
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<ZoneOffset> {
+        match self {
+            Self::System => Local
+                .offset_from_local_datetime(local)
+                .map(|offset| self.offset(offset)),
+            Self::Named(tz) => tz
+                .offset_from_local_datetime(local)
+                .map(|offset| self.offset(offset)),
+        }
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> ZoneOffset {
+        match self {
+            Self::System => self.offset(Local.offset_from_utc_date(utc)),
+            Self::Named(tz) => self.offset(tz.offset_from_utc_date(utc)),
+        }
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> ZoneOffset {
+        match self {
+            Self::System => self.offset(Local.offset_from_utc_datetime(utc)),
+            Self::Named(tz) => self.offset(tz.offset_from_utc_datetime(utc)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_zone() {
+        let zone = Zone::Named(Tz::Europe__Vienna);
+        let winter = zone.ymd(2021, 3, 27).and_hms(12, 0, 0);
+        let summer = zone.ymd(2021, 3, 29).and_hms(12, 0, 0);
+
+        assert_eq!("2021-03-27T12:00:00+01:00", winter.to_rfc3339());
+        assert_eq!("2021-03-29T12:00:00+02:00", summer.to_rfc3339());
+        assert_eq!(zone, summer.timezone());
+        // the clocks skip 02:00 to 03:00 when they are set forward
+        assert_eq!(
+            LocalResult::None,
+            zone.from_local_datetime(&NaiveDate::from_ymd(2021, 3, 28).and_hms(2, 30, 0))
+                .map(|time| time.to_rfc3339())
+        );
+    }
+}
@@ -0,0 +1,75 @@
+// not every test uses every helper
+#![allow(dead_code)]
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// the time the tests run at unless they set TT_NOW themselves
+pub const NOW: &str = "2021-03-01T12:00:00Z";
+
+/// a directory for the files of a test. it is removed when the test ends, also when it fails
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("tt-test-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+pub fn default_config() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/default_config.toml"))
+}
+
+/// a command which runs tt on the data file at NOW in UTC, unless --tz is given
+pub fn command(data_file: &Path, config_file: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_tt"));
+    command
+        .arg("--data-file")
+        .arg(data_file)
+        .arg("--config-file")
+        .arg(config_file)
+        .env("TT_NOW", NOW)
+        .env("TZ", "UTC");
+    command
+}
+
+/// runs tt with the default config
+pub fn tt(data_file: &Path, args: &[&str]) -> Output {
+    command(data_file, default_config())
+        .args(args)
+        .output()
+        .expect("could not run tt")
+}
+
+/// the stdout of a run which has to succeed
+pub fn stdout(output: Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// the stderr of a run which has to fail
+pub fn stderr(output: Output) -> String {
+    assert!(!output.status.success());
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
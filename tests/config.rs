@@ -0,0 +1,73 @@
+mod common;
+
+use common::{stderr, stdout, TempDir};
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn tt(dir: &Path, config: Option<&Path>, args: &[&str]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_tt"));
+    if let Some(config) = config {
+        command.arg("--config").arg(config);
+    }
+    command
+        .arg("--data-file")
+        .arg(dir.join("timetracking.data"))
+        .args(args)
+        .env("TT_NOW", common::NOW)
+        .env("TZ", "UTC")
+        .env_remove("TT_CONFIG")
+        .output()
+        .expect("could not run tt")
+}
+
+#[test]
+fn test_config_replaces_the_config_files() {
+    let dir = TempDir::new("config");
+    let config = dir.join("config.toml");
+    std::fs::write(&config, "[time_goal.daily]\nhours = 6\nminutes = 30\n").unwrap();
+
+    assert_eq!(
+        "Remaining Work Time: 06:30:00\n",
+        stdout(tt(&dir, Some(&config), &["show", "--remaining"]))
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tt"))
+        .arg("--data-file")
+        .arg(dir.join("timetracking.data"))
+        .args(["show", "--remaining", "--plain"])
+        .env("TT_NOW", common::NOW)
+        .env("TT_CONFIG", &config)
+        .output()
+        .unwrap();
+    assert_eq!("06:30:00\n", stdout(output));
+
+    let error = stderr(tt(&dir, Some(&dir.join("missing.toml")), &["show"]));
+    assert!(error.contains("doesn't exist"));
+
+    std::fs::write(&config, "[time_goal.daily\n").unwrap();
+    let error = stderr(tt(&dir, Some(&config), &["show"]));
+    assert!(error.contains("could not load the config file"));
+}
+
+#[test]
+fn test_local_config_file() {
+    let dir = TempDir::new("config-local");
+    // a version 1 config, which is migrated when it is loaded
+    std::fs::write(
+        dir.join(".timetracking.toml"),
+        "notify_url = \"\"\n[time_goal.daily]\nhours = 5\nminutes = 0\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tt"))
+        .current_dir(&*dir)
+        .arg("--data-file")
+        .arg(dir.join("timetracking.data"))
+        .args(["show", "--remaining", "--plain"])
+        .env("TT_NOW", common::NOW)
+        .env("HOME", &*dir)
+        .env_remove("TT_CONFIG")
+        .output()
+        .unwrap();
+    assert_eq!("05:00:00\n", stdout(output));
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::{stdout, tt, TempDir};
+
+#[test]
+fn test_confirmations() {
+    let dir = TempDir::new("confirmation");
+    let data_file = dir.join("timetracking.data");
+
+    assert_eq!(
+        "Started \"coding\" at 2021-03-01 09:00:00\n",
+        stdout(tt(&data_file, &["start", "coding", "--at", "09:00"]))
+    );
+    assert_eq!(
+        "Stopped \"coding\" at 2021-03-01 10:00:00\n",
+        stdout(tt(&data_file, &["stop", "--at", "10:00"]))
+    );
+    assert_eq!(
+        "Started \"coding\" (inherited) at 2021-03-01 10:30:00\n",
+        stdout(tt(&data_file, &["continue", "--at", "10:30"]))
+    );
+    assert_eq!("", stdout(tt(&data_file, &["--quiet", "stop", "--at", "11:00"])));
+    assert_eq!(
+        "Started \"review\" at 2021-03-01 11:30:00\n",
+        stdout(tt(&data_file, &["start", "review", "--at", "11:30"]))
+    );
+    stdout(tt(&data_file, &["--quiet", "stop", "--at", "11:45"]));
+    assert_eq!(
+        "Started \"email\" at 2021-03-01 11:50:00\n",
+        stdout(tt(&data_file, &["continue", "--new", "email", "--at", "11:50"]))
+    );
+}
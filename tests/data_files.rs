@@ -0,0 +1,46 @@
+mod common;
+
+use common::{command, default_config, stderr, stdout, TempDir};
+use std::path::Path;
+use std::process::Output;
+
+fn tt(data_files: &[&Path], args: &[&str]) -> Output {
+    let mut command = command(data_files[0], default_config());
+    for data_file in &data_files[1..] {
+        command.arg("--data-file").arg(data_file);
+    }
+    command
+        .args(args)
+        .env("TT_NOW", "2021-03-02T12:00:00Z")
+        .output()
+        .expect("could not run tt")
+}
+
+#[test]
+fn test_several_data_files() {
+    let dir = TempDir::new("data-files");
+    let (first, second) = (dir.join("first.data"), dir.join("second.data"));
+
+    // one hour in the first project and two hours in the second one
+    for (data_file, start, stop) in [
+        (&first, "2021-03-01 09:00", "2021-03-01 10:00"),
+        (&second, "2021-03-01 10:00", "2021-03-01 12:00"),
+    ] {
+        stdout(tt(&[data_file], &["start", "coding", "--at", start]));
+        stdout(tt(&[data_file], &["stop", "--at", stop]));
+    }
+
+    assert_eq!(
+        "Work Time: 03:00:00\n",
+        stdout(tt(&[&first, &second], &["show", "all"]))
+    );
+    let glob = dir.join("*.data");
+    assert_eq!(
+        "Work Time: 03:00:00\n",
+        stdout(tt(&[&glob], &["show", "all"]))
+    );
+
+    let error = stderr(tt(&[&first, &second], &["start", "coding"]));
+    assert!(error.contains("use a single --data-file"));
+    assert!(!tt(&[&dir.join("*.missing")], &["show"]).status.success());
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::{stdout, tt, TempDir};
+use std::path::Path;
+
+#[test]
+fn test_diff() {
+    let dir = TempDir::new("diff");
+    let (laptop, desktop) = (dir.join("laptop.data"), dir.join("desktop.data"));
+
+    // the same session in both files and one more start in each
+    for data_file in [&laptop, &desktop] {
+        stdout(tt(data_file, &["-q", "start", "coding", "--at", "09:00"]));
+        stdout(tt(data_file, &["-q", "stop", "--at", "10:00"]));
+    }
+    stdout(tt(&laptop, &["-q", "start", "review", "--at", "11:00"]));
+    stdout(tt(&desktop, &["-q", "start", "email", "--at", "10:30"]));
+
+    let diff = |data_file, other: &Path| stdout(tt(data_file, &["diff", other.to_str().unwrap()]));
+    assert_eq!(
+        "+Start at 2021-03-01 10:30:00 \"email\"\n\
+         -Start at 2021-03-01 11:00:00 \"review\"\n",
+        diff(&laptop, &desktop)
+    );
+    assert_eq!(
+        "-Start at 2021-03-01 10:30:00 \"email\"\n\
+         +Start at 2021-03-01 11:00:00 \"review\"\n",
+        diff(&desktop, &laptop)
+    );
+    assert_eq!("", diff(&laptop, &laptop));
+}
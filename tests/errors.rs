@@ -0,0 +1,53 @@
+mod common;
+
+use common::{stderr, tt, TempDir};
+
+#[test]
+fn test_errors_name_the_file() {
+    let dir = TempDir::new("errors");
+
+    // a directory can't be read as the data file
+    let error = stderr(tt(&dir, &["show"]));
+    assert!(
+        error.contains(&format!("could not read the data file {}: ", dir.display())),
+        "{}",
+        error
+    );
+
+    let missing = dir.join("missing").join("timetracking.data");
+    let error = stderr(tt(&missing, &["start", "coding"]));
+    assert!(
+        error.contains(&format!("could not write the data file {}: ", missing.display())),
+        "{}",
+        error
+    );
+
+    let export = missing.with_file_name("export.json");
+    let error = stderr(tt(&dir.join("data"), &["export", &export.to_string_lossy()]));
+    assert!(
+        error.contains(&format!("could not export to {}: ", export.display())),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_edit_rejects_a_description_filter() {
+    let dir = TempDir::new("errors-edit");
+    let data_file = dir.join("timetracking.data");
+
+    let error = stderr(tt(&data_file, &["edit", "--shift", "+1h", "coding"]));
+    assert!(error.contains("can't filter by description"), "{}", error);
+    assert!(!data_file.exists());
+}
+
+#[test]
+fn test_goal_placeholders_need_a_goal() {
+    let dir = TempDir::new("errors-goal");
+    let data_file = dir.join("timetracking.data");
+
+    let output = tt(&data_file, &["show", "--format", "{pct}%", "coding"]);
+    assert_eq!("", String::from_utf8_lossy(&output.stdout));
+    let error = stderr(output);
+    assert!(error.contains("{pct} and {goal} only work"), "{}", error);
+}
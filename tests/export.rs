@@ -0,0 +1,96 @@
+mod common;
+
+use common::{command, default_config, stdout, tt, TempDir};
+use std::path::Path;
+
+fn export(dir: &Path, config_file: &Path, format: &str) -> String {
+    let path = dir.join(format!("export.{}", format));
+    stdout(
+        command(&dir.join("timetracking.data"), config_file)
+            .args(["export", &path.to_string_lossy(), "--format", format])
+            .output()
+            .expect("could not run tt"),
+    );
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn test_export_is_byte_for_byte_stable() {
+    let dir = TempDir::new("export");
+    let data_file = dir.join("timetracking.data");
+
+    let fixture: [&[&str]; 4] = [
+        &["start", "coding, tests", "--at", "2021-03-01 09:00"],
+        &["stop", "--at", "2021-03-01 10:20"],
+        &["start", "meeting", "--at", "2021-03-01 11:00"],
+        &["stop", "--at", "2021-03-01 11:45"],
+    ];
+    for args in fixture {
+        stdout(tt(&data_file, args));
+    }
+
+    assert_eq!(
+        concat!(
+            r#"[{"Start":{"description":"coding, tests","time":1614589200}},"#,
+            r#"{"Stop":{"description":null,"time":1614594000}},"#,
+            r#"{"Start":{"description":"meeting","time":1614596400}},"#,
+            r#"{"Stop":{"description":null,"time":1614599100}}]"#
+        ),
+        export(&dir, default_config(), "json")
+    );
+    let csv = "start,stop,hours,description\n\
+               2021-03-01 09:00:00,2021-03-01 10:20:00,1.33,\"coding, tests\"\n\
+               2021-03-01 11:00:00,2021-03-01 11:45:00,0.75,meeting\n";
+    assert_eq!(csv, export(&dir, default_config(), "csv"));
+    assert_eq!(
+        "CLOCK: [2021-03-01 Mon 09:00]--[2021-03-01 Mon 10:20] =>  1:20\n\
+         CLOCK: [2021-03-01 Mon 11:00]--[2021-03-01 Mon 11:45] =>  0:45\n",
+        export(&dir, default_config(), "org")
+    );
+    let readable = "Start at 2021-03-01 09:00:00 \"coding, tests\"\n\
+                    Stop  at 2021-03-01 10:20:00\n\
+                    Start at 2021-03-01 11:00:00 \"meeting\"\n\
+                    Stop  at 2021-03-01 11:45:00\n";
+    assert_eq!(readable, export(&dir, default_config(), "readable"));
+    assert_eq!(
+        "| description | seconds | hours | percent |\n\
+         | --- | ---: | ---: | ---: |\n\
+         | coding, tests | 4800 | 1.33 | 64.00 |\n\
+         | meeting | 2700 | 0.75 | 36.00 |\n",
+        stdout(tt(&data_file, &["summary", "all", "--format", "markdown"]))
+    );
+
+    let crlf_config = dir.join("crlf.toml");
+    std::fs::write(&crlf_config, "export_line_ending = \"crlf\"\n").unwrap();
+    assert_eq!(csv.replace('\n', "\r\n"), export(&dir, &crlf_config, "csv"));
+    assert_eq!(
+        readable.replace('\n', "\r\n"),
+        export(&dir, &crlf_config, "readable")
+    );
+}
+
+#[test]
+fn test_export_filters_by_project() {
+    let dir = TempDir::new("export-project");
+    let data_file = dir.join("timetracking.data");
+
+    let fixture: [&[&str]; 4] = [
+        &["start", "coding", "--project", "acme", "--at", "09:00"],
+        &["stop", "--at", "10:00"],
+        &["start", "email", "--at", "10:00"],
+        &["stop", "--at", "11:00"],
+    ];
+    for args in fixture {
+        stdout(tt(&data_file, args));
+    }
+
+    let path = dir.join("export.csv");
+    stdout(tt(
+        &data_file,
+        &["export", &path.to_string_lossy(), "--format", "csv", "--project", "acme"],
+    ));
+    assert_eq!(
+        "start,stop,hours,description\n2021-03-01 09:00:00,2021-03-01 10:00:00,1.00,coding\n",
+        std::fs::read_to_string(path).unwrap()
+    );
+}
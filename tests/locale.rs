@@ -0,0 +1,41 @@
+mod common;
+
+use common::{command, default_config, TempDir};
+use std::path::Path;
+
+fn tt(data_file: &Path, locale: &str, args: &[&str]) -> String {
+    let output = command(data_file, default_config())
+        .args(args)
+        .env("TT_NOW", "2021-03-01T18:00:00Z")
+        .env_remove("LC_ALL")
+        .env_remove("LC_TIME")
+        .env("LANG", locale)
+        .output()
+        .expect("could not run tt");
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_locale_time() {
+    let dir = TempDir::new("locale");
+    let data_file = dir.join("timetracking.data");
+    tt(&data_file, "C", &["-q", "start", "coding", "--at", "14:30"]);
+
+    let list = |locale| tt(&data_file, locale, &["--locale-time", "--no-pager", "list"]);
+    assert_eq!(
+        "Start at 2021-03-01 02:30:00 PM \"coding\"\n",
+        list("en_US.UTF-8")
+    );
+    assert_eq!("Start at 2021-03-01 14:30:00 \"coding\"\n", list("de_DE.UTF-8"));
+    assert_eq!("Start at 2021-03-01 14:30:00 \"coding\"\n", list(""));
+
+    let status = |locale| tt(&data_file, locale, &["--locale-time", "status"]);
+    assert!(status("en_US.UTF-8").contains("Start Time: 02:30:00 PM\n"));
+    assert!(status("de_DE.UTF-8").contains("Start Time: 14:30:00\n"));
+
+    // without --locale-time, list_time_format is used
+    assert_eq!(
+        "Start at 2021-03-01 14:30:00 \"coding\"\n",
+        tt(&data_file, "en_US.UTF-8", &["--no-pager", "list"])
+    );
+}
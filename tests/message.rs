@@ -0,0 +1,50 @@
+mod common;
+
+use common::{command, default_config, stderr, stdout, TempDir};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Output, Stdio};
+
+fn tt(data_file: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = command(data_file, default_config())
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("could not run tt");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn last_description(data_file: &Path) -> String {
+    let stdout = stdout(tt(data_file, &["list", "--json", "all"], ""));
+    let start = stdout.rfind("\"description\":").expect("no description");
+    stdout[start..].split('"').nth(3).unwrap().to_string()
+}
+
+#[test]
+fn test_message_forms_record_the_same_description() {
+    let dir = TempDir::new("message");
+
+    let forms: &[(&[&str], &str)] = &[
+        (&["start", "fix bug"], ""),
+        (&["start", "-m", "fix bug"], ""),
+        (&["start", "--message", "-"], "fix bug\n"),
+        (&["start", "fix bug", "-m", "fix bug"], ""),
+    ];
+    for (index, (args, stdin)) in forms.iter().enumerate() {
+        let data_file = dir.join(format!("timetracking-{}.data", index));
+        stdout(tt(&data_file, args, stdin));
+        assert_eq!("fix bug", last_description(&data_file), "{:?}", args);
+    }
+
+    let data_file = dir.join("timetracking-conflict.data");
+    let error = stderr(tt(&data_file, &["start", "fix bug", "-m", "reviews"], ""));
+    assert!(error.contains("differ"));
+}
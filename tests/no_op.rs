@@ -0,0 +1,70 @@
+mod common;
+
+use common::{command, TempDir};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// runs tt and returns its stderr
+fn tt(data_file: &Path, config_file: &Path, args: &[&str]) -> String {
+    let output = command(data_file, config_file)
+        .args(args)
+        .output()
+        .expect("could not run tt");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+/// sets the modification time of the file to a fixed time in the past and returns it
+fn touch(path: &Path) -> SystemTime {
+    let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let file = std::fs::OpenOptions::new().write(true).open(path).unwrap();
+    file.set_modified(time).unwrap();
+    time
+}
+
+fn modified(path: &Path) -> SystemTime {
+    std::fs::metadata(path).unwrap().modified().unwrap()
+}
+
+#[test]
+fn test_no_op_commands_do_not_write() {
+    let dir = TempDir::new("no-op");
+    let data_file = dir.join("timetracking.data");
+    let config_file = dir.join("config.toml");
+    std::fs::write(&config_file, "auto_insert_stop = true\n").unwrap();
+
+    tt(&data_file, &config_file, &["start", "coding", "--at", "09:00"]);
+    let time = touch(&data_file);
+    tt(&data_file, &config_file, &["start", "coding"]);
+    assert_eq!(time, modified(&data_file));
+    tt(&data_file, &config_file, &["start", "coding", "--resume-if-same"]);
+    assert_eq!(time, modified(&data_file));
+
+    tt(&data_file, &config_file, &["stop", "--at", "10:00"]);
+    assert_ne!(time, modified(&data_file));
+    let time = touch(&data_file);
+    tt(&data_file, &config_file, &["stop"]);
+    assert_eq!(time, modified(&data_file));
+}
+
+#[test]
+fn test_stop_without_session_does_not_warn() {
+    let dir = TempDir::new("no-op-warn");
+    let data_file = dir.join("timetracking.data");
+    let config_file = dir.join("config.toml");
+    std::fs::write(&config_file, "[session_limits]\nmeeting = 60\n").unwrap();
+
+    tt(&data_file, &config_file, &["start", "meeting", "--at", "09:00"]);
+    let warning = tt(&data_file, &config_file, &["stop", "--at", "11:00"]);
+    assert!(warning.contains("over the 1h soft limit"));
+    let time = touch(&data_file);
+    assert_eq!(
+        "Time tracking is already stopped!\n",
+        tt(&data_file, &config_file, &["stop"])
+    );
+    assert_eq!(time, modified(&data_file));
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::{command, default_config, stderr, stdout, TempDir};
+use std::path::Path;
+use std::process::Output;
+
+fn tt(data_file: &Path, now: &str, args: &[&str]) -> Output {
+    command(data_file, default_config())
+        .args(args)
+        .env("TT_NOW", now)
+        .output()
+        .expect("could not run tt")
+}
+
+fn show_week(data_file: &Path, now: &str) -> String {
+    stdout(tt(data_file, now, &["show", "week"]))
+}
+
+#[test]
+fn test_now_sets_week_boundaries() {
+    let dir = TempDir::new("now");
+    let data_file = dir.join("timetracking.data");
+
+    // one hour on sunday 2021-04-04 and two hours on monday 2021-04-05
+    for (start, stop) in [
+        ("2021-04-04T10:00:00Z", "2021-04-04T11:00:00Z"),
+        ("2021-04-05T10:00:00Z", "2021-04-05T12:00:00Z"),
+    ] {
+        let now = stop;
+        assert!(tt(&data_file, now, &["start", "coding", "--at", start])
+            .status
+            .success());
+        assert!(tt(&data_file, now, &["stop", "--at", stop]).status.success());
+    }
+
+    assert_eq!("Work Time 2021-W13: 01:00:00\n",
+        show_week(&data_file, "2021-04-04T23:59:59Z"));
+    assert_eq!("Work Time 2021-W14: 02:00:00\n",
+        show_week(&data_file, "2021-04-05T00:00:00Z"));
+    assert_eq!("Work Time 2021-W14: 02:00:00\n",
+        show_week(&data_file, "2021-04-11T23:59:59Z"));
+    assert_eq!("Work Time 2021-W15: 00:00:00\n",
+        show_week(&data_file, "2021-04-12T00:00:00Z"));
+
+    let error = stderr(tt(&data_file, "yesterday", &["show", "week"]));
+    assert!(error.contains("invalid TT_NOW"));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{command, default_config, TempDir};
+use std::path::Path;
+use std::process::Output;
+
+/// runs tt at the current time, so a session can be stopped after it started
+fn tt(data_file: &Path, args: &[&str]) -> Output {
+    command(data_file, default_config())
+        .args(args)
+        .env_remove("TT_NOW")
+        .env_remove("NO_COLOR")
+        .output()
+        .expect("could not run tt")
+}
+
+#[test]
+fn test_status_has_no_color_when_piped() {
+    let dir = TempDir::new("status");
+    let data_file = dir.join("timetracking.bin");
+
+    assert!(tt(&data_file, &["start", "coding"]).status.success());
+    let output = tt(&data_file, &["status"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.starts_with("Active: true\nDescription: coding\nStart Time: "));
+    assert!(!stdout.contains('\x1b'));
+}
+
+#[test]
+fn test_status_exit_codes() {
+    let dir = TempDir::new("exit");
+    let data_file = dir.join("timetracking.bin");
+
+    assert_eq!(Some(3), tt(&data_file, &["status"]).status.code());
+    assert!(tt(&data_file, &["start", "coding"]).status.success());
+    assert_eq!(Some(0), tt(&data_file, &["status"]).status.code());
+    assert!(tt(&data_file, &["stop"]).status.success());
+    assert_eq!(Some(3), tt(&data_file, &["status"]).status.code());
+    assert_eq!(Some(0), tt(&data_file, &["status", "--short"]).status.code());
+
+    let output = tt(&data_file, &["show", "--from", "yesterday"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(Some(1), output.status.code());
+    assert!(stderr.starts_with("Error: "), "{}", stderr);
+    assert!(!stderr.contains("Caused by"), "{}", stderr);
+}
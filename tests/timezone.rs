@@ -0,0 +1,71 @@
+mod common;
+
+use common::{stderr, stdout, tt, TempDir};
+use std::path::Path;
+
+fn heatmap(data_file: &Path, tz: &str) -> String {
+    let out = data_file.with_extension(format!("{}.csv", tz.replace('/', "-")));
+    stdout(tt(
+        data_file,
+        &[
+            "--tz",
+            tz,
+            "export",
+            out.to_str().unwrap(),
+            "--format",
+            "heatmap",
+            "all",
+        ],
+    ));
+    std::fs::read_to_string(out).unwrap()
+}
+
+#[test]
+fn test_tz_changes_day_buckets() {
+    let dir = TempDir::new("tz");
+    let data_file = dir.join("timetracking.data");
+
+    stdout(tt(
+        &data_file,
+        &["start", "coding", "--at", "2021-03-29T23:30:00Z"],
+    ));
+    stdout(tt(&data_file, &["stop", "--at", "2021-03-30T00:30:00Z"]));
+
+    assert_eq!(
+        "date,minutes\n2021-03-29,30\n2021-03-30,30\n",
+        heatmap(&data_file, "UTC")
+    );
+    assert_eq!(
+        "date,minutes\n2021-03-30,60\n",
+        heatmap(&data_file, "Asia/Tokyo")
+    );
+
+    let error = stderr(tt(&data_file, &["--tz", "Europe/Nowhere", "path"]));
+    assert!(error.contains("unknown timezone"));
+}
+
+#[test]
+fn test_buckets_across_clock_changes() {
+    let dir = TempDir::new("tz-dst");
+    let data_file = dir.join("timetracking.data");
+
+    // 02:00 to 03:00 happens twice in Vienna when the clocks are set back
+    stdout(tt(&data_file, &["start", "coding", "--at", "2021-10-30T23:30:00Z"]));
+    stdout(tt(&data_file, &["stop", "--at", "2021-10-31T01:30:00Z"]));
+    assert_eq!(
+        "Busiest Hour: 02:00 - 03:00 (01:30:00)\n",
+        stdout(tt(
+            &data_file,
+            &["--tz", "Europe/Vienna", "show", "all", "--busiest", "hour"]
+        ))
+    );
+
+    // midnight didn't exist in Sao Paulo when the clocks were set forward in 2018
+    let data_file = dir.join("sao-paulo.data");
+    stdout(tt(&data_file, &["start", "coding", "--at", "2018-11-04T01:00:00Z"]));
+    stdout(tt(&data_file, &["stop", "--at", "2018-11-04T04:00:00Z"]));
+    assert_eq!(
+        "date,minutes\n2018-11-03,120\n2018-11-04,60\n",
+        heatmap(&data_file, "America/Sao_Paulo")
+    );
+}